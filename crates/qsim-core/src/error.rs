@@ -19,6 +19,9 @@ pub enum CoreError {
 
     #[error("Simulation error: {0}")]
     SimulationError(String),
+
+    #[error("Not observable: {0}")]
+    NotObservable(String),
 }
 
 pub type Result<T> = std::result::Result<T, CoreError>;
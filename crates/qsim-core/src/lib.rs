@@ -8,13 +8,16 @@
 //! - [`Solver`] — Trait for power flow solvers
 //! - [`StateStore`] — Central state storage
 //! - [`Topology`] — Graph-based network topology
+//! - [`TimeSeriesRunner`] — Drives a [`TimeStepper`] and its [`OutputHandler`]s
 
 mod error;
+mod simulation;
 mod state;
 mod topology;
 mod traits;
 
 pub use error::*;
+pub use simulation::*;
 pub use state::*;
 pub use topology::*;
 pub use traits::*;
@@ -0,0 +1,79 @@
+//! Time-series simulation driver
+
+use crate::{CoreError, OutputHandler, Result, StateStore, TimeStepper};
+
+/// Drives a [`TimeStepper`] over a fixed number of steps
+///
+/// Every handler sees `on_step` after each step and `on_complete` once at
+/// the end. The step is labelled with the stepper's own clock when it
+/// keeps one ([`TimeStepper::simulation_time`]), i.e. the time the state
+/// has reached, and otherwise with the time at which the step applies,
+/// `start_time + step·time_step`.
+#[derive(Debug, Clone)]
+pub struct TimeSeriesRunner {
+    /// Time of the first step
+    pub start_time: f64,
+    /// Step length (in the stepper's time unit)
+    pub time_step: f64,
+    /// End step (exclusive): steps `first_step..steps` are run
+    pub steps: usize,
+    /// First step to run (non-zero when resuming)
+    pub first_step: usize,
+}
+
+impl TimeSeriesRunner {
+    /// Create a runner of steps `0..steps` starting at time zero
+    pub fn new(time_step: f64, steps: usize) -> Self {
+        Self {
+            start_time: 0.0,
+            time_step,
+            steps,
+            first_step: 0,
+        }
+    }
+
+    /// Builder: set the start time
+    pub fn with_start_time(mut self, start_time: f64) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    /// Builder: resume at `step`, skipping the steps before it
+    ///
+    /// `steps` stays the end step, so `steps - step` steps remain.
+    pub fn with_first_step(mut self, step: usize) -> Self {
+        self.first_step = step;
+        self
+    }
+
+    /// Run all steps, starting from and updating `state`
+    ///
+    /// Stops at the first error returned by the stepper; handlers are not
+    /// completed in that case. Handlers that use a fixed copy of the network
+    /// are rejected before the first step if the stepper changes the network.
+    pub fn run(
+        &self,
+        stepper: &mut dyn TimeStepper,
+        state: &mut StateStore,
+        outputs: &mut [&mut dyn OutputHandler],
+    ) -> Result<()> {
+        if stepper.changes_network() && outputs.iter().any(|o| o.uses_fixed_network()) {
+            return Err(CoreError::SimulationError(
+                "Output uses a fixed network but the stepper changes the network".into(),
+            ));
+        }
+        for step in self.first_step..self.steps {
+            stepper.step(state, self.time_step)?;
+            let time = stepper
+                .simulation_time()
+                .unwrap_or(self.start_time + step as f64 * self.time_step);
+            for output in outputs.iter_mut() {
+                output.on_step(step, time, state);
+            }
+        }
+        for output in outputs.iter_mut() {
+            output.on_complete(state);
+        }
+        Ok(())
+    }
+}
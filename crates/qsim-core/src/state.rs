@@ -1,10 +1,12 @@
 //! Central state storage for simulation
 
+use serde::{Deserialize, Serialize};
+
 /// Central storage for simulation state.
 ///
 /// Uses contiguous `Vec<f64>` arrays for cache-friendly access
 /// and efficient parallel computation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StateStore {
     /// Voltage magnitudes (per-unit)
     pub voltage_magnitude: Vec<f64>,
@@ -14,8 +16,24 @@ pub struct StateStore {
     pub active_power: Vec<f64>,
     /// Reactive power injection (MVAr)
     pub reactive_power: Vec<f64>,
+    /// Per-phase voltage magnitudes [a, b, c] (per-unit, empty unless phase-resolved)
+    #[serde(default)]
+    pub phase_voltage_magnitude: Vec<[f64; 3]>,
+    /// Per-phase voltage angles [a, b, c] (radians, empty unless phase-resolved)
+    #[serde(default)]
+    pub phase_voltage_angle: Vec<[f64; 3]>,
+    /// State variables of dynamic models (empty unless a dynamic simulation runs)
+    #[serde(default)]
+    pub dynamic_states: Vec<f64>,
 }
 
+/// Nominal phase angles of a balanced a-b-c set (radians)
+pub const NOMINAL_PHASE_ANGLES: [f64; 3] = [
+    0.0,
+    -2.0 * std::f64::consts::FRAC_PI_3,
+    2.0 * std::f64::consts::FRAC_PI_3,
+];
+
 impl StateStore {
     /// Create a new state store with given capacity
     pub fn new(bus_count: usize) -> Self {
@@ -24,9 +42,25 @@ impl StateStore {
             voltage_angle: vec![0.0; bus_count],
             active_power: vec![0.0; bus_count],
             reactive_power: vec![0.0; bus_count],
+            phase_voltage_magnitude: Vec::new(),
+            phase_voltage_angle: Vec::new(),
+            dynamic_states: Vec::new(),
         }
     }
 
+    /// Create a state store with per-phase voltages at a balanced flat start
+    pub fn with_phases(bus_count: usize) -> Self {
+        let mut state = Self::new(bus_count);
+        state.phase_voltage_magnitude = vec![[1.0; 3]; bus_count];
+        state.phase_voltage_angle = vec![NOMINAL_PHASE_ANGLES; bus_count];
+        state
+    }
+
+    /// Whether per-phase voltages are tracked
+    pub fn has_phases(&self) -> bool {
+        !self.phase_voltage_magnitude.is_empty()
+    }
+
     /// Number of buses in the state
     pub fn bus_count(&self) -> usize {
         self.voltage_magnitude.len()
@@ -38,6 +72,9 @@ impl StateStore {
         self.voltage_angle.fill(0.0);
         self.active_power.fill(0.0);
         self.reactive_power.fill(0.0);
+        self.phase_voltage_magnitude.fill([1.0; 3]);
+        self.phase_voltage_angle.fill(NOMINAL_PHASE_ANGLES);
+        self.dynamic_states.clear();
     }
 }
 
@@ -1,6 +1,12 @@
 //! Graph-based topology representation
 
+use std::collections::{HashMap, HashSet, VecDeque};
+
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::{Bfs, EdgeRef};
+
+use crate::{CoreError, Result};
 
 /// Unique identifier for a bus
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -10,6 +16,14 @@ pub struct BusId(pub usize);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct BranchId(pub usize);
 
+/// A bus in a radial traversal, with the branch leading back to its parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadialNode {
+    pub bus_id: BusId,
+    /// Parent bus and connecting branch (`None` for the root)
+    pub parent: Option<(BusId, BranchId)>,
+}
+
 /// Node data in the topology graph
 #[derive(Debug, Clone)]
 pub struct TopologyNode {
@@ -63,6 +77,294 @@ impl Topology {
         self.graph.edge_count() / 2
     }
 
+    /// Connected groups of buses (electrical islands)
+    ///
+    /// Buses within an island are sorted, and islands are ordered by their
+    /// lowest bus.
+    pub fn islands(&self) -> Vec<Vec<BusId>> {
+        let mut visited = vec![false; self.graph.node_count()];
+        let mut islands = Vec::new();
+
+        for start in self.graph.node_indices() {
+            if visited[start.index()] {
+                continue;
+            }
+            let mut island = Vec::new();
+            let mut bfs = Bfs::new(&self.graph, start);
+            while let Some(node) = bfs.next(&self.graph) {
+                visited[node.index()] = true;
+                island.push(self.graph[node].bus_id);
+            }
+            island.sort_by_key(|b| b.0);
+            islands.push(island);
+        }
+
+        islands
+    }
+
+    /// Whether all buses belong to a single island
+    pub fn is_connected(&self) -> bool {
+        self.islands().len() <= 1
+    }
+
+    /// Observable islands for a set of measurements (topological method)
+    ///
+    /// Every flow measurement may be assigned to its own branch and every
+    /// injection measurement to one branch incident to its bus. The largest
+    /// assignment whose branches form a forest is found by matroid
+    /// intersection (greedy start, then shortest augmenting paths); the
+    /// trees of that forest are the observable islands. Islands are
+    /// ordered as in [`Topology::islands`].
+    pub fn observable_islands(
+        &self,
+        flow_branches: &[BranchId],
+        injection_buses: &[BusId],
+    ) -> Vec<Vec<BusId>> {
+        let n = self.graph.node_count();
+        let measured: HashSet<BranchId> = flow_branches.iter().copied().collect();
+
+        // Candidate (measurement, branch) pairs; `group` identifies the measurement
+        let mut elements: Vec<(usize, usize, usize)> = Vec::new();
+        let mut groups = 0;
+        for edge in self.graph.edge_references() {
+            let (a, b) = (edge.source().index(), edge.target().index());
+            if a < b && measured.contains(&edge.weight().branch_id) {
+                elements.push((groups, a, b));
+                groups += 1;
+            }
+        }
+        for node in self.graph.node_indices() {
+            if injection_buses.contains(&self.graph[node].bus_id) {
+                for edge in self.graph.edges(node) {
+                    elements.push((groups, node.index(), edge.target().index()));
+                }
+                groups += 1;
+            }
+        }
+
+        // Greedy initial assignment
+        let mut chosen = vec![false; elements.len()];
+        let mut forest = UnionFind::new(n);
+        let mut group_used = vec![false; groups];
+        for (k, &(group, a, b)) in elements.iter().enumerate() {
+            if !group_used[group] && !forest.equiv(a, b) {
+                forest.union(a, b);
+                chosen[k] = true;
+                group_used[group] = true;
+            }
+        }
+
+        // Augment along shortest paths in the exchange graph
+        loop {
+            let mut forest = UnionFind::new(n);
+            let mut adjacency: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+            let mut group_member = vec![None; groups];
+            for (k, &(group, a, b)) in elements.iter().enumerate() {
+                if chosen[k] {
+                    forest.union(a, b);
+                    adjacency[a].push((b, k));
+                    adjacency[b].push((a, k));
+                    group_member[group] = Some(k);
+                }
+            }
+
+            // x → y when swapping chosen x for y keeps a forest: x lies on
+            // the forest path between the ends of y
+            let mut swaps: Vec<Vec<usize>> = vec![Vec::new(); elements.len()];
+            let mut queue = VecDeque::new();
+            let mut previous: Vec<Option<usize>> = vec![None; elements.len()];
+            let mut visited = vec![false; elements.len()];
+            for (y, &(_, a, b)) in elements.iter().enumerate() {
+                if chosen[y] || a == b {
+                    continue;
+                }
+                if forest.equiv(a, b) {
+                    for x in Self::forest_path(&adjacency, a, b) {
+                        swaps[x].push(y);
+                    }
+                } else {
+                    visited[y] = true;
+                    queue.push_back(y);
+                }
+            }
+
+            let mut end = None;
+            while let Some(k) = queue.pop_front() {
+                if !chosen[k] {
+                    // y → x when x holds y's measurement
+                    match group_member[elements[k].0] {
+                        None => {
+                            end = Some(k);
+                            break;
+                        }
+                        Some(x) if !visited[x] => {
+                            visited[x] = true;
+                            previous[x] = Some(k);
+                            queue.push_back(x);
+                        }
+                        Some(_) => {}
+                    }
+                } else {
+                    for &y in &swaps[k] {
+                        if !visited[y] {
+                            visited[y] = true;
+                            previous[y] = Some(k);
+                            queue.push_back(y);
+                        }
+                    }
+                }
+            }
+
+            let Some(mut k) = end else {
+                break;
+            };
+            loop {
+                chosen[k] = !chosen[k];
+                match previous[k] {
+                    Some(p) => k = p,
+                    None => break,
+                }
+            }
+        }
+
+        let mut forest = UnionFind::new(n);
+        for (k, &(_, a, b)) in elements.iter().enumerate() {
+            if chosen[k] {
+                forest.union(a, b);
+            }
+        }
+
+        let mut islands: Vec<Vec<BusId>> = Vec::new();
+        let mut island_of_root = HashMap::new();
+        for node in self.graph.node_indices() {
+            let root = forest.find(node.index());
+            let island = *island_of_root.entry(root).or_insert_with(|| {
+                islands.push(Vec::new());
+                islands.len() - 1
+            });
+            islands[island].push(self.graph[node].bus_id);
+        }
+        for island in &mut islands {
+            island.sort_by_key(|b| b.0);
+        }
+
+        islands
+    }
+
+    /// Elements on the path between `from` and `to` in a forest
+    fn forest_path(adjacency: &[Vec<(usize, usize)>], from: usize, to: usize) -> Vec<usize> {
+        let mut via: Vec<Option<(usize, usize)>> = vec![None; adjacency.len()];
+        let mut seen = vec![false; adjacency.len()];
+        let mut queue = VecDeque::from([from]);
+        seen[from] = true;
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                break;
+            }
+            for &(next, element) in &adjacency[node] {
+                if !seen[next] {
+                    seen[next] = true;
+                    via[next] = Some((node, element));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut path = Vec::new();
+        let mut node = to;
+        while let Some((parent, element)) = via[node] {
+            path.push(element);
+            node = parent;
+        }
+        path
+    }
+
+    /// Branches closing a loop (chords of a breadth-first spanning forest)
+    ///
+    /// Empty for a radial network. Sorted by branch id.
+    pub fn loop_branches(&self) -> Vec<BranchId> {
+        let mut tree = HashSet::new();
+        let mut visited = vec![false; self.graph.node_count()];
+        for start in self.graph.node_indices() {
+            if visited[start.index()] {
+                continue;
+            }
+            visited[start.index()] = true;
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                for edge in self.graph.edges(node) {
+                    let next = edge.target();
+                    if !visited[next.index()] {
+                        visited[next.index()] = true;
+                        tree.insert(edge.weight().branch_id);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let mut chords: Vec<BranchId> = self
+            .graph
+            .edge_weights()
+            .map(|e| e.branch_id)
+            .filter(|b| !tree.contains(b))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        chords.sort_by_key(|b| b.0);
+        chords
+    }
+
+    /// Buses of a radial network in breadth-first order from `root`
+    ///
+    /// Every bus comes after its parent. Fails with the loop branches listed if the network is
+    /// meshed, or if some buses cannot be reached from the root.
+    pub fn radial_order(&self, root: BusId) -> Result<Vec<RadialNode>> {
+        let loops = self.loop_branches();
+        if !loops.is_empty() {
+            let ids: Vec<String> = loops.iter().map(|b| b.0.to_string()).collect();
+            return Err(CoreError::TopologyError(format!(
+                "Network is meshed; loop branches: {}",
+                ids.join(", ")
+            )));
+        }
+        let start = self
+            .graph
+            .node_indices()
+            .find(|&n| self.graph[n].bus_id == root)
+            .ok_or(CoreError::InvalidBusId(root.0))?;
+
+        let mut visited = vec![false; self.graph.node_count()];
+        visited[start.index()] = true;
+        let mut order = vec![RadialNode {
+            bus_id: root,
+            parent: None,
+        }];
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for edge in self.graph.edges(node) {
+                let next = edge.target();
+                if !visited[next.index()] {
+                    visited[next.index()] = true;
+                    order.push(RadialNode {
+                        bus_id: self.graph[next].bus_id,
+                        parent: Some((self.graph[node].bus_id, edge.weight().branch_id)),
+                    });
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if order.len() < self.graph.node_count() {
+            return Err(CoreError::TopologyError(format!(
+                "{} buses are not connected to root bus {}",
+                self.graph.node_count() - order.len(),
+                root.0
+            )));
+        }
+        Ok(order)
+    }
+
     /// Get reference to internal graph
     pub fn graph(&self) -> &DiGraph<TopologyNode, TopologyEdge> {
         &self.graph
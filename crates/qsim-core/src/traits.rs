@@ -55,6 +55,16 @@ impl SolverResult {
 pub trait TimeStepper: Send + Sync {
     /// Advance simulation by one step
     fn step(&mut self, state: &mut StateStore, dt: f64) -> Result<()>;
+
+    /// Time reached by the last step, for steppers that keep their own clock
+    fn simulation_time(&self) -> Option<f64> {
+        None
+    }
+
+    /// Whether steps may change the network itself, e.g. by switching branches
+    fn changes_network(&self) -> bool {
+        false
+    }
 }
 
 /// Trait for output/observation handlers
@@ -64,4 +74,9 @@ pub trait OutputHandler: Send + Sync {
 
     /// Called when simulation completes
     fn on_complete(&mut self, state: &StateStore);
+
+    /// Whether results are computed on a fixed copy of the network
+    fn uses_fixed_network(&self) -> bool {
+        false
+    }
 }
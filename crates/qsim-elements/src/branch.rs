@@ -3,6 +3,49 @@
 use qsim_core::{GridElement, StateStore};
 use serde::{Deserialize, Serialize};
 
+/// Transformer winding connection, as seen by zero-sequence currents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindingConnection {
+    /// Grounded wye
+    #[default]
+    Yg,
+    /// Ungrounded wye
+    Y,
+    /// Delta
+    D,
+}
+
+/// Automatic control of a transformer's tap ratio or phase shift
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TransformerControl {
+    /// On-load tap changer holding a (possibly remote) bus voltage in a band
+    Voltage {
+        /// Regulated bus index
+        bus: usize,
+        /// Lower edge of the voltage band (per-unit)
+        v_min: f64,
+        /// Upper edge of the voltage band (per-unit)
+        v_max: f64,
+        /// Minimum tap ratio
+        tap_min: f64,
+        /// Maximum tap ratio
+        tap_max: f64,
+        /// Tap step (0.0 = continuous)
+        tap_step: f64,
+    },
+    /// Phase shifter holding the active flow at the from end
+    ActiveFlow {
+        /// Target flow (MW)
+        target: f64,
+        /// Accepted deviation from the target (MW)
+        tolerance: f64,
+        /// Minimum phase shift (radians)
+        shift_min: f64,
+        /// Maximum phase shift (radians)
+        shift_max: f64,
+    },
+}
+
 /// A branch (line or transformer) connecting two buses
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Branch {
@@ -20,6 +63,36 @@ pub struct Branch {
     pub tap_ratio: f64,
     /// Transformer phase shift (radians)
     pub phase_shift: f64,
+    /// Thermal rating (MVA, 0.0 = unlimited)
+    #[serde(default)]
+    pub rating: f64,
+    /// Negative-sequence resistance (per-unit, 0.0 with `x2` = positive sequence)
+    #[serde(default)]
+    pub r2: f64,
+    /// Negative-sequence reactance (per-unit, 0.0 = positive sequence)
+    #[serde(default)]
+    pub x2: f64,
+    /// Zero-sequence resistance (per-unit, 0.0 with `x0` = positive sequence)
+    #[serde(default)]
+    pub r0: f64,
+    /// Zero-sequence reactance (per-unit, 0.0 = positive sequence)
+    #[serde(default)]
+    pub x0: f64,
+    /// Winding connection at the from side (transformers only)
+    #[serde(default)]
+    pub from_connection: WindingConnection,
+    /// Winding connection at the to side (transformers only)
+    #[serde(default)]
+    pub to_connection: WindingConnection,
+    /// Automatic tap or phase-shift control (None = fixed)
+    #[serde(default)]
+    pub control: Option<TransformerControl>,
+    /// Whether the branch is a transformer, whatever its tap ratio
+    #[serde(default)]
+    pub is_transformer: bool,
+    /// Transformer rated power (MVA, 0.0 = system base)
+    #[serde(default)]
+    pub rated_mva: f64,
     /// Branch status (true = in service)
     pub in_service: bool,
 }
@@ -35,6 +108,16 @@ impl Branch {
             susceptance: 0.0,
             tap_ratio: 1.0,
             phase_shift: 0.0,
+            rating: 0.0,
+            r2: 0.0,
+            x2: 0.0,
+            r0: 0.0,
+            x0: 0.0,
+            from_connection: WindingConnection::Yg,
+            to_connection: WindingConnection::Yg,
+            control: None,
+            is_transformer: false,
+            rated_mva: 0.0,
             in_service: true,
         }
     }
@@ -55,6 +138,16 @@ impl Branch {
             susceptance,
             tap_ratio: 1.0,
             phase_shift: 0.0,
+            rating: 0.0,
+            r2: 0.0,
+            x2: 0.0,
+            r0: 0.0,
+            x0: 0.0,
+            from_connection: WindingConnection::Yg,
+            to_connection: WindingConnection::Yg,
+            control: None,
+            is_transformer: false,
+            rated_mva: 0.0,
             in_service: true,
         }
     }
@@ -75,6 +168,16 @@ impl Branch {
             susceptance: 0.0,
             tap_ratio,
             phase_shift: 0.0,
+            rating: 0.0,
+            r2: 0.0,
+            x2: 0.0,
+            r0: 0.0,
+            x0: 0.0,
+            from_connection: WindingConnection::Yg,
+            to_connection: WindingConnection::Yg,
+            control: None,
+            is_transformer: true,
+            rated_mva: 0.0,
             in_service: true,
         }
     }
@@ -93,14 +196,57 @@ impl Branch {
             (0.0, 0.0)
         }
     }
+
+    /// Negative-sequence series impedance (r, x)
+    pub fn negative_sequence_impedance(&self) -> (f64, f64) {
+        if self.r2 == 0.0 && self.x2 == 0.0 {
+            (self.resistance, self.reactance)
+        } else {
+            (self.r2, self.x2)
+        }
+    }
+
+    /// Zero-sequence series impedance (r, x)
+    pub fn zero_sequence_impedance(&self) -> (f64, f64) {
+        if self.r0 == 0.0 && self.x0 == 0.0 {
+            (self.resistance, self.reactance)
+        } else {
+            (self.r0, self.x0)
+        }
+    }
+
+    /// Whether the branch acts as a transformer: flagged as one, or with an
+    /// off-nominal tap ratio
+    pub fn acts_as_transformer(&self) -> bool {
+        self.is_transformer || (self.tap_ratio - 1.0).abs() >= 1e-6
+    }
+
+    /// Set the automatic tap or phase-shift control
+    pub fn with_control(mut self, control: TransformerControl) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    /// Set the transformer rated power (MVA)
+    pub fn with_rated_mva(mut self, rated_mva: f64) -> Self {
+        self.rated_mva = rated_mva;
+        self
+    }
+
+    /// Set the winding connections (from side, to side)
+    pub fn with_connections(mut self, from: WindingConnection, to: WindingConnection) -> Self {
+        self.from_connection = from;
+        self.to_connection = to;
+        self
+    }
 }
 
 impl GridElement for Branch {
     fn element_type(&self) -> &'static str {
-        if (self.tap_ratio - 1.0).abs() < 1e-6 {
-            "Branch::Line"
-        } else {
+        if self.acts_as_transformer() {
             "Branch::Transformer"
+        } else {
+            "Branch::Line"
         }
     }
 
@@ -29,6 +29,26 @@ pub struct Bus {
     pub reactive_power: f64,
     /// Base voltage (kV)
     pub base_voltage_kv: f64,
+    /// Minimum voltage magnitude (per-unit)
+    #[serde(default = "default_v_min")]
+    pub v_min: f64,
+    /// Maximum voltage magnitude (per-unit)
+    #[serde(default = "default_v_max")]
+    pub v_max: f64,
+    /// Fixed shunt conductance (MW consumed at 1.0 p.u.)
+    #[serde(default, alias = "gs")]
+    pub shunt_conductance: f64,
+    /// Fixed shunt susceptance (MVAr injected at 1.0 p.u.)
+    #[serde(default, alias = "bs")]
+    pub shunt_susceptance: f64,
+}
+
+fn default_v_min() -> f64 {
+    0.9
+}
+
+fn default_v_max() -> f64 {
+    1.1
 }
 
 impl Bus {
@@ -41,6 +61,10 @@ impl Bus {
             active_power: 0.0,
             reactive_power: 0.0,
             base_voltage_kv: 1.0,
+            v_min: default_v_min(),
+            v_max: default_v_max(),
+            shunt_conductance: 0.0,
+            shunt_susceptance: 0.0,
         }
     }
 
@@ -53,6 +77,10 @@ impl Bus {
             active_power,
             reactive_power: 0.0,
             base_voltage_kv: 1.0,
+            v_min: default_v_min(),
+            v_max: default_v_max(),
+            shunt_conductance: 0.0,
+            shunt_susceptance: 0.0,
         }
     }
 
@@ -65,8 +93,19 @@ impl Bus {
             active_power,
             reactive_power,
             base_voltage_kv: 1.0,
+            v_min: default_v_min(),
+            v_max: default_v_max(),
+            shunt_conductance: 0.0,
+            shunt_susceptance: 0.0,
         }
     }
+
+    /// Set the fixed shunt (MW and MVAr at 1.0 p.u.)
+    pub fn with_shunt(mut self, conductance: f64, susceptance: f64) -> Self {
+        self.shunt_conductance = conductance;
+        self.shunt_susceptance = susceptance;
+        self
+    }
 }
 
 impl GridElement for Bus {
@@ -0,0 +1,250 @@
+//! Excitation system models (IEEE 421.5)
+//!
+//! Parameters follow the PSS/E model records (ESST1A, ESAC1A, ESDC1A) in
+//! order, per-unit on the machine base. Defaults are the IEEE 421.5 sample
+//! data sets.
+
+use serde::{Deserialize, Serialize};
+
+/// Exciter saturation through two points of `Se(E)`
+///
+/// Uses the quadratic form `Se(E) = B·(E − A)² / E`, so the saturation
+/// voltage is `Se(E)·E`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Saturation {
+    /// First exciter voltage E1
+    pub e1: f64,
+    /// Saturation Se(E1)
+    pub se1: f64,
+    /// Second exciter voltage E2
+    pub e2: f64,
+    /// Saturation Se(E2)
+    pub se2: f64,
+}
+
+impl Saturation {
+    /// Create a saturation curve
+    pub fn new(e1: f64, se1: f64, e2: f64, se2: f64) -> Self {
+        Self { e1, se1, e2, se2 }
+    }
+
+    /// Saturation function Se(E); zero without saturation data
+    pub fn value(&self, e: f64) -> f64 {
+        if self.e1 <= 0.0 || self.e2 <= 0.0 || self.se1 <= 0.0 || self.se2 <= 0.0 {
+            return 0.0;
+        }
+        // Put the larger voltage first, then solve (E1 − A)/(E2 − A) = r
+        let ((e_high, s_high), (e_low, s_low)) = if self.e1 >= self.e2 {
+            ((self.e1, self.se1), (self.e2, self.se2))
+        } else {
+            ((self.e2, self.se2), (self.e1, self.se1))
+        };
+        let ratio = (s_high * e_high / (s_low * e_low)).sqrt();
+        if (ratio - 1.0).abs() < 1e-12 {
+            return 0.0;
+        }
+        let a = (e_high - ratio * e_low) / (1.0 - ratio);
+        let b = s_high * e_high / (e_high - a).powi(2);
+        if e > a && e > 0.0 {
+            b * (e - a).powi(2) / e
+        } else {
+            0.0
+        }
+    }
+}
+
+/// IEEE type ST1A static exciter (PSS/E ESST1A)
+///
+/// The field current limiter (`klr`, `ilr`) and under-excitation limiter
+/// input are kept for reference but not simulated.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct St1a {
+    /// Under-excitation limiter input location (ICON)
+    pub uel: i32,
+    /// Stabilizer input location: 1 = voltage error, 2 = regulator output (ICON)
+    pub vos: i32,
+    /// Voltage transducer time constant (s)
+    pub tr: f64,
+    /// Maximum voltage error
+    pub vimax: f64,
+    /// Minimum voltage error
+    pub vimin: f64,
+    /// First lead time constant (s)
+    pub tc: f64,
+    /// First lag time constant (s)
+    pub tb: f64,
+    /// Second lead time constant (s)
+    pub tc1: f64,
+    /// Second lag time constant (s)
+    pub tb1: f64,
+    /// Regulator gain
+    pub ka: f64,
+    /// Regulator time constant (s)
+    pub ta: f64,
+    /// Maximum regulator output
+    pub vamax: f64,
+    /// Minimum regulator output
+    pub vamin: f64,
+    /// Maximum exciter output (times terminal voltage)
+    pub vrmax: f64,
+    /// Minimum exciter output (times terminal voltage)
+    pub vrmin: f64,
+    /// Rectifier loading factor
+    pub kc: f64,
+    /// Rate feedback gain
+    pub kf: f64,
+    /// Rate feedback time constant (s)
+    pub tf: f64,
+    /// Field current limiter gain
+    pub klr: f64,
+    /// Field current limiter reference
+    pub ilr: f64,
+}
+
+impl Default for St1a {
+    fn default() -> Self {
+        Self {
+            uel: 1,
+            vos: 1,
+            tr: 0.0,
+            vimax: 999.0,
+            vimin: -999.0,
+            tc: 1.0,
+            tb: 1.0,
+            tc1: 0.0,
+            tb1: 0.0,
+            ka: 210.0,
+            ta: 0.0,
+            vamax: 999.0,
+            vamin: -999.0,
+            vrmax: 6.43,
+            vrmin: -6.0,
+            kc: 0.038,
+            kf: 0.0,
+            tf: 1.0,
+            klr: 4.54,
+            ilr: 4.4,
+        }
+    }
+}
+
+/// IEEE type AC1A alternator-rectifier exciter (PSS/E ESAC1A)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ac1a {
+    /// Voltage transducer time constant (s)
+    pub tr: f64,
+    /// Lag time constant (s)
+    pub tb: f64,
+    /// Lead time constant (s)
+    pub tc: f64,
+    /// Regulator gain
+    pub ka: f64,
+    /// Regulator time constant (s)
+    pub ta: f64,
+    /// Maximum regulator output
+    pub vamax: f64,
+    /// Minimum regulator output
+    pub vamin: f64,
+    /// Exciter time constant (s)
+    pub te: f64,
+    /// Rate feedback gain
+    pub kf: f64,
+    /// Rate feedback time constant (s)
+    pub tf: f64,
+    /// Rectifier loading factor
+    pub kc: f64,
+    /// Demagnetizing factor
+    pub kd: f64,
+    /// Exciter field constant
+    pub ke: f64,
+    /// Exciter saturation
+    pub saturation: Saturation,
+    /// Maximum regulator output
+    pub vrmax: f64,
+    /// Minimum regulator output
+    pub vrmin: f64,
+}
+
+impl Default for Ac1a {
+    // E2 = 3.14 is sample data, not π
+    #[allow(clippy::approx_constant)]
+    fn default() -> Self {
+        Self {
+            tr: 0.0,
+            tb: 0.0,
+            tc: 0.0,
+            ka: 400.0,
+            ta: 0.02,
+            vamax: 14.5,
+            vamin: -14.5,
+            te: 0.8,
+            kf: 0.03,
+            tf: 1.0,
+            kc: 0.2,
+            kd: 0.38,
+            ke: 1.0,
+            saturation: Saturation::new(4.18, 0.1, 3.14, 0.03),
+            vrmax: 6.03,
+            vrmin: -5.43,
+        }
+    }
+}
+
+/// IEEE type DC1A commutator exciter (PSS/E ESDC1A)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Dc1a {
+    /// Voltage transducer time constant (s)
+    pub tr: f64,
+    /// Regulator gain
+    pub ka: f64,
+    /// Regulator time constant (s)
+    pub ta: f64,
+    /// Lag time constant (s)
+    pub tb: f64,
+    /// Lead time constant (s)
+    pub tc: f64,
+    /// Maximum regulator output
+    pub vrmax: f64,
+    /// Minimum regulator output
+    pub vrmin: f64,
+    /// Exciter field constant (negative for self-excited exciters)
+    pub ke: f64,
+    /// Exciter time constant (s)
+    pub te: f64,
+    /// Rate feedback gain
+    pub kf: f64,
+    /// Rate feedback time constant (s)
+    pub tf: f64,
+    /// Exciter saturation
+    pub saturation: Saturation,
+}
+
+impl Default for Dc1a {
+    fn default() -> Self {
+        Self {
+            tr: 0.0,
+            ka: 46.0,
+            ta: 0.06,
+            tb: 0.0,
+            tc: 0.0,
+            vrmax: 1.0,
+            vrmin: -0.9,
+            ke: -0.0582,
+            te: 0.46,
+            kf: 0.1,
+            tf: 1.0,
+            saturation: Saturation::new(3.1, 0.33, 2.3, 0.1),
+        }
+    }
+}
+
+/// Excitation system of a synchronous machine
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Exciter {
+    /// IEEE ST1A static exciter
+    St1a(St1a),
+    /// IEEE AC1A alternator-rectifier exciter
+    Ac1a(Ac1a),
+    /// IEEE DC1A commutator exciter
+    Dc1a(Dc1a),
+}
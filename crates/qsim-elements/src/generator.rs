@@ -3,6 +3,8 @@
 use qsim_core::{GridElement, StateStore};
 use serde::{Deserialize, Serialize};
 
+use crate::{MachineDynamics, WindingConnection};
+
 /// A generator connected to a bus
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Generator {
@@ -22,10 +24,48 @@ pub struct Generator {
     pub q_min: f64,
     /// Maximum reactive power (MVAr)
     pub q_max: f64,
+    /// Machine base (MVA, 0.0 = system base)
+    #[serde(default)]
+    pub mva_base: f64,
+    /// Subtransient reactance X''d (per-unit on machine base, 0.0 = not modelled)
+    #[serde(default)]
+    pub subtransient_reactance: f64,
+    /// Negative-sequence reactance (per-unit on machine base, 0.0 = X''d)
+    #[serde(default)]
+    pub negative_sequence_reactance: f64,
+    /// Zero-sequence reactance (per-unit on machine base, 0.0 = X''d)
+    #[serde(default)]
+    pub zero_sequence_reactance: f64,
+    /// Neutral grounding reactance (per-unit on machine base)
+    #[serde(default)]
+    pub grounding_reactance: f64,
+    /// Stator winding connection
+    #[serde(default)]
+    pub connection: WindingConnection,
+    /// Rated power factor cos φ
+    #[serde(default = "default_rated_power_factor")]
+    pub rated_power_factor: f64,
+    /// Bus whose voltage the generator regulates (`None` = its own terminal)
+    #[serde(default)]
+    pub regulated_bus: Option<usize>,
+    /// Share of the reactive output among units regulating the same bus
+    #[serde(default = "default_reactive_share")]
+    pub reactive_share: f64,
+    /// Machine dynamics for transient stability (`None` = static injection)
+    #[serde(default)]
+    pub dynamics: Option<MachineDynamics>,
     /// Generator status (true = in service)
     pub in_service: bool,
 }
 
+fn default_rated_power_factor() -> f64 {
+    0.85
+}
+
+fn default_reactive_share() -> f64 {
+    1.0
+}
+
 impl Generator {
     /// Create a new generator
     pub fn new(bus: usize, active_power: f64, voltage_setpoint: f64) -> Self {
@@ -38,6 +78,16 @@ impl Generator {
             p_max: active_power * 2.0,
             q_min: -active_power,
             q_max: active_power,
+            mva_base: 0.0,
+            subtransient_reactance: 0.0,
+            negative_sequence_reactance: 0.0,
+            zero_sequence_reactance: 0.0,
+            grounding_reactance: 0.0,
+            connection: WindingConnection::Yg,
+            rated_power_factor: default_rated_power_factor(),
+            regulated_bus: None,
+            reactive_share: default_reactive_share(),
+            dynamics: None,
             in_service: true,
         }
     }
@@ -61,9 +111,51 @@ impl Generator {
             p_max,
             q_min,
             q_max,
+            mva_base: 0.0,
+            subtransient_reactance: 0.0,
+            negative_sequence_reactance: 0.0,
+            zero_sequence_reactance: 0.0,
+            grounding_reactance: 0.0,
+            connection: WindingConnection::Yg,
+            rated_power_factor: default_rated_power_factor(),
+            regulated_bus: None,
+            reactive_share: default_reactive_share(),
+            dynamics: None,
             in_service: true,
         }
     }
+
+    /// Builder: regulate the voltage of a remote bus
+    pub fn with_regulated_bus(mut self, bus: usize) -> Self {
+        self.regulated_bus = Some(bus);
+        self
+    }
+
+    /// Builder: set the reactive sharing factor
+    pub fn with_reactive_share(mut self, share: f64) -> Self {
+        self.reactive_share = share;
+        self
+    }
+
+    /// Builder: attach machine dynamics
+    pub fn with_dynamics(mut self, dynamics: MachineDynamics) -> Self {
+        self.dynamics = Some(dynamics);
+        self
+    }
+
+    /// Bus whose voltage the generator regulates
+    pub fn controlled_bus(&self) -> usize {
+        self.regulated_bus.unwrap_or(self.bus)
+    }
+
+    /// Machine base, falling back to `system_base` when unset (MVA)
+    pub fn machine_base(&self, system_base: f64) -> f64 {
+        if self.mva_base > 0.0 {
+            self.mva_base
+        } else {
+            system_base
+        }
+    }
 }
 
 impl GridElement for Generator {
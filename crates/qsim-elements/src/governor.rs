@@ -0,0 +1,180 @@
+//! Turbine-governor models
+//!
+//! Parameters follow the PSS/E model records (TGOV1, HYGOV, IEEEG1) in
+//! order, per-unit on the machine base.
+
+use serde::{Deserialize, Serialize};
+
+/// Steam turbine-governor (PSS/E TGOV1)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tgov1 {
+    /// Permanent droop R
+    pub r: f64,
+    /// Valve time constant (s)
+    pub t1: f64,
+    /// Maximum valve position
+    pub vmax: f64,
+    /// Minimum valve position
+    pub vmin: f64,
+    /// Turbine lead time constant (s)
+    pub t2: f64,
+    /// Turbine lag time constant (s)
+    pub t3: f64,
+    /// Turbine damping coefficient
+    pub dt: f64,
+}
+
+impl Default for Tgov1 {
+    fn default() -> Self {
+        Self {
+            r: 0.05,
+            t1: 0.5,
+            vmax: 1.0,
+            vmin: 0.0,
+            t2: 3.0,
+            t3: 10.0,
+            dt: 0.0,
+        }
+    }
+}
+
+/// Hydro turbine-governor with non-elastic water column (PSS/E HYGOV)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hygov {
+    /// Permanent droop R
+    pub r: f64,
+    /// Temporary droop r
+    pub temporary_droop: f64,
+    /// Governor time constant Tr (s)
+    pub tr: f64,
+    /// Filter time constant (s)
+    pub tf: f64,
+    /// Servo time constant (s)
+    pub tg: f64,
+    /// Gate velocity limit
+    pub velm: f64,
+    /// Maximum gate opening
+    pub gmax: f64,
+    /// Minimum gate opening
+    pub gmin: f64,
+    /// Water inertia time constant (s)
+    pub tw: f64,
+    /// Turbine gain
+    pub at: f64,
+    /// Turbine damping factor
+    pub dturb: f64,
+    /// No-load flow
+    pub qnl: f64,
+}
+
+impl Default for Hygov {
+    fn default() -> Self {
+        Self {
+            r: 0.05,
+            temporary_droop: 0.3,
+            tr: 5.0,
+            tf: 0.05,
+            tg: 0.5,
+            velm: 0.2,
+            gmax: 1.0,
+            gmin: 0.0,
+            tw: 1.0,
+            at: 1.2,
+            dturb: 0.5,
+            qnl: 0.08,
+        }
+    }
+}
+
+/// IEEE type 1 steam turbine-governor (PSS/E IEEEG1)
+///
+/// Only the high-pressure unit output (K1, K3, K5, K7) drives the machine;
+/// the low-pressure gains belong to a second machine and are kept for
+/// reference.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ieeeg1 {
+    /// Bus of the low-pressure unit, 0 = none (ICON)
+    pub jbus: i32,
+    /// Machine number of the low-pressure unit (ICON)
+    pub m: i32,
+    /// Governor gain K (1/R)
+    pub k: f64,
+    /// Governor lag time constant (s)
+    pub t1: f64,
+    /// Governor lead time constant (s)
+    pub t2: f64,
+    /// Valve positioner time constant (s)
+    pub t3: f64,
+    /// Maximum valve opening rate (per-unit/s)
+    pub uo: f64,
+    /// Maximum valve closing rate (per-unit/s, negative)
+    pub uc: f64,
+    /// Maximum valve opening
+    pub pmax: f64,
+    /// Minimum valve opening
+    pub pmin: f64,
+    /// Steam chest time constant (s)
+    pub t4: f64,
+    /// High-pressure fraction after T4
+    pub k1: f64,
+    /// Low-pressure fraction after T4
+    pub k2: f64,
+    /// Reheater time constant (s)
+    pub t5: f64,
+    /// High-pressure fraction after T5
+    pub k3: f64,
+    /// Low-pressure fraction after T5
+    pub k4: f64,
+    /// Crossover time constant (s)
+    pub t6: f64,
+    /// High-pressure fraction after T6
+    pub k5: f64,
+    /// Low-pressure fraction after T6
+    pub k6: f64,
+    /// Double reheat time constant (s)
+    pub t7: f64,
+    /// High-pressure fraction after T7
+    pub k7: f64,
+    /// Low-pressure fraction after T7
+    pub k8: f64,
+}
+
+impl Default for Ieeeg1 {
+    fn default() -> Self {
+        Self {
+            jbus: 0,
+            m: 0,
+            k: 20.0,
+            t1: 0.0,
+            t2: 0.0,
+            t3: 0.1,
+            uo: 1.0,
+            uc: -1.0,
+            pmax: 1.0,
+            pmin: 0.0,
+            t4: 0.3,
+            k1: 0.3,
+            k2: 0.0,
+            t5: 7.0,
+            k3: 0.4,
+            k4: 0.0,
+            t6: 0.6,
+            k5: 0.3,
+            k6: 0.0,
+            t7: 0.0,
+            k7: 0.0,
+            k8: 0.0,
+        }
+    }
+}
+
+/// Turbine-governor of a synchronous machine
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Governor {
+    /// Steam turbine-governor TGOV1
+    Tgov1(Tgov1),
+    /// Hydro turbine-governor HYGOV
+    Hygov(Hygov),
+    /// IEEE type 1 steam turbine-governor
+    Ieeeg1(Ieeeg1),
+}
@@ -0,0 +1,232 @@
+//! HVDC link element — point-to-point LCC and VSC interconnectors
+
+use serde::{Deserialize, Serialize};
+
+/// Converter losses as a function of the AC-side current
+///
+/// `P_loss = constant + linear·I + quadratic·I²` (MW, with I in kA).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConverterLosses {
+    /// No-load losses (MW)
+    pub constant: f64,
+    /// Losses proportional to current (MW/kA)
+    pub linear: f64,
+    /// Losses proportional to current squared (MW/kA²)
+    pub quadratic: f64,
+}
+
+impl ConverterLosses {
+    /// Create a loss model
+    pub fn new(constant: f64, linear: f64, quadratic: f64) -> Self {
+        Self {
+            constant,
+            linear,
+            quadratic,
+        }
+    }
+
+    /// Losses at an AC-side current (MW)
+    pub fn at_current(&self, current_ka: f64) -> f64 {
+        self.constant + self.linear * current_ka + self.quadratic * current_ka * current_ka
+    }
+}
+
+/// Reactive power control of a VSC converter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReactiveControl {
+    /// Fixed reactive injection (MVAr, positive = into the AC bus)
+    Reactive(f64),
+    /// Hold the AC bus voltage (per-unit) within the reactive limits
+    Voltage(f64),
+}
+
+impl Default for ReactiveControl {
+    fn default() -> Self {
+        Self::Reactive(0.0)
+    }
+}
+
+/// A line-commutated (thyristor) converter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LccConverter {
+    /// Ideal no-load DC voltage at 1.0 p.u. AC voltage (kV)
+    pub no_load_voltage_kv: f64,
+    /// Equivalent commutating resistance `3·Xc/π` (Ω)
+    pub commutating_resistance: f64,
+    /// Minimum firing (rectifier) or extinction (inverter) angle (radians)
+    pub min_angle: f64,
+    /// Converter losses
+    #[serde(default)]
+    pub losses: ConverterLosses,
+}
+
+impl LccConverter {
+    /// Create a converter with no losses
+    pub fn new(no_load_voltage_kv: f64, commutating_resistance: f64, min_angle: f64) -> Self {
+        Self {
+            no_load_voltage_kv,
+            commutating_resistance,
+            min_angle,
+            losses: ConverterLosses::default(),
+        }
+    }
+
+    /// Builder: set converter losses
+    pub fn with_losses(mut self, losses: ConverterLosses) -> Self {
+        self.losses = losses;
+        self
+    }
+}
+
+/// A voltage-source converter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VscConverter {
+    /// Reactive power control mode
+    #[serde(default)]
+    pub reactive_control: ReactiveControl,
+    /// Minimum reactive injection (MVAr)
+    pub q_min: f64,
+    /// Maximum reactive injection (MVAr)
+    pub q_max: f64,
+    /// Apparent power rating (MVA, 0.0 = unlimited); active power has priority
+    #[serde(default)]
+    pub rating: f64,
+    /// Converter losses
+    #[serde(default)]
+    pub losses: ConverterLosses,
+}
+
+impl VscConverter {
+    /// Create a converter with fixed reactive injection and no losses
+    pub fn new(q_min: f64, q_max: f64) -> Self {
+        Self {
+            reactive_control: ReactiveControl::default(),
+            q_min,
+            q_max,
+            rating: 0.0,
+            losses: ConverterLosses::default(),
+        }
+    }
+
+    /// Builder: set the reactive control mode
+    pub fn with_reactive_control(mut self, control: ReactiveControl) -> Self {
+        self.reactive_control = control;
+        self
+    }
+
+    /// Builder: set the apparent power rating (MVA)
+    pub fn with_rating(mut self, rating: f64) -> Self {
+        self.rating = rating;
+        self
+    }
+
+    /// Builder: set converter losses
+    pub fn with_losses(mut self, losses: ConverterLosses) -> Self {
+        self.losses = losses;
+        self
+    }
+}
+
+/// Converter technology of an HVDC link
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HvdcConverters {
+    /// Line-commutated link: rectifier at the from bus in power control,
+    /// inverter at the to bus at minimum extinction angle
+    Lcc {
+        rectifier: LccConverter,
+        inverter: LccConverter,
+    },
+    /// Voltage-source link: from converter in power control, to converter
+    /// holding the DC voltage
+    Vsc {
+        from: VscConverter,
+        to: VscConverter,
+        /// Scheduled DC voltage at the to converter (kV)
+        dc_voltage_kv: f64,
+    },
+}
+
+/// A point-to-point HVDC link between two AC buses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HvdcLink {
+    /// From (sending) AC bus index
+    pub from_bus: usize,
+    /// To (receiving) AC bus index
+    pub to_bus: usize,
+    /// DC line resistance (Ω)
+    pub resistance: f64,
+    /// Scheduled DC power at the from converter (MW, positive = from → to)
+    pub power_setpoint: f64,
+    /// Maximum DC power magnitude (MW, 0.0 = unlimited)
+    #[serde(default)]
+    pub p_max: f64,
+    /// Converter technology and data
+    pub converters: HvdcConverters,
+    /// Link status (true = in service)
+    pub in_service: bool,
+}
+
+impl HvdcLink {
+    /// Create a line-commutated link
+    pub fn lcc(
+        from_bus: usize,
+        to_bus: usize,
+        resistance: f64,
+        power_setpoint: f64,
+        rectifier: LccConverter,
+        inverter: LccConverter,
+    ) -> Self {
+        Self {
+            from_bus,
+            to_bus,
+            resistance,
+            power_setpoint,
+            p_max: 0.0,
+            converters: HvdcConverters::Lcc {
+                rectifier,
+                inverter,
+            },
+            in_service: true,
+        }
+    }
+
+    /// Create a voltage-source link
+    pub fn vsc(
+        from_bus: usize,
+        to_bus: usize,
+        resistance: f64,
+        power_setpoint: f64,
+        dc_voltage_kv: f64,
+        from: VscConverter,
+        to: VscConverter,
+    ) -> Self {
+        Self {
+            from_bus,
+            to_bus,
+            resistance,
+            power_setpoint,
+            p_max: 0.0,
+            converters: HvdcConverters::Vsc {
+                from,
+                to,
+                dc_voltage_kv,
+            },
+            in_service: true,
+        }
+    }
+
+    /// Builder: set the DC power limit (MW)
+    pub fn with_p_max(mut self, p_max: f64) -> Self {
+        self.p_max = p_max;
+        self
+    }
+
+    /// Scheduled DC power after applying the power limit (MW)
+    pub fn scheduled_power(&self) -> f64 {
+        if self.p_max > 0.0 {
+            self.power_setpoint.clamp(-self.p_max, self.p_max)
+        } else {
+            self.power_setpoint
+        }
+    }
+}
@@ -6,15 +6,46 @@
 //!
 //! - [`Bus`] — Network nodes (Slack, PV, PQ)
 //! - [`Branch`] — Lines and transformers
+//! - [`ThreeWindingTransformer`] — Three-winding transformers (star equivalent)
 //! - [`Generator`] — Power generation units
+//! - [`MachineDynamics`] — Synchronous machine data for transient stability
+//! - [`Exciter`], [`Governor`], [`Stabilizer`] — Machine controllers (IEEE / PSS/E models)
 //! - [`Load`] — Power consumption
+//! - [`HvdcLink`] — Point-to-point LCC and VSC interconnectors
+//! - [`SwitchedShunt`] — Stepped capacitor and reactor banks
+//! - [`Network`] — Container tying the elements together
+//! - [`Measurement`] — Telemetered quantities for state estimation
+//! - [`Profile`] — Time-series values for loads and generators
+//! - [`PhaseNetwork`] — Phase-resolved lines, loads and transformers
 
 mod bus;
 mod branch;
+mod exciter;
 mod generator;
+mod governor;
+mod hvdc;
 mod load;
+mod machine;
+mod measurement;
+mod network;
+mod profile;
+mod shunt;
+mod stabilizer;
+mod three_phase;
+mod transformer3w;
 
 pub use bus::*;
 pub use branch::*;
+pub use exciter::*;
 pub use generator::*;
+pub use governor::*;
+pub use hvdc::*;
 pub use load::*;
+pub use machine::*;
+pub use measurement::*;
+pub use network::*;
+pub use profile::*;
+pub use shunt::*;
+pub use stabilizer::*;
+pub use three_phase::*;
+pub use transformer3w::*;
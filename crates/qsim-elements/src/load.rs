@@ -3,6 +3,58 @@
 use qsim_core::{GridElement, StateStore};
 use serde::{Deserialize, Serialize};
 
+/// Voltage dependence of a load's demand
+///
+/// Demand is scaled from its nominal value at 1.0 p.u. voltage:
+///
+/// ```text
+/// ZIP:          P = P0·(z·V² + i·V + p)
+/// Exponential:  P = P0·V^α
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum LoadModel {
+    /// Demand independent of voltage
+    #[default]
+    ConstantPower,
+    /// Constant impedance, current and power fractions for P and Q
+    Zip {
+        /// Active power fractions [z, i, p] (sum to 1.0)
+        active: [f64; 3],
+        /// Reactive power fractions [z, i, p] (sum to 1.0)
+        reactive: [f64; 3],
+    },
+    /// Exponential voltage dependence
+    Exponential {
+        /// Active power exponent α
+        active: f64,
+        /// Reactive power exponent β
+        reactive: f64,
+    },
+}
+
+impl LoadModel {
+    /// Demand scaling (P, Q) at voltage magnitude `vm` (per-unit)
+    pub fn factors(&self, vm: f64) -> (f64, f64) {
+        let zip = |c: &[f64; 3]| c[0] * vm * vm + c[1] * vm + c[2];
+        match self {
+            LoadModel::ConstantPower => (1.0, 1.0),
+            LoadModel::Zip { active, reactive } => (zip(active), zip(reactive)),
+            LoadModel::Exponential { active, reactive } => (vm.powf(*active), vm.powf(*reactive)),
+        }
+    }
+
+    /// Derivative of the demand scaling (P, Q) with respect to `vm`
+    pub fn derivatives(&self, vm: f64) -> (f64, f64) {
+        let zip = |c: &[f64; 3]| 2.0 * c[0] * vm + c[1];
+        let exp = |a: f64| if a == 0.0 { 0.0 } else { a * vm.powf(a - 1.0) };
+        match self {
+            LoadModel::ConstantPower => (0.0, 0.0),
+            LoadModel::Zip { active, reactive } => (zip(active), zip(reactive)),
+            LoadModel::Exponential { active, reactive } => (exp(*active), exp(*reactive)),
+        }
+    }
+}
+
 /// A load connected to a bus
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Load {
@@ -12,6 +64,9 @@ pub struct Load {
     pub active_power: f64,
     /// Reactive power consumption (MVAr, positive = consumption)
     pub reactive_power: f64,
+    /// Voltage dependence (active/reactive power are nominal values at 1.0 p.u.)
+    #[serde(default)]
+    pub model: LoadModel,
     /// Load status (true = in service)
     pub in_service: bool,
 }
@@ -23,6 +78,7 @@ impl Load {
             bus,
             active_power,
             reactive_power,
+            model: LoadModel::ConstantPower,
             in_service: true,
         }
     }
@@ -38,6 +94,18 @@ impl Load {
         let reactive_power = apparent_power * (1.0 - power_factor.powi(2)).sqrt();
         Self::new(bus, active_power, reactive_power)
     }
+
+    /// Set the voltage dependence
+    pub fn with_model(mut self, model: LoadModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Demand (MW, MVAr) at voltage magnitude `vm` (per-unit)
+    pub fn demand_at(&self, vm: f64) -> (f64, f64) {
+        let (kp, kq) = self.model.factors(vm);
+        (self.active_power * kp, self.reactive_power * kq)
+    }
 }
 
 impl GridElement for Load {
@@ -0,0 +1,133 @@
+//! Synchronous machine dynamic data for transient stability
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Exciter, Governor, Stabilizer};
+
+/// Order of the synchronous machine model
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MachineModel {
+    /// Constant voltage behind the transient reactance X'd (2nd order)
+    #[default]
+    Classical,
+    /// Two-axis model with d- and q-axis transient flux dynamics (4th order)
+    TwoAxis,
+}
+
+/// Dynamic data of a synchronous machine
+///
+/// Reactances and resistance are per-unit on the machine base, the inertia
+/// constant in MW·s/MVA of machine base.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MachineDynamics {
+    /// Model order
+    #[serde(default)]
+    pub model: MachineModel,
+    /// Inertia constant H (s)
+    pub inertia: f64,
+    /// Damping D (per-unit power per per-unit speed deviation)
+    #[serde(default)]
+    pub damping: f64,
+    /// Armature resistance Ra
+    #[serde(default)]
+    pub armature_resistance: f64,
+    /// d-axis synchronous reactance Xd
+    pub xd: f64,
+    /// q-axis synchronous reactance Xq
+    pub xq: f64,
+    /// d-axis transient reactance X'd
+    pub xd_prime: f64,
+    /// q-axis transient reactance X'q
+    pub xq_prime: f64,
+    /// d-axis open-circuit transient time constant T'd0 (s)
+    pub td0_prime: f64,
+    /// q-axis open-circuit transient time constant T'q0 (s)
+    pub tq0_prime: f64,
+    /// Excitation system; requires the two-axis model
+    #[serde(default)]
+    pub exciter: Option<Exciter>,
+    /// Turbine-governor
+    #[serde(default)]
+    pub governor: Option<Governor>,
+    /// Power system stabilizer feeding the exciter
+    #[serde(default)]
+    pub stabilizer: Option<Stabilizer>,
+}
+
+impl MachineDynamics {
+    /// Classical model with inertia H, damping D and transient reactance X'd
+    pub fn classical(inertia: f64, damping: f64, xd_prime: f64) -> Self {
+        Self {
+            model: MachineModel::Classical,
+            inertia,
+            damping,
+            armature_resistance: 0.0,
+            xd: xd_prime,
+            xq: xd_prime,
+            xd_prime,
+            xq_prime: xd_prime,
+            td0_prime: 0.0,
+            tq0_prime: 0.0,
+            exciter: None,
+            governor: None,
+            stabilizer: None,
+        }
+    }
+
+    /// Two-axis model; time constants default to T'd0 = 6 s and T'q0 = 0.5 s
+    pub fn two_axis(
+        inertia: f64,
+        damping: f64,
+        xd: f64,
+        xq: f64,
+        xd_prime: f64,
+        xq_prime: f64,
+    ) -> Self {
+        Self {
+            model: MachineModel::TwoAxis,
+            inertia,
+            damping,
+            armature_resistance: 0.0,
+            xd,
+            xq,
+            xd_prime,
+            xq_prime,
+            td0_prime: 6.0,
+            tq0_prime: 0.5,
+            exciter: None,
+            governor: None,
+            stabilizer: None,
+        }
+    }
+
+    /// Builder: set the open-circuit transient time constants (s)
+    pub fn with_time_constants(mut self, td0_prime: f64, tq0_prime: f64) -> Self {
+        self.td0_prime = td0_prime;
+        self.tq0_prime = tq0_prime;
+        self
+    }
+
+    /// Builder: set the armature resistance
+    pub fn with_armature_resistance(mut self, resistance: f64) -> Self {
+        self.armature_resistance = resistance;
+        self
+    }
+
+    /// Builder: attach an excitation system
+    pub fn with_exciter(mut self, exciter: Exciter) -> Self {
+        self.exciter = Some(exciter);
+        self
+    }
+
+    /// Builder: attach a turbine-governor
+    pub fn with_governor(mut self, governor: Governor) -> Self {
+        self.governor = Some(governor);
+        self
+    }
+
+    /// Builder: attach a power system stabilizer
+    pub fn with_stabilizer(mut self, stabilizer: Stabilizer) -> Self {
+        self.stabilizer = Some(stabilizer);
+        self
+    }
+}
@@ -0,0 +1,90 @@
+//! Measurement model — telemetered quantities for state estimation
+
+use serde::{Deserialize, Serialize};
+
+/// Branch terminal at which a flow is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BranchEnd {
+    /// Terminal at `Branch::from_bus`
+    From,
+    /// Terminal at `Branch::to_bus`
+    To,
+}
+
+/// Measured quantity and its location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MeasurementKind {
+    /// Bus voltage magnitude (per-unit)
+    VoltageMagnitude { bus: usize },
+    /// Bus voltage angle from a PMU (radians)
+    VoltageAngle { bus: usize },
+    /// Active power injection at a bus (MW)
+    ActiveInjection { bus: usize },
+    /// Reactive power injection at a bus (MVAr)
+    ReactiveInjection { bus: usize },
+    /// Active power flow into a branch (MW)
+    ActiveFlow { branch: usize, end: BranchEnd },
+    /// Reactive power flow into a branch (MVAr)
+    ReactiveFlow { branch: usize, end: BranchEnd },
+}
+
+/// A single measurement with its accuracy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    /// What is measured, and where
+    pub kind: MeasurementKind,
+    /// Measured value (units per [`MeasurementKind`])
+    pub value: f64,
+    /// Standard deviation of the measurement error (same units as `value`)
+    pub std_dev: f64,
+}
+
+impl Measurement {
+    /// Create a measurement
+    pub fn new(kind: MeasurementKind, value: f64, std_dev: f64) -> Self {
+        Self {
+            kind,
+            value,
+            std_dev,
+        }
+    }
+
+    /// Voltage magnitude measurement
+    pub fn voltage_magnitude(bus: usize, value: f64, std_dev: f64) -> Self {
+        Self::new(MeasurementKind::VoltageMagnitude { bus }, value, std_dev)
+    }
+
+    /// PMU voltage angle measurement
+    pub fn voltage_angle(bus: usize, value: f64, std_dev: f64) -> Self {
+        Self::new(MeasurementKind::VoltageAngle { bus }, value, std_dev)
+    }
+
+    /// Active power injection measurement
+    pub fn active_injection(bus: usize, value: f64, std_dev: f64) -> Self {
+        Self::new(MeasurementKind::ActiveInjection { bus }, value, std_dev)
+    }
+
+    /// Reactive power injection measurement
+    pub fn reactive_injection(bus: usize, value: f64, std_dev: f64) -> Self {
+        Self::new(MeasurementKind::ReactiveInjection { bus }, value, std_dev)
+    }
+
+    /// Active power flow measurement
+    pub fn active_flow(branch: usize, end: BranchEnd, value: f64, std_dev: f64) -> Self {
+        Self::new(MeasurementKind::ActiveFlow { branch, end }, value, std_dev)
+    }
+
+    /// Reactive power flow measurement
+    pub fn reactive_flow(branch: usize, end: BranchEnd, value: f64, std_dev: f64) -> Self {
+        Self::new(
+            MeasurementKind::ReactiveFlow { branch, end },
+            value,
+            std_dev,
+        )
+    }
+
+    /// Weight of the measurement (1/σ²)
+    pub fn weight(&self) -> f64 {
+        1.0 / (self.std_dev * self.std_dev)
+    }
+}
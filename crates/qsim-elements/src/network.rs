@@ -0,0 +1,284 @@
+//! Network container — the full set of grid elements
+
+use qsim_core::{BranchId, BusId, CoreError, GridElement, Result, StateStore, Topology};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Branch, Bus, BusType, Generator, HvdcLink, Load, SwitchedShunt, ThreeWindingTransformer,
+};
+
+/// A power network assembled from grid elements
+///
+/// Buses are addressed by their position in [`Network::buses`]; branches,
+/// generators and loads refer to buses by that index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Network {
+    /// Base MVA for per-unit conversion
+    pub base_mva: f64,
+    /// Buses
+    pub buses: Vec<Bus>,
+    /// Branches
+    pub branches: Vec<Branch>,
+    /// Generators
+    pub generators: Vec<Generator>,
+    /// Loads
+    pub loads: Vec<Load>,
+    /// Switched shunts
+    #[serde(default)]
+    pub switched_shunts: Vec<SwitchedShunt>,
+    /// Three-winding transformers (expanded into `buses` and `branches` by
+    /// [`Network::expand_three_winding_transformers`])
+    #[serde(default)]
+    pub three_winding_transformers: Vec<ThreeWindingTransformer>,
+    /// Point-to-point HVDC links
+    #[serde(default)]
+    pub hvdc_links: Vec<HvdcLink>,
+}
+
+impl Network {
+    /// Create an empty network with a 100 MVA base
+    pub fn new() -> Self {
+        Self::with_base_mva(100.0)
+    }
+
+    /// Create an empty network with the given base MVA
+    pub fn with_base_mva(base_mva: f64) -> Self {
+        Self {
+            base_mva,
+            buses: Vec::new(),
+            branches: Vec::new(),
+            generators: Vec::new(),
+            loads: Vec::new(),
+            switched_shunts: Vec::new(),
+            three_winding_transformers: Vec::new(),
+            hvdc_links: Vec::new(),
+        }
+    }
+
+    /// Add a bus, returning its index
+    pub fn add_bus(&mut self, bus: Bus) -> usize {
+        self.buses.push(bus);
+        self.buses.len() - 1
+    }
+
+    /// Add a branch, returning its index
+    pub fn add_branch(&mut self, branch: Branch) -> usize {
+        self.branches.push(branch);
+        self.branches.len() - 1
+    }
+
+    /// Add a generator, returning its index
+    pub fn add_generator(&mut self, generator: Generator) -> usize {
+        self.generators.push(generator);
+        self.generators.len() - 1
+    }
+
+    /// Add a load, returning its index
+    pub fn add_load(&mut self, load: Load) -> usize {
+        self.loads.push(load);
+        self.loads.len() - 1
+    }
+
+    /// Add a switched shunt, returning its index
+    pub fn add_switched_shunt(&mut self, shunt: SwitchedShunt) -> usize {
+        self.switched_shunts.push(shunt);
+        self.switched_shunts.len() - 1
+    }
+
+    /// Add an HVDC link, returning its index
+    pub fn add_hvdc_link(&mut self, link: HvdcLink) -> usize {
+        self.hvdc_links.push(link);
+        self.hvdc_links.len() - 1
+    }
+
+    /// Add a three-winding transformer, returning its index
+    ///
+    /// The transformer is expanded into its star equivalent: a star bus
+    /// (taking the primary's base voltage) and one branch per winding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a winding bus is not in the network.
+    pub fn add_three_winding_transformer(&mut self, transformer: ThreeWindingTransformer) -> usize {
+        self.three_winding_transformers.push(transformer);
+        let index = self.three_winding_transformers.len() - 1;
+        self.expand_three_winding_transformer(index)
+            .expect("winding buses must be in the network");
+        index
+    }
+
+    /// Expand every three-winding transformer into its star equivalent
+    ///
+    /// Transformers without a star bus get a new star bus and branches;
+    /// the star branches of the others are rebuilt from the transformer
+    /// data, keeping any branch control. Call after loading a network or
+    /// editing a transformer's impedances, taps, ratings or status.
+    pub fn expand_three_winding_transformers(&mut self) -> Result<()> {
+        (0..self.three_winding_transformers.len())
+            .try_for_each(|index| self.expand_three_winding_transformer(index))
+    }
+
+    fn expand_three_winding_transformer(&mut self, index: usize) -> Result<()> {
+        let transformer = self.three_winding_transformers[index].clone();
+        if let Some(&bus) = transformer.buses.iter().find(|&&b| b >= self.buses.len()) {
+            return Err(CoreError::InvalidBusId(bus));
+        }
+        let (star_bus, branches) = match (transformer.star_bus, transformer.branches) {
+            (None, None) => {
+                let mut star = Bus::pq(0.0, 0.0);
+                star.base_voltage_kv = self.buses[transformer.buses[0]].base_voltage_kv;
+                let star_bus = self.add_bus(star);
+                let branches = [0, 1, 2].map(|w| self.add_branch(transformer.star_branch(w, star_bus)));
+                let transformer = &mut self.three_winding_transformers[index];
+                transformer.star_bus = Some(star_bus);
+                transformer.branches = Some(branches);
+                return Ok(());
+            }
+            (Some(star_bus), Some(branches)) => (star_bus, branches),
+            _ => {
+                return Err(CoreError::TopologyError(format!(
+                    "Three-winding transformer {index} has a star bus or star branches, not both"
+                )))
+            }
+        };
+
+        if star_bus >= self.buses.len() || transformer.buses.contains(&star_bus) {
+            return Err(CoreError::InvalidBusId(star_bus));
+        }
+        for (winding, &branch) in branches.iter().enumerate() {
+            let connected = self
+                .branches
+                .get(branch)
+                .is_some_and(|b| b.from_bus == transformer.buses[winding] && b.to_bus == star_bus);
+            if !connected {
+                return Err(CoreError::InvalidBranchId(branch));
+            }
+        }
+        for (winding, &branch) in branches.iter().enumerate() {
+            let control = self.branches[branch].control;
+            self.branches[branch] = transformer.star_branch(winding, star_bus);
+            self.branches[branch].control = control;
+        }
+        Ok(())
+    }
+
+    /// Total shunt admittance per bus (MW, MVAr at 1.0 p.u.)
+    ///
+    /// Combines fixed bus shunts with the steps of in-service switched
+    /// shunts.
+    pub fn shunt_admittance(&self) -> Vec<(f64, f64)> {
+        let mut shunts: Vec<(f64, f64)> = self
+            .buses
+            .iter()
+            .map(|b| (b.shunt_conductance, b.shunt_susceptance))
+            .collect();
+        for shunt in &self.switched_shunts {
+            shunts[shunt.bus].1 += shunt.susceptance();
+        }
+        shunts
+    }
+
+    /// Number of buses
+    pub fn bus_count(&self) -> usize {
+        self.buses.len()
+    }
+
+    /// Number of branches
+    pub fn branch_count(&self) -> usize {
+        self.branches.len()
+    }
+
+    /// Index of the slack bus (first bus of type Slack, or bus 0)
+    pub fn slack_bus(&self) -> Option<usize> {
+        if self.buses.is_empty() {
+            return None;
+        }
+        Some(
+            self.buses
+                .iter()
+                .position(|b| b.bus_type == BusType::Slack)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Build the topology graph from in-service branches
+    ///
+    /// Node `i` of the graph corresponds to bus `i`.
+    pub fn topology(&self) -> Topology {
+        let mut topology = Topology::new();
+        let nodes: Vec<_> = (0..self.buses.len())
+            .map(|i| topology.add_bus(BusId(i)))
+            .collect();
+
+        for (i, branch) in self.branches.iter().enumerate() {
+            if branch.in_service
+                && branch.from_bus < nodes.len()
+                && branch.to_bus < nodes.len()
+            {
+                topology.add_branch(nodes[branch.from_bus], nodes[branch.to_bus], BranchId(i));
+            }
+        }
+
+        topology
+    }
+
+    /// Voltage setpoint of a bus
+    ///
+    /// Uses the setpoint of the first in-service generator at the bus,
+    /// falling back to the bus voltage magnitude.
+    pub fn voltage_setpoint(&self, bus: usize) -> f64 {
+        self.generators
+            .iter()
+            .find(|g| g.in_service && g.bus == bus)
+            .map(|g| g.voltage_setpoint)
+            .unwrap_or(self.buses[bus].voltage_magnitude)
+    }
+
+    /// Build the initial simulation state
+    ///
+    /// Net injections (MW/MVAr) combine bus injections, generators and loads.
+    /// Slack and PV buses start at their voltage setpoint, PQ buses at the
+    /// bus voltage magnitude.
+    pub fn initial_state(&self) -> StateStore {
+        let mut state = StateStore::new(self.buses.len());
+
+        for (i, bus) in self.buses.iter().enumerate() {
+            state.voltage_magnitude[i] = match bus.bus_type {
+                BusType::Slack | BusType::PV => self.voltage_setpoint(i),
+                BusType::PQ => bus.voltage_magnitude,
+            };
+            state.voltage_angle[i] = bus.voltage_angle;
+            state.active_power[i] = bus.active_power;
+            state.reactive_power[i] = bus.reactive_power;
+        }
+        for generator in &self.generators {
+            generator.apply(&mut state);
+        }
+        for load in &self.loads {
+            load.apply(&mut state);
+        }
+
+        state
+    }
+
+    /// Load demand per bus (MW, MVAr) at the voltages in `state`
+    ///
+    /// Honours each load's voltage dependence, so undervoltage reduces the
+    /// demand of ZIP and exponential loads.
+    pub fn load_demand(&self, state: &StateStore) -> (Vec<f64>, Vec<f64>) {
+        let mut active = vec![0.0; self.buses.len()];
+        let mut reactive = vec![0.0; self.buses.len()];
+        for load in self.loads.iter().filter(|l| l.in_service) {
+            let (p, q) = load.demand_at(state.voltage_magnitude[load.bus]);
+            active[load.bus] += p;
+            reactive[load.bus] += q;
+        }
+        (active, reactive)
+    }
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self::new()
+    }
+}
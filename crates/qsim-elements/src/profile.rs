@@ -0,0 +1,124 @@
+//! Time-series profiles for loads and generators
+
+use serde::{Deserialize, Serialize};
+
+use crate::Network;
+
+/// Element driven by a profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileTarget {
+    /// Load index in the network
+    Load(usize),
+    /// Generator index in the network
+    Generator(usize),
+}
+
+/// How profile values are interpreted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProfileKind {
+    /// Scale the element's base active (and reactive) power
+    #[default]
+    Multiplier,
+    /// Active power in MW; loads keep their power factor
+    Absolute,
+}
+
+/// One value per time step for a load or generator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Driven element
+    pub target: ProfileTarget,
+    /// Interpretation of the values
+    #[serde(default)]
+    pub kind: ProfileKind,
+    /// Value per time step
+    pub values: Vec<f64>,
+}
+
+impl Profile {
+    /// Create a profile
+    pub fn new(target: ProfileTarget, kind: ProfileKind, values: Vec<f64>) -> Self {
+        Self {
+            target,
+            kind,
+            values,
+        }
+    }
+
+    /// Create a multiplier profile
+    pub fn multiplier(target: ProfileTarget, values: Vec<f64>) -> Self {
+        Self::new(target, ProfileKind::Multiplier, values)
+    }
+
+    /// Create an absolute (MW) profile
+    pub fn absolute(target: ProfileTarget, values: Vec<f64>) -> Self {
+        Self::new(target, ProfileKind::Absolute, values)
+    }
+
+    /// Number of time steps
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the profile has no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Whether the target element exists in `network`
+    pub fn is_bound(&self, network: &Network) -> bool {
+        match self.target {
+            ProfileTarget::Load(i) => i < network.loads.len(),
+            ProfileTarget::Generator(i) => i < network.generators.len(),
+        }
+    }
+
+    /// Value at `step`, holding the last value past the end
+    pub fn value(&self, step: usize) -> Option<f64> {
+        self.values
+            .get(step)
+            .or_else(|| self.values.last())
+            .copied()
+    }
+
+    /// Set the target element in `network` to its value at `step`
+    ///
+    /// Values are applied relative to the element's data in `base`; unbound
+    /// targets and empty profiles are ignored.
+    pub fn apply(&self, base: &Network, network: &mut Network, step: usize) {
+        let Some(value) = self.value(step) else {
+            return;
+        };
+        match self.target {
+            ProfileTarget::Load(i) => {
+                let (Some(original), Some(load)) = (base.loads.get(i), network.loads.get_mut(i))
+                else {
+                    return;
+                };
+                let scale = match self.kind {
+                    ProfileKind::Multiplier => value,
+                    ProfileKind::Absolute if original.active_power != 0.0 => {
+                        value / original.active_power
+                    }
+                    ProfileKind::Absolute => {
+                        load.active_power = value;
+                        return;
+                    }
+                };
+                load.active_power = original.active_power * scale;
+                load.reactive_power = original.reactive_power * scale;
+            }
+            ProfileTarget::Generator(i) => {
+                let (Some(original), Some(generator)) =
+                    (base.generators.get(i), network.generators.get_mut(i))
+                else {
+                    return;
+                };
+                generator.active_power = match self.kind {
+                    ProfileKind::Multiplier => original.active_power * value,
+                    ProfileKind::Absolute => value,
+                };
+            }
+        }
+    }
+}
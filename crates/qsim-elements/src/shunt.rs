@@ -0,0 +1,74 @@
+//! Switched shunt element — stepped capacitor and reactor banks
+
+use serde::{Deserialize, Serialize};
+
+/// A shunt bank switched in discrete steps to hold a bus voltage in a band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchedShunt {
+    /// Connected bus index
+    pub bus: usize,
+    /// Reactive power per step at 1.0 p.u. (MVAr, positive = capacitor)
+    pub step_size: f64,
+    /// Number of available steps
+    pub max_steps: usize,
+    /// Steps currently in service
+    pub step: usize,
+    /// Lower edge of the voltage band (per-unit)
+    pub v_low: f64,
+    /// Upper edge of the voltage band (per-unit)
+    pub v_high: f64,
+    /// Shunt status (true = in service)
+    pub in_service: bool,
+}
+
+impl SwitchedShunt {
+    /// Create a switched shunt with all steps out of service
+    pub fn new(bus: usize, step_size: f64, max_steps: usize, v_low: f64, v_high: f64) -> Self {
+        Self {
+            bus,
+            step_size,
+            max_steps,
+            step: 0,
+            v_low,
+            v_high,
+            in_service: true,
+        }
+    }
+
+    /// Susceptance currently switched in (MVAr at 1.0 p.u.)
+    pub fn susceptance(&self) -> f64 {
+        if self.in_service {
+            self.step_size * self.step as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Move one step towards the voltage band, returning whether it moved
+    ///
+    /// Below the band, susceptance is increased (more capacitive); above it,
+    /// susceptance is decreased.
+    pub fn regulate(&mut self, vm: f64) -> bool {
+        if !self.in_service || self.step_size == 0.0 {
+            return false;
+        }
+        let raise = if vm < self.v_low {
+            true
+        } else if vm > self.v_high {
+            false
+        } else {
+            return false;
+        };
+        // Adding a capacitor step raises the voltage; adding a reactor lowers it
+        let add = raise == (self.step_size > 0.0);
+        if add && self.step < self.max_steps {
+            self.step += 1;
+            true
+        } else if !add && self.step > 0 {
+            self.step -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Power system stabilizer models
+//!
+//! Parameters follow the PSS/E PSS2A record in order.
+
+use serde::{Deserialize, Serialize};
+
+/// Input signal of a stabilizer (PSS/E input signal codes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StabilizerInput {
+    /// Rotor speed deviation (code 1)
+    SpeedDeviation,
+    /// Electrical power (code 3)
+    ElectricalPower,
+    /// Any other PSS/E input code, not simulated
+    Other(i32),
+}
+
+impl StabilizerInput {
+    /// Input from a PSS/E signal code
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1 => Self::SpeedDeviation,
+            3 => Self::ElectricalPower,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// IEEE dual-input stabilizer PSS2A
+///
+/// The first input passes washouts TW1, TW2 and lag T6; the second
+/// washouts TW3, TW4 and lag KS2/(1 + sT7). Their combination feeds the
+/// ramp-tracking filter `((1 + sT8)/(1 + sT9)^M)^N`, the gain KS1 and two
+/// lead-lag stages.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pss2a {
+    /// First input signal (ICON J1)
+    pub input1: StabilizerInput,
+    /// Remote bus of the first input, 0 = local (ICON K1)
+    pub remote_bus1: i32,
+    /// Second input signal (ICON J2)
+    pub input2: StabilizerInput,
+    /// Remote bus of the second input, 0 = local (ICON K2)
+    pub remote_bus2: i32,
+    /// Ramp-tracking filter lag order M (ICON)
+    pub m: usize,
+    /// Ramp-tracking filter stages N (ICON)
+    pub n: usize,
+    /// First washout of input 1 (s)
+    pub tw1: f64,
+    /// Second washout of input 1 (s)
+    pub tw2: f64,
+    /// Lag of input 1 (s)
+    pub t6: f64,
+    /// First washout of input 2 (s)
+    pub tw3: f64,
+    /// Second washout of input 2 (s)
+    pub tw4: f64,
+    /// Lag of input 2 (s)
+    pub t7: f64,
+    /// Gain of input 2
+    pub ks2: f64,
+    /// Gain combining input 2 with input 1
+    pub ks3: f64,
+    /// Ramp-tracking filter lead (s)
+    pub t8: f64,
+    /// Ramp-tracking filter lag (s)
+    pub t9: f64,
+    /// Stabilizer gain
+    pub ks1: f64,
+    /// First lead time constant (s)
+    pub t1: f64,
+    /// First lag time constant (s)
+    pub t2: f64,
+    /// Second lead time constant (s)
+    pub t3: f64,
+    /// Second lag time constant (s)
+    pub t4: f64,
+    /// Maximum output
+    pub vstmax: f64,
+    /// Minimum output
+    pub vstmin: f64,
+}
+
+impl Default for Pss2a {
+    fn default() -> Self {
+        Self {
+            input1: StabilizerInput::SpeedDeviation,
+            remote_bus1: 0,
+            input2: StabilizerInput::ElectricalPower,
+            remote_bus2: 0,
+            m: 5,
+            n: 1,
+            tw1: 2.0,
+            tw2: 2.0,
+            t6: 0.0,
+            tw3: 2.0,
+            tw4: 0.0,
+            t7: 2.0,
+            ks2: 0.2,
+            ks3: 1.0,
+            t8: 0.5,
+            t9: 0.1,
+            ks1: 10.0,
+            t1: 0.15,
+            t2: 0.03,
+            t3: 0.15,
+            t4: 0.03,
+            vstmax: 0.1,
+            vstmin: -0.1,
+        }
+    }
+}
+
+/// Power system stabilizer of a synchronous machine
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Stabilizer {
+    /// IEEE dual-input stabilizer PSS2A
+    Pss2a(Pss2a),
+}
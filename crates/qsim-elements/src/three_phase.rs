@@ -0,0 +1,280 @@
+//! Phase-resolved elements for unbalanced distribution networks
+//!
+//! Impedances are per-unit on the system base with phase-to-neutral voltage
+//! bases, so a balanced three-phase element has the same per-unit values as
+//! its positive-sequence equivalent. Per-phase powers are in MW/MVAr.
+
+use serde::{Deserialize, Serialize};
+
+use crate::WindingConnection;
+
+/// Phases present at a bus or on an element, indexed a, b, c
+pub type PhaseSet = [bool; 3];
+
+/// All three phases
+pub const ABC: PhaseSet = [true, true, true];
+
+/// 3×3 phase matrix
+pub type PhaseMatrix = [[f64; 3]; 3];
+
+/// A bus in a phase-resolved network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseBus {
+    /// Phases present at the bus
+    pub phases: PhaseSet,
+    /// Base voltage, line-to-line (kV)
+    pub base_voltage_kv: f64,
+}
+
+impl PhaseBus {
+    /// Create a three-phase bus
+    pub fn new(base_voltage_kv: f64) -> Self {
+        Self::with_phases(ABC, base_voltage_kv)
+    }
+
+    /// Create a bus with a subset of phases
+    pub fn with_phases(phases: PhaseSet, base_voltage_kv: f64) -> Self {
+        Self {
+            phases,
+            base_voltage_kv,
+        }
+    }
+}
+
+/// A line section with mutual coupling between phases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseLine {
+    /// From bus index
+    pub from_bus: usize,
+    /// To bus index
+    pub to_bus: usize,
+    /// Phases carried by the line
+    pub phases: PhaseSet,
+    /// Series resistance matrix (per-unit)
+    pub resistance: PhaseMatrix,
+    /// Series reactance matrix (per-unit)
+    pub reactance: PhaseMatrix,
+    /// Total shunt susceptance matrix (per-unit)
+    pub susceptance: PhaseMatrix,
+    /// Line status (true = in service)
+    pub in_service: bool,
+}
+
+impl PhaseLine {
+    /// Create a line from full 3×3 series impedance matrices
+    pub fn new(
+        from_bus: usize,
+        to_bus: usize,
+        resistance: PhaseMatrix,
+        reactance: PhaseMatrix,
+    ) -> Self {
+        Self {
+            from_bus,
+            to_bus,
+            phases: ABC,
+            resistance,
+            reactance,
+            susceptance: [[0.0; 3]; 3],
+            in_service: true,
+        }
+    }
+
+    /// Create a transposed three-phase line from sequence impedances
+    ///
+    /// `z1` and `z0` are (r, x) pairs; self and mutual terms follow from
+    /// Zs = (Z0 + 2·Z1)/3 and Zm = (Z0 − Z1)/3.
+    pub fn from_sequence(from_bus: usize, to_bus: usize, z1: (f64, f64), z0: (f64, f64)) -> Self {
+        let fill = |s: f64, m: f64| {
+            let mut matrix = [[m; 3]; 3];
+            for (i, row) in matrix.iter_mut().enumerate() {
+                row[i] = s;
+            }
+            matrix
+        };
+        let resistance = fill((z0.0 + 2.0 * z1.0) / 3.0, (z0.0 - z1.0) / 3.0);
+        let reactance = fill((z0.1 + 2.0 * z1.1) / 3.0, (z0.1 - z1.1) / 3.0);
+        Self::new(from_bus, to_bus, resistance, reactance)
+    }
+
+    /// Create a single-phase lateral on `phase` (0 = a, 1 = b, 2 = c)
+    pub fn single_phase(
+        from_bus: usize,
+        to_bus: usize,
+        phase: usize,
+        resistance: f64,
+        reactance: f64,
+    ) -> Self {
+        let mut r = [[0.0; 3]; 3];
+        let mut x = [[0.0; 3]; 3];
+        r[phase][phase] = resistance;
+        x[phase][phase] = reactance;
+        let mut line = Self::new(from_bus, to_bus, r, x);
+        line.phases = [false; 3];
+        line.phases[phase] = true;
+        line
+    }
+}
+
+/// How a phase load is connected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoadConnection {
+    /// Phase to neutral (a, b, c)
+    #[default]
+    Wye,
+    /// Phase to phase (ab, bc, ca)
+    Delta,
+}
+
+/// A constant-power load specified per phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseLoad {
+    /// Connected bus index
+    pub bus: usize,
+    /// Wye (a, b, c) or delta (ab, bc, ca) connection
+    pub connection: LoadConnection,
+    /// Active power per phase or phase pair (MW)
+    pub active_power: [f64; 3],
+    /// Reactive power per phase or phase pair (MVAr)
+    pub reactive_power: [f64; 3],
+    /// Load status (true = in service)
+    pub in_service: bool,
+}
+
+impl PhaseLoad {
+    /// Create a wye-connected load
+    pub fn wye(bus: usize, active_power: [f64; 3], reactive_power: [f64; 3]) -> Self {
+        Self {
+            bus,
+            connection: LoadConnection::Wye,
+            active_power,
+            reactive_power,
+            in_service: true,
+        }
+    }
+
+    /// Create a delta-connected load
+    pub fn delta(bus: usize, active_power: [f64; 3], reactive_power: [f64; 3]) -> Self {
+        Self {
+            connection: LoadConnection::Delta,
+            ..Self::wye(bus, active_power, reactive_power)
+        }
+    }
+
+    /// Total active power (MW)
+    pub fn total_active_power(&self) -> f64 {
+        self.active_power.iter().sum()
+    }
+}
+
+/// A three-phase two-winding transformer bank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseTransformer {
+    /// Primary bus index
+    pub from_bus: usize,
+    /// Secondary bus index
+    pub to_bus: usize,
+    /// Primary winding connection
+    pub from_connection: WindingConnection,
+    /// Secondary winding connection
+    pub to_connection: WindingConnection,
+    /// Leakage resistance (per-unit)
+    pub resistance: f64,
+    /// Leakage reactance (per-unit)
+    pub reactance: f64,
+    /// Off-nominal tap ratio on the primary side
+    pub tap_ratio: f64,
+    /// Transformer status (true = in service)
+    pub in_service: bool,
+}
+
+impl PhaseTransformer {
+    /// Create a transformer bank
+    pub fn new(
+        from_bus: usize,
+        to_bus: usize,
+        from_connection: WindingConnection,
+        to_connection: WindingConnection,
+        resistance: f64,
+        reactance: f64,
+    ) -> Self {
+        Self {
+            from_bus,
+            to_bus,
+            from_connection,
+            to_connection,
+            resistance,
+            reactance,
+            tap_ratio: 1.0,
+            in_service: true,
+        }
+    }
+}
+
+/// A phase-resolved distribution network fed from a balanced source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseNetwork {
+    /// Base MVA (three-phase) for per-unit conversion
+    pub base_mva: f64,
+    /// Bus fed by the balanced source
+    pub source_bus: usize,
+    /// Source voltage magnitude (per-unit)
+    pub source_voltage: f64,
+    /// Buses
+    pub buses: Vec<PhaseBus>,
+    /// Line sections
+    pub lines: Vec<PhaseLine>,
+    /// Transformer banks
+    pub transformers: Vec<PhaseTransformer>,
+    /// Phase loads
+    pub loads: Vec<PhaseLoad>,
+}
+
+impl PhaseNetwork {
+    /// Create an empty network with a 100 MVA base, fed at bus 0
+    pub fn new() -> Self {
+        Self {
+            base_mva: 100.0,
+            source_bus: 0,
+            source_voltage: 1.0,
+            buses: Vec::new(),
+            lines: Vec::new(),
+            transformers: Vec::new(),
+            loads: Vec::new(),
+        }
+    }
+
+    /// Add a bus, returning its index
+    pub fn add_bus(&mut self, bus: PhaseBus) -> usize {
+        self.buses.push(bus);
+        self.buses.len() - 1
+    }
+
+    /// Add a line section, returning its index
+    pub fn add_line(&mut self, line: PhaseLine) -> usize {
+        self.lines.push(line);
+        self.lines.len() - 1
+    }
+
+    /// Add a transformer bank, returning its index
+    pub fn add_transformer(&mut self, transformer: PhaseTransformer) -> usize {
+        self.transformers.push(transformer);
+        self.transformers.len() - 1
+    }
+
+    /// Add a load, returning its index
+    pub fn add_load(&mut self, load: PhaseLoad) -> usize {
+        self.loads.push(load);
+        self.loads.len() - 1
+    }
+
+    /// Number of buses
+    pub fn bus_count(&self) -> usize {
+        self.buses.len()
+    }
+}
+
+impl Default for PhaseNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,91 @@
+//! Three-winding transformer element
+//!
+//! Represented by its star equivalent: a fictitious star bus joined to each
+//! winding bus by a branch carrying that winding's impedance and tap.
+//! Pairwise short-circuit impedances convert to star impedances as
+//!
+//! ```text
+//! Z1 = (Z12 + Z31 − Z23) / 2
+//! Z2 = (Z12 + Z23 − Z31) / 2
+//! Z3 = (Z23 + Z31 − Z12) / 2
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::Branch;
+
+/// A three-winding transformer (primary, secondary, tertiary)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreeWindingTransformer {
+    /// Winding bus indices (primary, secondary, tertiary)
+    pub buses: [usize; 3],
+    /// Star resistance per winding (per-unit)
+    pub resistance: [f64; 3],
+    /// Star reactance per winding (per-unit)
+    pub reactance: [f64; 3],
+    /// Off-nominal tap ratio per winding (on the winding side)
+    pub taps: [f64; 3],
+    /// Rating per winding (MVA, 0.0 = unlimited)
+    #[serde(default)]
+    pub ratings: [f64; 3],
+    /// Fictitious star bus, `None` until the network expands the transformer
+    pub star_bus: Option<usize>,
+    /// Star branches per winding, `None` until the network expands the
+    /// transformer
+    pub branches: Option<[usize; 3]>,
+    /// Transformer status (true = in service)
+    pub in_service: bool,
+}
+
+impl ThreeWindingTransformer {
+    /// Create a transformer from star impedances
+    pub fn new(buses: [usize; 3], resistance: [f64; 3], reactance: [f64; 3]) -> Self {
+        Self {
+            buses,
+            resistance,
+            reactance,
+            taps: [1.0; 3],
+            ratings: [0.0; 3],
+            star_bus: None,
+            branches: None,
+            in_service: true,
+        }
+    }
+
+    /// Create a transformer from pairwise impedances (r, x) between windings
+    pub fn from_pairwise(
+        buses: [usize; 3],
+        z12: (f64, f64),
+        z23: (f64, f64),
+        z31: (f64, f64),
+    ) -> Self {
+        let star = |a: f64, b: f64, opposite: f64| (a + b - opposite) / 2.0;
+        let resistance = [
+            star(z12.0, z31.0, z23.0),
+            star(z12.0, z23.0, z31.0),
+            star(z23.0, z31.0, z12.0),
+        ];
+        let reactance = [
+            star(z12.1, z31.1, z23.1),
+            star(z12.1, z23.1, z31.1),
+            star(z23.1, z31.1, z12.1),
+        ];
+        Self::new(buses, resistance, reactance)
+    }
+
+    /// Star-equivalent branch for `winding` (0 = primary, 1 = secondary, 2 = tertiary)
+    ///
+    /// Runs from the winding bus (tap side) to `star_bus`.
+    pub fn star_branch(&self, winding: usize, star_bus: usize) -> Branch {
+        let mut branch = Branch::transformer(
+            self.buses[winding],
+            star_bus,
+            self.resistance[winding],
+            self.reactance[winding],
+            self.taps[winding],
+        );
+        branch.rating = self.ratings[winding];
+        branch.in_service = self.in_service;
+        branch
+    }
+}
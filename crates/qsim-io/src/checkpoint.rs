@@ -0,0 +1,647 @@
+//! Simulation checkpoints
+//!
+//! A [`Checkpoint`] stores the [`StateStore`] after a completed step along
+//! with the step count, simulation time and a hash of the network, so a run
+//! can be resumed later with identical results. Dynamic runs also keep the
+//! pre-disturbance state, from which [`Checkpoint::resume_stability`]
+//! rebuilds the simulation.
+//!
+//! Checkpoints are written as JSON or as a compact little-endian binary
+//! format. The binary format round-trips every value exactly; JSON
+//! round-trips finite values exactly and cannot hold NaN or infinity, so
+//! [`Checkpoint::to_json`] rejects them.
+
+use std::path::{Path, PathBuf};
+
+use qsim_core::{OutputHandler, StateStore};
+use qsim_elements::Network;
+use qsim_solvers::TransientStability;
+use serde::{Deserialize, Serialize};
+
+use crate::IoError;
+
+/// Current checkpoint format version
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 8] = b"QSIMCKPT";
+
+/// Snapshot of a simulation after a completed step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Format version
+    pub version: u32,
+    /// Number of completed steps, i.e. the step to resume at
+    pub step: usize,
+    /// Time label of the last completed step; for steppers with their own
+    /// clock, the time the state has reached
+    pub time: f64,
+    /// [`network_hash`] of the simulated network
+    pub network_hash: u64,
+    /// Simulation state
+    pub state: StateStore,
+    /// Pre-disturbance state of a dynamic run
+    #[serde(default)]
+    pub initial_state: Option<StateStore>,
+}
+
+impl Checkpoint {
+    /// Create a checkpoint of `state` after `step` completed steps
+    pub fn new(
+        network: &Network,
+        step: usize,
+        time: f64,
+        state: &StateStore,
+    ) -> Result<Self, IoError> {
+        Ok(Self {
+            version: CHECKPOINT_VERSION,
+            step,
+            time,
+            network_hash: network_hash(network)?,
+            state: state.clone(),
+            initial_state: None,
+        })
+    }
+
+    /// Builder: keep the pre-disturbance state of a dynamic run
+    pub fn with_initial_state(mut self, initial: &StateStore) -> Self {
+        self.initial_state = Some(initial.clone());
+        self
+    }
+
+    /// Whether the checkpoint was taken on `network`
+    pub fn matches(&self, network: &Network) -> Result<bool, IoError> {
+        Ok(self.network_hash == network_hash(network)?)
+    }
+
+    /// Restore the state for `network` and return the step to resume at
+    ///
+    /// Pass the step to `TimeSeriesRunner::with_first_step` and to the
+    /// stepper (e.g. `QuasiStaticStepper::with_first_step`).
+    pub fn restore(&self, network: &Network, state: &mut StateStore) -> Result<usize, IoError> {
+        if !self.matches(network)? {
+            return Err(IoError::InvalidData(
+                "Checkpoint was taken on a different network".to_string(),
+            ));
+        }
+        state.clone_from(&self.state);
+        Ok(self.step)
+    }
+
+    /// Rebuild a transient stability run of `network` at this checkpoint
+    ///
+    /// The run must have been checkpointed with its pre-disturbance state.
+    /// Restores `state` and sets the simulation clock to
+    /// [`Checkpoint::time`]; schedule the original events on the returned
+    /// simulation, start any wrapping integrator at the same time, and
+    /// continue from step [`Checkpoint::step`].
+    pub fn resume_stability(
+        &self,
+        network: &Network,
+        state: &mut StateStore,
+    ) -> Result<TransientStability, IoError> {
+        let initial = self.initial_state.as_ref().ok_or_else(|| {
+            IoError::InvalidData("Checkpoint has no pre-disturbance state".to_string())
+        })?;
+        self.restore(network, state)?;
+        let simulation = TransientStability::resume(network, initial, state, self.time)?;
+        Ok(simulation)
+    }
+
+    /// Serialize to JSON; fails if any value is NaN or infinite
+    pub fn to_json(&self) -> Result<String, IoError> {
+        let states = std::iter::once(&self.state).chain(&self.initial_state);
+        if !self.time.is_finite() || !states.flat_map(state_values).all(|v| v.is_finite()) {
+            return Err(IoError::InvalidData(
+                "JSON checkpoints cannot hold NaN or infinite values".to_string(),
+            ));
+        }
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserialize from JSON
+    pub fn from_json(json: &str) -> Result<Self, IoError> {
+        let checkpoint: Self = serde_json::from_str(json)?;
+        checkpoint.check_version()?;
+        Ok(checkpoint)
+    }
+
+    /// Serialize to the binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend_from_slice(&(self.step as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.time.to_le_bytes());
+        bytes.extend_from_slice(&self.network_hash.to_le_bytes());
+        write_state(&mut bytes, &self.state);
+        match &self.initial_state {
+            Some(initial) => {
+                bytes.push(1);
+                write_state(&mut bytes, initial);
+            }
+            None => bytes.push(0),
+        }
+        bytes
+    }
+
+    /// Deserialize from the binary format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IoError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(IoError::InvalidData("Not a qsim checkpoint".to_string()));
+        }
+        let version = u32::from_le_bytes(reader.array()?);
+        let step = reader.u64()? as usize;
+        let time = f64::from_le_bytes(reader.array()?);
+        let network_hash = reader.u64()?;
+        if version > CHECKPOINT_VERSION {
+            return Err(unsupported_version(version));
+        }
+
+        let state = reader.state()?;
+        let initial_state = match reader.take(1)?[0] {
+            0 => None,
+            1 => Some(reader.state()?),
+            _ => {
+                return Err(IoError::InvalidData(
+                    "Invalid initial state marker".to_string(),
+                ))
+            }
+        };
+        if !reader.bytes.is_empty() {
+            return Err(IoError::InvalidData(
+                "Trailing data after checkpoint".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            version,
+            step,
+            time,
+            network_hash,
+            state,
+            initial_state,
+        })
+    }
+
+    /// Save to a file: JSON for a `.json` extension, binary otherwise
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<(), IoError> {
+        let path = path.as_ref();
+        let bytes = if is_json(path) {
+            self.to_json()?.into_bytes()
+        } else {
+            self.to_bytes()
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Load from a file written by [`Checkpoint::to_file`]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, IoError> {
+        let path = path.as_ref();
+        if is_json(path) {
+            Self::from_json(&std::fs::read_to_string(path)?)
+        } else {
+            Self::from_bytes(&std::fs::read(path)?)
+        }
+    }
+
+    fn check_version(&self) -> Result<(), IoError> {
+        if self.version > CHECKPOINT_VERSION {
+            return Err(unsupported_version(self.version));
+        }
+        Ok(())
+    }
+}
+
+fn unsupported_version(version: u32) -> IoError {
+    IoError::InvalidData(format!("Unsupported checkpoint version {version}"))
+}
+
+/// Stable 64-bit hash (FNV-1a) of a network's serialized data
+pub fn network_hash(network: &Network) -> Result<u64, IoError> {
+    let data = serde_json::to_vec(network)?;
+    Ok(data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    }))
+}
+
+/// Every value held by a state
+fn state_values(state: &StateStore) -> impl Iterator<Item = &f64> {
+    state
+        .voltage_magnitude
+        .iter()
+        .chain(&state.voltage_angle)
+        .chain(&state.active_power)
+        .chain(&state.reactive_power)
+        .chain(&state.dynamic_states)
+        .chain(state.phase_voltage_magnitude.as_flattened())
+        .chain(state.phase_voltage_angle.as_flattened())
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+}
+
+fn write_state(bytes: &mut Vec<u8>, state: &StateStore) {
+    for values in [
+        &state.voltage_magnitude,
+        &state.voltage_angle,
+        &state.active_power,
+        &state.reactive_power,
+        &state.dynamic_states,
+    ] {
+        write_values(bytes, values);
+    }
+    for phases in [&state.phase_voltage_magnitude, &state.phase_voltage_angle] {
+        write_values(bytes, phases.as_flattened());
+    }
+}
+
+fn write_values(bytes: &mut Vec<u8>, values: &[f64]) {
+    bytes.extend_from_slice(&(values.len() as u64).to_le_bytes());
+    for value in values {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], IoError> {
+        if self.bytes.len() < len {
+            return Err(IoError::InvalidData("Truncated checkpoint".to_string()));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], IoError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u64(&mut self) -> Result<u64, IoError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn values(&mut self) -> Result<Vec<f64>, IoError> {
+        let len = self.u64()? as usize;
+        if len > self.bytes.len() / 8 {
+            return Err(IoError::InvalidData("Truncated checkpoint".to_string()));
+        }
+        (0..len)
+            .map(|_| Ok(f64::from_le_bytes(self.array()?)))
+            .collect()
+    }
+
+    fn state(&mut self) -> Result<StateStore, IoError> {
+        let mut state = StateStore::new(0);
+        state.voltage_magnitude = self.values()?;
+        state.voltage_angle = self.values()?;
+        state.active_power = self.values()?;
+        state.reactive_power = self.values()?;
+        state.dynamic_states = self.values()?;
+        state.phase_voltage_magnitude = self.phases()?;
+        state.phase_voltage_angle = self.phases()?;
+        Ok(state)
+    }
+
+    fn phases(&mut self) -> Result<Vec<[f64; 3]>, IoError> {
+        let values = self.values()?;
+        if !values.len().is_multiple_of(3) {
+            return Err(IoError::InvalidData(
+                "Per-phase values are not a multiple of three".to_string(),
+            ));
+        }
+        Ok(values.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect())
+    }
+}
+
+/// [`OutputHandler`] that writes a checkpoint file every `interval` steps
+///
+/// Each checkpoint overwrites the previous one; a final checkpoint is
+/// written on completion. Write errors are kept and reported by
+/// [`CheckpointWriter::finish`].
+#[derive(Debug)]
+pub struct CheckpointWriter {
+    /// Checkpoint file (JSON for a `.json` extension, binary otherwise)
+    pub path: PathBuf,
+    /// Steps between checkpoints
+    pub interval: usize,
+    network_hash: u64,
+    initial_state: Option<StateStore>,
+    last: Option<(usize, f64)>,
+    error: Option<IoError>,
+}
+
+impl CheckpointWriter {
+    /// Checkpoint a simulation of `network` to `path` every `interval` steps
+    pub fn new(
+        path: impl Into<PathBuf>,
+        network: &Network,
+        interval: usize,
+    ) -> Result<Self, IoError> {
+        Ok(Self {
+            path: path.into(),
+            interval: interval.max(1),
+            network_hash: network_hash(network)?,
+            initial_state: None,
+            last: None,
+            error: None,
+        })
+    }
+
+    /// Builder: store the pre-disturbance state of a dynamic run
+    pub fn with_initial_state(mut self, initial: &StateStore) -> Self {
+        self.initial_state = Some(initial.clone());
+        self
+    }
+
+    /// Return the first write error, if any
+    pub fn finish(self) -> Result<(), IoError> {
+        self.error.map_or(Ok(()), Err)
+    }
+
+    fn write(&mut self, step: usize, time: f64, state: &StateStore) {
+        if self.error.is_some() {
+            return;
+        }
+        let checkpoint = Checkpoint {
+            version: CHECKPOINT_VERSION,
+            step,
+            time,
+            network_hash: self.network_hash,
+            state: state.clone(),
+            initial_state: self.initial_state.clone(),
+        };
+        self.error = checkpoint.to_file(&self.path).err();
+    }
+}
+
+impl OutputHandler for CheckpointWriter {
+    fn on_step(&mut self, step: usize, time: f64, state: &StateStore) {
+        let completed = step + 1;
+        self.last = Some((completed, time));
+        if completed.is_multiple_of(self.interval) {
+            self.write(completed, time, state);
+        }
+    }
+
+    fn on_complete(&mut self, state: &StateStore) {
+        if let Some((completed, time)) = self.last {
+            if !completed.is_multiple_of(self.interval) {
+                self.write(completed, time, state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_core::TimeSeriesRunner;
+    use qsim_elements::{
+        Branch, Bus, Exciter, Generator, Load, MachineDynamics, Profile, ProfileTarget, St1a,
+    };
+    use qsim_solvers::{
+        AcPowerFlowSolver, DisturbanceEvent, NetworkSolver, QuasiStaticStepper, RungeKutta4,
+    };
+
+    fn network() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.01, 0.1));
+        network.add_load(Load::new(1, 40.0, 10.0));
+        network
+    }
+
+    fn checkpoint() -> Checkpoint {
+        let mut state = StateStore::with_phases(2);
+        state.voltage_magnitude = vec![1.0, 0.1 + 0.2];
+        state.voltage_angle = vec![0.0, -0.123_456_789_012_345_6];
+        state.active_power = vec![40.4, -40.0];
+        state.dynamic_states = vec![1.0 / 3.0, 2.0e-17];
+        Checkpoint::new(&network(), 7, 0.35, &state).unwrap()
+    }
+
+    /// Unique file in the temporary directory, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("qsim-{}-{name}", std::process::id())))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let checkpoint = checkpoint().with_initial_state(&StateStore::new(2));
+        let json = checkpoint.to_json().unwrap();
+        assert_eq!(Checkpoint::from_json(&json).unwrap(), checkpoint);
+
+        let mut invalid = checkpoint.clone();
+        invalid.state.dynamic_states[0] = f64::NAN;
+        assert!(invalid.to_json().is_err());
+        let mut invalid = checkpoint;
+        invalid.initial_state.as_mut().unwrap().voltage_angle[1] = f64::INFINITY;
+        assert!(invalid.to_json().is_err());
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut checkpoint = checkpoint();
+        checkpoint.state.reactive_power = vec![f64::NAN, f64::NEG_INFINITY];
+        checkpoint.state.phase_voltage_angle[1] = [f64::INFINITY, -0.0, 1e-300];
+        let restored = Checkpoint::from_bytes(&checkpoint.to_bytes()).unwrap();
+        let bits = |c: &Checkpoint| {
+            state_values(&c.state)
+                .map(|v| v.to_bits())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bits(&restored), bits(&checkpoint));
+        assert_eq!(restored.step, 7);
+        assert_eq!(restored.initial_state, None);
+
+        let checkpoint = self::checkpoint().with_initial_state(&StateStore::new(2));
+        assert_eq!(
+            Checkpoint::from_bytes(&checkpoint.to_bytes()).unwrap(),
+            checkpoint
+        );
+    }
+
+    #[test]
+    fn test_truncated_or_corrupt_input_is_rejected() {
+        let bytes = checkpoint()
+            .with_initial_state(&StateStore::new(2))
+            .to_bytes();
+        for len in 0..bytes.len() {
+            assert!(Checkpoint::from_bytes(&bytes[..len]).is_err());
+        }
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(Checkpoint::from_bytes(&trailing).is_err());
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        assert!(Checkpoint::from_bytes(&magic).is_err());
+        // Element count far beyond the remaining bytes
+        let mut count = bytes;
+        count[36..44].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(Checkpoint::from_bytes(&count).is_err());
+
+        let json = checkpoint().to_json().unwrap();
+        assert!(Checkpoint::from_json(&json[..json.len() / 2]).is_err());
+        assert!(Checkpoint::from_json(&json.replace("\"step\"", "\"stop\"")).is_err());
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut checkpoint = checkpoint();
+        checkpoint.version = 99;
+        assert!(Checkpoint::from_json(&serde_json::to_string(&checkpoint).unwrap()).is_err());
+        assert!(Checkpoint::from_bytes(&checkpoint.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_restore_checks_network() {
+        let checkpoint = checkpoint();
+        let mut state = StateStore::new(2);
+        assert_eq!(checkpoint.restore(&network(), &mut state).unwrap(), 7);
+        assert_eq!(state, checkpoint.state);
+
+        let mut other = network();
+        other.loads[0].active_power = 41.0;
+        let mut state = StateStore::new(2);
+        assert!(checkpoint.restore(&other, &mut state).is_err());
+        assert_eq!(state, StateStore::new(2));
+        assert!(checkpoint.resume_stability(&network(), &mut state).is_err());
+    }
+
+    #[test]
+    fn test_quasi_static_resume_from_file() {
+        let network = network();
+        let profiles = vec![Profile::multiplier(
+            ProfileTarget::Load(0),
+            vec![0.6, 0.9, 1.2, 0.8, 1.1, 1.3],
+        )];
+        let stepper = |first| {
+            QuasiStaticStepper::new(AcPowerFlowSolver::new(), network.clone(), profiles.clone())
+                .unwrap()
+                .with_first_step(first)
+        };
+
+        let mut full = network.initial_state();
+        TimeSeriesRunner::new(1.0, 6)
+            .run(&mut stepper(0), &mut full, &mut [])
+            .unwrap();
+
+        // Interrupted after four steps, checkpointed every three
+        let file = TempFile::new("quasi-static.json");
+        let mut writer = CheckpointWriter::new(&file.0, &network, 3).unwrap();
+        let mut partial = network.initial_state();
+        TimeSeriesRunner::new(1.0, 4)
+            .run(&mut stepper(0), &mut partial, &mut [&mut writer])
+            .unwrap();
+        writer.finish().unwrap();
+
+        let checkpoint = Checkpoint::from_file(&file.0).unwrap();
+        assert_eq!(checkpoint.time, 3.0);
+        let mut resumed = StateStore::new(0);
+        let step = checkpoint.restore(&network, &mut resumed).unwrap();
+        assert_eq!(step, 4);
+        assert_eq!(resumed, partial);
+        TimeSeriesRunner::new(1.0, 6)
+            .with_first_step(step)
+            .run(&mut stepper(step), &mut resumed, &mut [])
+            .unwrap();
+        assert_eq!(resumed, full);
+    }
+
+    #[test]
+    fn test_stability_resume_from_file() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.4));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.4));
+        network.add_generator(
+            Generator::new(0, 0.0, 1.0).with_dynamics(MachineDynamics::classical(1e6, 0.0, 1e-4)),
+        );
+        network.add_generator(
+            Generator::new(1, 80.0, 1.0).with_dynamics(
+                MachineDynamics::two_axis(5.0, 2.0, 1.8, 1.7, 0.3, 0.55)
+                    .with_exciter(Exciter::St1a(St1a::default())),
+            ),
+        );
+        let mut initial = network.initial_state();
+        AcPowerFlowSolver::new()
+            .solve_network(&network, &mut initial)
+            .unwrap();
+        let events = [
+            DisturbanceEvent::bus_fault(0.05, 1),
+            DisturbanceEvent::clear_fault(0.15, 1),
+            DisturbanceEvent::branch_trip(0.15, 0),
+        ];
+        let simulation = |state: &mut StateStore| {
+            RungeKutta4::new(
+                TransientStability::new(&network, state)
+                    .unwrap()
+                    .with_events(events),
+            )
+        };
+        let dt = 0.005;
+
+        let mut full = initial.clone();
+        let mut uninterrupted = simulation(&mut full);
+        TimeSeriesRunner::new(dt, 100)
+            .run(&mut uninterrupted, &mut full, &mut [])
+            .unwrap();
+
+        let file = TempFile::new("stability.ckpt");
+        let mut writer = CheckpointWriter::new(&file.0, &network, 25)
+            .unwrap()
+            .with_initial_state(&initial);
+        let mut partial = initial.clone();
+        let mut interrupted = simulation(&mut partial);
+        TimeSeriesRunner::new(dt, 40)
+            .run(&mut interrupted, &mut partial, &mut [&mut writer])
+            .unwrap();
+        writer.finish().unwrap();
+
+        // The checkpoint holds the time the state has reached
+        let checkpoint = Checkpoint::from_file(&file.0).unwrap();
+        assert_eq!(checkpoint.step, 40);
+        assert!((checkpoint.time - 40.0 * dt).abs() < 1e-12);
+        let mut resumed = StateStore::new(0);
+        let mut simulation = RungeKutta4::new(
+            checkpoint
+                .resume_stability(&network, &mut resumed)
+                .unwrap()
+                .with_events(events),
+        )
+        .with_start_time(checkpoint.time);
+        TimeSeriesRunner::new(dt, 100)
+            .with_first_step(checkpoint.step)
+            .run(&mut simulation, &mut resumed, &mut [])
+            .unwrap();
+
+        assert!((simulation.time() - uninterrupted.time()).abs() < 1e-9);
+        for (resumed, reference) in resumed.dynamic_states.iter().zip(&full.dynamic_states) {
+            assert!((resumed - reference).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,978 @@
+//! PSS/E dynamic data (`.dyr`) files
+//!
+//! Each record names a bus number, a model and a machine id, followed by
+//! the model's ICONs and CONs, and ends with `/`; text after the `/` on the
+//! same line is a comment:
+//!
+//! ```text
+//! 101 'GENROU' 1  6.5 0.05 0.2 0.05  4.0 0.0  1.8 1.75 0.3 0.55 0.25 0.15  0.1 0.3 /
+//! 101 'ESST1A' 1  1 1  0.0 999 -999 1.0 1.0 0.0 0.0 210 0.0 999 -999 6.43 -6.0 0.038 0.0 1.0 4.54 4.4 /
+//! ```
+//!
+//! Supported records become [`MachineDynamics`] on the matching generator:
+//!
+//! - machines: GENCLS, GENROU, GENSAL (subtransient data is dropped)
+//! - exciters: ESST1A, ESAC1A, ESDC1A, IEEET1
+//! - governors: TGOV1, HYGOV, IEEEG1
+//! - stabilizers: PSS2A with speed and electrical power inputs
+//!
+//! Other models, and records that cannot be attached, are listed in the
+//! [`DyrReport`] instead of failing the import.
+
+use std::collections::HashMap;
+
+use qsim_elements::{
+    Ac1a, Dc1a, Exciter, Governor, Hygov, Ieeeg1, MachineDynamics, MachineModel, Network, Pss2a,
+    Saturation, St1a, Stabilizer, StabilizerInput, Tgov1,
+};
+
+use crate::IoError;
+
+/// One record of a DYR file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DyrRecord {
+    /// PSS/E bus number
+    pub bus: u32,
+    /// Model name, upper case
+    pub model: String,
+    /// Machine id
+    pub id: String,
+    /// Remaining fields (ICONs, then CONs) as written
+    pub fields: Vec<String>,
+}
+
+impl DyrRecord {
+    /// Fields parsed as numbers
+    pub fn parameters(&self) -> Result<Vec<f64>, IoError> {
+        self.fields
+            .iter()
+            .map(|field| {
+                field.parse().map_err(|_| {
+                    IoError::InvalidData(format!(
+                        "{} at bus {}: invalid number '{field}'",
+                        self.model, self.bus
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Fields parsed as numbers, checking their count
+    fn expect_parameters(&self, count: usize) -> Result<Vec<f64>, IoError> {
+        let parameters = self.parameters()?;
+        if parameters.len() < count {
+            return Err(IoError::InvalidData(format!(
+                "{} at bus {} needs {count} parameters, found {}",
+                self.model,
+                self.bus,
+                parameters.len()
+            )));
+        }
+        Ok(parameters)
+    }
+}
+
+/// Generators by PSS/E bus number and machine id
+#[derive(Debug, Clone, Default)]
+pub struct MachineMap {
+    machines: HashMap<(u32, String), usize>,
+}
+
+impl MachineMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number buses from 1 in network order and machines "1", "2", ... per bus
+    pub fn sequential(network: &Network) -> Self {
+        let mut map = Self::new();
+        let mut per_bus = HashMap::new();
+        for (index, generator) in network.generators.iter().enumerate() {
+            let count = per_bus.entry(generator.bus).or_insert(0);
+            *count += 1;
+            map.insert(generator.bus as u32 + 1, &count.to_string(), index);
+        }
+        map
+    }
+
+    /// Map bus number and machine id to a generator index
+    pub fn insert(&mut self, bus: u32, id: &str, generator: usize) {
+        self.machines.insert((bus, normalize_id(id)), generator);
+    }
+
+    /// Builder: map bus number and machine id to a generator index
+    pub fn with_machine(mut self, bus: u32, id: &str, generator: usize) -> Self {
+        self.insert(bus, id, generator);
+        self
+    }
+
+    /// Generator index of a machine
+    pub fn get(&self, bus: u32, id: &str) -> Option<usize> {
+        self.machines.get(&(bus, normalize_id(id))).copied()
+    }
+}
+
+fn normalize_id(id: &str) -> String {
+    id.trim().to_uppercase()
+}
+
+/// A record that was not applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRecord {
+    /// PSS/E bus number
+    pub bus: u32,
+    /// Machine id
+    pub id: String,
+    /// Model name
+    pub model: String,
+    /// Why the record was skipped
+    pub reason: String,
+}
+
+/// Outcome of applying a DYR file to a network
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DyrReport {
+    /// Number of records applied
+    pub applied: usize,
+    /// Records of models qsim does not simulate
+    pub unsupported: Vec<SkippedRecord>,
+    /// Supported records that could not be attached to a generator
+    pub unmatched: Vec<SkippedRecord>,
+}
+
+impl DyrReport {
+    /// Whether every record was applied
+    pub fn is_complete(&self) -> bool {
+        self.unsupported.is_empty() && self.unmatched.is_empty()
+    }
+
+    /// Names of the unsupported models, sorted and without repeats
+    pub fn unsupported_models(&self) -> Vec<String> {
+        let mut models: Vec<String> = self.unsupported.iter().map(|r| r.model.clone()).collect();
+        models.sort();
+        models.dedup();
+        models
+    }
+
+    fn skip(list: &mut Vec<SkippedRecord>, record: &DyrRecord, reason: impl Into<String>) {
+        list.push(SkippedRecord {
+            bus: record.bus,
+            id: record.id.clone(),
+            model: record.model.clone(),
+            reason: reason.into(),
+        });
+    }
+}
+
+/// Controller slot a record fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Machine,
+    Exciter,
+    Governor,
+    Stabilizer,
+}
+
+fn category(model: &str) -> Option<Category> {
+    match model {
+        "GENCLS" | "GENROU" | "GENSAL" => Some(Category::Machine),
+        "ESST1A" | "ESAC1A" | "ESDC1A" | "IEEET1" => Some(Category::Exciter),
+        "TGOV1" | "HYGOV" | "IEEEG1" => Some(Category::Governor),
+        "PSS2A" => Some(Category::Stabilizer),
+        _ => None,
+    }
+}
+
+/// Contents of a PSS/E dynamic data file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DyrFile {
+    /// Records in file order
+    pub records: Vec<DyrRecord>,
+}
+
+impl DyrFile {
+    /// Parse DYR text
+    pub fn parse(text: &str) -> Result<Self, IoError> {
+        let mut records = Vec::new();
+        let mut tokens: Vec<String> = Vec::new();
+        let mut token = String::new();
+        let mut quoted = false;
+        let mut comment = false;
+        for c in text.chars() {
+            if comment {
+                comment = c != '\n';
+                continue;
+            }
+            if quoted {
+                if c == '\'' {
+                    quoted = false;
+                } else {
+                    token.push(c);
+                }
+                continue;
+            }
+            match c {
+                '\'' => quoted = true,
+                '/' => {
+                    push_token(&mut tokens, &mut token);
+                    if !tokens.is_empty() {
+                        records.push(parse_record(std::mem::take(&mut tokens))?);
+                    }
+                    comment = true;
+                }
+                c if c.is_whitespace() || c == ',' => push_token(&mut tokens, &mut token),
+                c => token.push(c),
+            }
+        }
+        push_token(&mut tokens, &mut token);
+        if !tokens.is_empty() {
+            return Err(IoError::InvalidData(format!(
+                "Unterminated DYR record starting with '{}'",
+                tokens[0]
+            )));
+        }
+        Ok(Self { records })
+    }
+
+    /// Read a DYR file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, IoError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Attach the supported models to the generators of `network`
+    ///
+    /// Machine records are applied first and replace existing dynamics;
+    /// controllers then attach to machines by bus number and id. Malformed
+    /// parameters of a supported model are an error, and leave `network`
+    /// unchanged.
+    pub fn apply(
+        &self,
+        network: &mut Network,
+        machines: &MachineMap,
+    ) -> Result<DyrReport, IoError> {
+        let mut report = DyrReport::default();
+        // Work on a copy so a malformed record leaves the network untouched
+        let mut generators = network.generators.clone();
+        for pass in [
+            Category::Machine,
+            Category::Exciter,
+            Category::Governor,
+            Category::Stabilizer,
+        ] {
+            for record in &self.records {
+                let Some(kind) = category(&record.model) else {
+                    if pass == Category::Machine {
+                        DyrReport::skip(&mut report.unsupported, record, "Model not supported");
+                    }
+                    continue;
+                };
+                if kind != pass {
+                    continue;
+                }
+                let Some(index) = machines.get(record.bus, &record.id) else {
+                    DyrReport::skip(&mut report.unmatched, record, "No matching generator");
+                    continue;
+                };
+                let Some(generator) = generators.get_mut(index) else {
+                    DyrReport::skip(
+                        &mut report.unmatched,
+                        record,
+                        "Generator index out of range",
+                    );
+                    continue;
+                };
+
+                if kind == Category::Machine {
+                    match machine_dynamics(record, generator.subtransient_reactance)? {
+                        Ok(dynamics) => {
+                            generator.dynamics = Some(dynamics);
+                            report.applied += 1;
+                        }
+                        Err(reason) => DyrReport::skip(&mut report.unmatched, record, reason),
+                    }
+                    continue;
+                }
+                let Some(dynamics) = generator.dynamics.as_mut() else {
+                    DyrReport::skip(
+                        &mut report.unmatched,
+                        record,
+                        "Generator has no machine model",
+                    );
+                    continue;
+                };
+                match kind {
+                    Category::Exciter if dynamics.model != MachineModel::TwoAxis => {
+                        let reason = "Exciter needs a GENROU or GENSAL machine";
+                        DyrReport::skip(&mut report.unmatched, record, reason);
+                        continue;
+                    }
+                    Category::Exciter => dynamics.exciter = Some(exciter(record)?),
+                    Category::Governor => dynamics.governor = Some(governor(record)?),
+                    _ if dynamics.exciter.is_none() => {
+                        let reason = "Stabilizer needs an exciter";
+                        DyrReport::skip(&mut report.unmatched, record, reason);
+                        continue;
+                    }
+                    _ => match stabilizer(record)? {
+                        Ok(model) => dynamics.stabilizer = Some(model),
+                        Err(reason) => {
+                            DyrReport::skip(&mut report.unsupported, record, reason);
+                            continue;
+                        }
+                    },
+                }
+                report.applied += 1;
+            }
+        }
+        network.generators = generators;
+        Ok(report)
+    }
+}
+
+fn push_token(tokens: &mut Vec<String>, token: &mut String) {
+    if !token.is_empty() {
+        tokens.push(std::mem::take(token));
+    }
+}
+
+fn parse_record(tokens: Vec<String>) -> Result<DyrRecord, IoError> {
+    if tokens.len() < 3 {
+        return Err(IoError::InvalidData(format!(
+            "DYR record '{}' needs a bus, model and id",
+            tokens.join(" ")
+        )));
+    }
+    let mut tokens = tokens.into_iter();
+    let bus = tokens.next().unwrap_or_default();
+    let bus = bus
+        .parse()
+        .map_err(|_| IoError::InvalidData(format!("Invalid DYR bus number '{bus}'")))?;
+    let model = tokens.next().unwrap_or_default().trim().to_uppercase();
+    let id = tokens.next().unwrap_or_default().trim().to_string();
+    Ok(DyrRecord {
+        bus,
+        model,
+        id,
+        fields: tokens.collect(),
+    })
+}
+
+/// Machine data, or the reason it cannot be used
+fn machine_dynamics(
+    record: &DyrRecord,
+    source_reactance: f64,
+) -> Result<Result<MachineDynamics, String>, IoError> {
+    let dynamics = match record.model.as_str() {
+        "GENCLS" => {
+            let p = record.expect_parameters(2)?;
+            if source_reactance <= 0.0 {
+                return Ok(Err(
+                    "GENCLS needs the generator subtransient reactance".to_string()
+                ));
+            }
+            MachineDynamics::classical(p[0], p[1], source_reactance)
+        }
+        "GENROU" => {
+            let p = record.expect_parameters(14)?;
+            MachineDynamics::two_axis(p[4], p[5], p[6], p[7], p[8], p[9])
+                .with_time_constants(p[0], p[2])
+        }
+        _ => {
+            // GENSAL has no q-axis transient circuit: X'q = Xq
+            let p = record.expect_parameters(12)?;
+            MachineDynamics::two_axis(p[3], p[4], p[5], p[6], p[7], p[6])
+                .with_time_constants(p[0], p[2])
+        }
+    };
+    Ok(Ok(dynamics))
+}
+
+fn exciter(record: &DyrRecord) -> Result<Exciter, IoError> {
+    let p = record.expect_parameters(count_for(&record.model))?;
+    Ok(match record.model.as_str() {
+        "ESST1A" => Exciter::St1a(St1a {
+            uel: p[0] as i32,
+            vos: p[1] as i32,
+            tr: p[2],
+            vimax: p[3],
+            vimin: p[4],
+            tc: p[5],
+            tb: p[6],
+            tc1: p[7],
+            tb1: p[8],
+            ka: p[9],
+            ta: p[10],
+            vamax: p[11],
+            vamin: p[12],
+            vrmax: p[13],
+            vrmin: p[14],
+            kc: p[15],
+            kf: p[16],
+            tf: p[17],
+            klr: p[18],
+            ilr: p[19],
+        }),
+        "ESAC1A" => Exciter::Ac1a(Ac1a {
+            tr: p[0],
+            tb: p[1],
+            tc: p[2],
+            ka: p[3],
+            ta: p[4],
+            vamax: p[5],
+            vamin: p[6],
+            te: p[7],
+            kf: p[8],
+            tf: p[9],
+            kc: p[10],
+            kd: p[11],
+            ke: p[12],
+            saturation: Saturation::new(p[13], p[14], p[15], p[16]),
+            vrmax: p[17],
+            vrmin: p[18],
+        }),
+        "ESDC1A" => Exciter::Dc1a(Dc1a {
+            tr: p[0],
+            ka: p[1],
+            ta: p[2],
+            tb: p[3],
+            tc: p[4],
+            vrmax: p[5],
+            vrmin: p[6],
+            ke: p[7],
+            te: p[8],
+            kf: p[9],
+            tf: p[10],
+            saturation: Saturation::new(p[12], p[13], p[14], p[15]),
+        }),
+        // IEEET1 is DC1A without the lead-lag
+        _ => Exciter::Dc1a(Dc1a {
+            tr: p[0],
+            ka: p[1],
+            ta: p[2],
+            tb: 0.0,
+            tc: 0.0,
+            vrmax: p[3],
+            vrmin: p[4],
+            ke: p[5],
+            te: p[6],
+            kf: p[7],
+            tf: p[8],
+            saturation: Saturation::new(p[10], p[11], p[12], p[13]),
+        }),
+    })
+}
+
+fn governor(record: &DyrRecord) -> Result<Governor, IoError> {
+    let p = record.expect_parameters(count_for(&record.model))?;
+    Ok(match record.model.as_str() {
+        "TGOV1" => Governor::Tgov1(Tgov1 {
+            r: p[0],
+            t1: p[1],
+            vmax: p[2],
+            vmin: p[3],
+            t2: p[4],
+            t3: p[5],
+            dt: p[6],
+        }),
+        "HYGOV" => Governor::Hygov(Hygov {
+            r: p[0],
+            temporary_droop: p[1],
+            tr: p[2],
+            tf: p[3],
+            tg: p[4],
+            velm: p[5],
+            gmax: p[6],
+            gmin: p[7],
+            tw: p[8],
+            at: p[9],
+            dturb: p[10],
+            qnl: p[11],
+        }),
+        _ => Governor::Ieeeg1(Ieeeg1 {
+            jbus: p[0] as i32,
+            m: p[1] as i32,
+            k: p[2],
+            t1: p[3],
+            t2: p[4],
+            t3: p[5],
+            uo: p[6],
+            uc: p[7],
+            pmax: p[8],
+            pmin: p[9],
+            t4: p[10],
+            k1: p[11],
+            k2: p[12],
+            t5: p[13],
+            k3: p[14],
+            k4: p[15],
+            t6: p[16],
+            k5: p[17],
+            k6: p[18],
+            t7: p[19],
+            k7: p[20],
+            k8: p[21],
+        }),
+    })
+}
+
+/// Stabilizer, or the reason its inputs are not supported
+fn stabilizer(record: &DyrRecord) -> Result<Result<Stabilizer, String>, IoError> {
+    let p = record.expect_parameters(count_for(&record.model))?;
+    let pss = Pss2a {
+        input1: StabilizerInput::from_code(p[0] as i32),
+        remote_bus1: p[1] as i32,
+        input2: StabilizerInput::from_code(p[2] as i32),
+        remote_bus2: p[3] as i32,
+        m: p[4].max(0.0) as usize,
+        n: p[5].max(0.0) as usize,
+        tw1: p[6],
+        tw2: p[7],
+        t6: p[8],
+        tw3: p[9],
+        tw4: p[10],
+        t7: p[11],
+        ks2: p[12],
+        ks3: p[13],
+        t8: p[14],
+        t9: p[15],
+        ks1: p[16],
+        t1: p[17],
+        t2: p[18],
+        t3: p[19],
+        t4: p[20],
+        vstmax: p[21],
+        vstmin: p[22],
+    };
+    for input in [pss.input1, pss.input2] {
+        if let StabilizerInput::Other(code) = input {
+            return Ok(Err(format!("PSS2A input signal {code} not supported")));
+        }
+    }
+    if pss.remote_bus1 != 0 || pss.remote_bus2 != 0 {
+        return Ok(Err("PSS2A remote bus inputs not supported".to_string()));
+    }
+    Ok(Ok(Stabilizer::Pss2a(pss)))
+}
+
+/// Number of ICONs and CONs of a supported controller model
+fn count_for(model: &str) -> usize {
+    match model {
+        "ESST1A" => 20,
+        "ESAC1A" => 19,
+        "ESDC1A" => 16,
+        "IEEET1" => 14,
+        "TGOV1" => 7,
+        "HYGOV" => 12,
+        "IEEEG1" => 22,
+        _ => 23,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_elements::{Bus, Generator};
+
+    /// The example in the module documentation
+    const EXAMPLE: &str = "\
+101 'GENROU' 1  6.5 0.05 0.2 0.05  4.0 0.0  1.8 1.75 0.3 0.55 0.25 0.15  0.1 0.3 /
+101 'ESST1A' 1  1 1  0.0 999 -999 1.0 1.0 0.0 0.0 210 0.0 999 -999 6.43 -6.0 0.038 0.0 1.0 4.54 4.4 /
+";
+
+    fn network() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_generator(Generator::new(0, 0.0, 1.0));
+        network.add_generator(Generator::new(1, 50.0, 1.0));
+        network
+    }
+
+    /// Apply `text` with sequentially numbered machines
+    fn apply(text: &str, network: &mut Network) -> DyrReport {
+        let machines = MachineMap::sequential(network);
+        DyrFile::parse(text)
+            .unwrap()
+            .apply(network, &machines)
+            .unwrap()
+    }
+
+    /// Record of `model` whose fields are numbered 1, 2, 3, ...
+    fn numbered(model: &str, count: usize) -> DyrRecord {
+        DyrRecord {
+            bus: 1,
+            model: model.to_string(),
+            id: "1".to_string(),
+            fields: (1..=count).map(|i| i.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_module_example() {
+        let file = DyrFile::parse(EXAMPLE).unwrap();
+        assert_eq!(file.records.len(), 2);
+        assert_eq!(file.records[0].bus, 101);
+        assert_eq!(file.records[1].model, "ESST1A");
+
+        let mut network = network();
+        let machines = MachineMap::new().with_machine(101, "1", 1);
+        let report = file.apply(&mut network, &machines).unwrap();
+        assert_eq!(report.applied, 2);
+        assert!(report.is_complete());
+
+        let dynamics = network.generators[1].dynamics.unwrap();
+        assert_eq!(dynamics.model, MachineModel::TwoAxis);
+        assert_eq!(dynamics.td0_prime, 6.5);
+        assert_eq!(dynamics.tq0_prime, 0.2);
+        assert_eq!(dynamics.inertia, 4.0);
+        assert_eq!(dynamics.damping, 0.0);
+        assert_eq!((dynamics.xd, dynamics.xq), (1.8, 1.75));
+        assert_eq!((dynamics.xd_prime, dynamics.xq_prime), (0.3, 0.55));
+        // The example holds the IEEE sample data
+        assert_eq!(dynamics.exciter, Some(Exciter::St1a(St1a::default())));
+        assert_eq!(dynamics.governor, None);
+        assert!(network.generators[0].dynamics.is_none());
+    }
+
+    #[test]
+    fn test_parse_syntax() {
+        let text = "\
+1 'gencls' 'G 1'  3.0, 0.5 / comment with 'quotes' and 1 2 3
+  2,'TGOV1',2,0.05,0.5,1.0,
+  0.0,3.0,10.0,0.0/
+/ a comment line
+";
+        let file = DyrFile::parse(text).unwrap();
+        assert_eq!(
+            file.records,
+            vec![
+                DyrRecord {
+                    bus: 1,
+                    model: "GENCLS".to_string(),
+                    id: "G 1".to_string(),
+                    fields: vec!["3.0".to_string(), "0.5".to_string()],
+                },
+                DyrRecord {
+                    bus: 2,
+                    model: "TGOV1".to_string(),
+                    id: "2".to_string(),
+                    fields: ["0.05", "0.5", "1.0", "0.0", "3.0", "10.0", "0.0"]
+                        .map(String::from)
+                        .to_vec(),
+                },
+            ]
+        );
+        assert_eq!(file.records[0].parameters().unwrap(), vec![3.0, 0.5]);
+        assert_eq!(DyrFile::parse("").unwrap(), DyrFile::default());
+
+        assert!(DyrFile::parse("1 'GENCLS' 1 3.0 0.5").is_err());
+        assert!(DyrFile::parse(&format!("{EXAMPLE}101 'GENCLS' 1 3.0")).is_err());
+        assert!(DyrFile::parse("1 'GENCLS' /").is_err());
+        assert!(DyrFile::parse("BUS 'GENCLS' 1 3.0 0.5 /").is_err());
+        let file = DyrFile::parse("1 'GENCLS' 1 3.0 x /").unwrap();
+        assert!(file.records[0].parameters().is_err());
+    }
+
+    #[test]
+    fn test_machine_records() {
+        let text = "\
+1 'GENCLS' 1  3.0 0.5 /
+2 'GENSAL' 1  5.0 0.05 0.1  3.5 0.2  1.2 0.8 0.35 0.25 0.1 0.1 0.2 /
+";
+        let mut network = network();
+        network.generators[0].subtransient_reactance = 0.2;
+        let report = apply(text, &mut network);
+        assert_eq!(report.applied, 2);
+
+        let classical = network.generators[0].dynamics.unwrap();
+        assert_eq!(classical, MachineDynamics::classical(3.0, 0.5, 0.2));
+        // GENSAL: X'q = Xq
+        let salient = network.generators[1].dynamics.unwrap();
+        assert_eq!(
+            salient,
+            MachineDynamics::two_axis(3.5, 0.2, 1.2, 0.8, 0.35, 0.8).with_time_constants(5.0, 0.1)
+        );
+
+        // GENCLS needs the subtransient reactance
+        let mut network = self::network();
+        let report = apply(text, &mut network);
+        assert_eq!(report.applied, 1);
+        assert_eq!(report.unmatched[0].model, "GENCLS");
+        assert!(network.generators[0].dynamics.is_none());
+    }
+
+    #[test]
+    fn test_exciter_con_mapping() {
+        let ac1a = exciter(&numbered("ESAC1A", 19)).unwrap();
+        assert_eq!(
+            ac1a,
+            Exciter::Ac1a(Ac1a {
+                tr: 1.0,
+                tb: 2.0,
+                tc: 3.0,
+                ka: 4.0,
+                ta: 5.0,
+                vamax: 6.0,
+                vamin: 7.0,
+                te: 8.0,
+                kf: 9.0,
+                tf: 10.0,
+                kc: 11.0,
+                kd: 12.0,
+                ke: 13.0,
+                saturation: Saturation::new(14.0, 15.0, 16.0, 17.0),
+                vrmax: 18.0,
+                vrmin: 19.0,
+            })
+        );
+
+        // CON 12 (the switch) is not used
+        let dc1a = exciter(&numbered("ESDC1A", 16)).unwrap();
+        assert_eq!(
+            dc1a,
+            Exciter::Dc1a(Dc1a {
+                tr: 1.0,
+                ka: 2.0,
+                ta: 3.0,
+                tb: 4.0,
+                tc: 5.0,
+                vrmax: 6.0,
+                vrmin: 7.0,
+                ke: 8.0,
+                te: 9.0,
+                kf: 10.0,
+                tf: 11.0,
+                saturation: Saturation::new(13.0, 14.0, 15.0, 16.0),
+            })
+        );
+
+        // IEEET1: no lead-lag and CON 10 (the switch) is not used
+        let ieeet1 = exciter(&numbered("IEEET1", 14)).unwrap();
+        assert_eq!(
+            ieeet1,
+            Exciter::Dc1a(Dc1a {
+                tr: 1.0,
+                ka: 2.0,
+                ta: 3.0,
+                tb: 0.0,
+                tc: 0.0,
+                vrmax: 4.0,
+                vrmin: 5.0,
+                ke: 6.0,
+                te: 7.0,
+                kf: 8.0,
+                tf: 9.0,
+                saturation: Saturation::new(11.0, 12.0, 13.0, 14.0),
+            })
+        );
+
+        let Exciter::St1a(st1a) = exciter(&numbered("ESST1A", 20)).unwrap() else {
+            panic!("ESST1A must map to St1a");
+        };
+        assert_eq!((st1a.uel, st1a.vos, st1a.tr), (1, 2, 3.0));
+        assert_eq!((st1a.ka, st1a.ta, st1a.kc), (10.0, 11.0, 16.0));
+        assert_eq!((st1a.klr, st1a.ilr), (19.0, 20.0));
+
+        assert!(exciter(&numbered("ESAC1A", 18)).is_err());
+        assert!(exciter(&numbered("IEEET1", 13)).is_err());
+    }
+
+    #[test]
+    fn test_governor_con_mapping() {
+        assert_eq!(
+            governor(&numbered("TGOV1", 7)).unwrap(),
+            Governor::Tgov1(Tgov1 {
+                r: 1.0,
+                t1: 2.0,
+                vmax: 3.0,
+                vmin: 4.0,
+                t2: 5.0,
+                t3: 6.0,
+                dt: 7.0,
+            })
+        );
+        assert_eq!(
+            governor(&numbered("HYGOV", 12)).unwrap(),
+            Governor::Hygov(Hygov {
+                r: 1.0,
+                temporary_droop: 2.0,
+                tr: 3.0,
+                tf: 4.0,
+                tg: 5.0,
+                velm: 6.0,
+                gmax: 7.0,
+                gmin: 8.0,
+                tw: 9.0,
+                at: 10.0,
+                dturb: 11.0,
+                qnl: 12.0,
+            })
+        );
+        assert_eq!(
+            governor(&numbered("IEEEG1", 22)).unwrap(),
+            Governor::Ieeeg1(Ieeeg1 {
+                jbus: 1,
+                m: 2,
+                k: 3.0,
+                t1: 4.0,
+                t2: 5.0,
+                t3: 6.0,
+                uo: 7.0,
+                uc: 8.0,
+                pmax: 9.0,
+                pmin: 10.0,
+                t4: 11.0,
+                k1: 12.0,
+                k2: 13.0,
+                t5: 14.0,
+                k3: 15.0,
+                k4: 16.0,
+                t6: 17.0,
+                k5: 18.0,
+                k6: 19.0,
+                t7: 20.0,
+                k7: 21.0,
+                k8: 22.0,
+            })
+        );
+        assert!(governor(&numbered("IEEEG1", 21)).is_err());
+    }
+
+    #[test]
+    fn test_stabilizer_con_mapping() {
+        let mut record = numbered("PSS2A", 23);
+        // Speed and electrical power inputs, both local
+        for (index, value) in [(0, "1"), (1, "0"), (2, "3"), (3, "0")] {
+            record.fields[index] = value.to_string();
+        }
+        assert_eq!(
+            stabilizer(&record).unwrap(),
+            Ok(Stabilizer::Pss2a(Pss2a {
+                input1: StabilizerInput::SpeedDeviation,
+                remote_bus1: 0,
+                input2: StabilizerInput::ElectricalPower,
+                remote_bus2: 0,
+                m: 5,
+                n: 6,
+                tw1: 7.0,
+                tw2: 8.0,
+                t6: 9.0,
+                tw3: 10.0,
+                tw4: 11.0,
+                t7: 12.0,
+                ks2: 13.0,
+                ks3: 14.0,
+                t8: 15.0,
+                t9: 16.0,
+                ks1: 17.0,
+                t1: 18.0,
+                t2: 19.0,
+                t3: 20.0,
+                t4: 21.0,
+                vstmax: 22.0,
+                vstmin: 23.0,
+            }))
+        );
+
+        record.fields[2] = "2".to_string();
+        assert!(stabilizer(&record).unwrap().is_err());
+        record.fields[2] = "3".to_string();
+        record.fields[1] = "101".to_string();
+        assert!(stabilizer(&record).unwrap().is_err());
+        assert!(stabilizer(&numbered("PSS2A", 22)).is_err());
+    }
+
+    #[test]
+    fn test_sequential_machine_map() {
+        let mut network = network();
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_generator(Generator::new(2, 10.0, 1.0));
+        network.add_generator(Generator::new(2, 10.0, 1.0));
+        let map = MachineMap::sequential(&network);
+
+        assert_eq!(map.get(1, "1"), Some(0));
+        assert_eq!(map.get(2, "1"), Some(1));
+        assert_eq!(map.get(3, "1"), Some(2));
+        assert_eq!(map.get(3, " 2 "), Some(3));
+        assert_eq!(map.get(3, "3"), None);
+        assert_eq!(map.get(0, "1"), None);
+
+        // Ids are trimmed and case-insensitive
+        let map = MachineMap::new().with_machine(5, "g1", 0);
+        assert_eq!(map.get(5, "G1 "), Some(0));
+    }
+
+    #[test]
+    fn test_report_lists_skipped_records() {
+        let text = "\
+1 'GENROU' 1  6.5 0.05 0.2 0.05  4.0 0.0  1.8 1.75 0.3 0.55 0.25 0.15  0.1 0.3 /
+1 'REGCA1' 1  1 2 3 /
+1 'GENTPJ' 1  1 2 3 /
+2 'GENTPJ' 1  1 2 3 /
+1 'PSS2A' 1  1 0 3 0 5 1 2 2 0 2 0 2 0.2 1 0.5 0.1 10 0.15 0.03 0.15 0.03 0.1 -0.1 /
+1 'TGOV1' 1  0.05 0.5 1.0 0.0 3.0 10.0 0.0 /
+2 'TGOV1' 1  0.05 0.5 1.0 0.0 3.0 10.0 0.0 /
+3 'TGOV1' 1  0.05 0.5 1.0 0.0 3.0 10.0 0.0 /
+9 'ESST1A' 1  1 1 0 999 -999 1 1 0 0 210 0 999 -999 6.43 -6 0.038 0 1 4.54 4.4 /
+";
+        let mut network = network();
+        let machines = MachineMap::sequential(&network).with_machine(3, "1", 7);
+        let report = DyrFile::parse(text)
+            .unwrap()
+            .apply(&mut network, &machines)
+            .unwrap();
+
+        assert_eq!(report.applied, 2);
+        assert!(!report.is_complete());
+        assert_eq!(report.unsupported_models(), vec!["GENTPJ", "REGCA1"]);
+        assert_eq!(report.unsupported.len(), 3);
+        let unmatched: Vec<(u32, &str, &str)> = report
+            .unmatched
+            .iter()
+            .map(|r| (r.bus, r.model.as_str(), r.reason.as_str()))
+            .collect();
+        assert_eq!(
+            unmatched,
+            vec![
+                (9, "ESST1A", "No matching generator"),
+                (2, "TGOV1", "Generator has no machine model"),
+                (3, "TGOV1", "Generator index out of range"),
+                (1, "PSS2A", "Stabilizer needs an exciter"),
+            ]
+        );
+        let dynamics = network.generators[0].dynamics.unwrap();
+        assert!(dynamics.governor.is_some());
+        assert!(dynamics.stabilizer.is_none());
+
+        // An exciter needs a two-axis machine
+        let mut network = self::network();
+        network.generators[0].subtransient_reactance = 0.2;
+        let text = "\
+1 'GENCLS' 1  3.0 0.5 /
+1 'ESST1A' 1  1 1 0 999 -999 1 1 0 0 210 0 999 -999 6.43 -6 0.038 0 1 4.54 4.4 /
+";
+        let report = apply(text, &mut network);
+        assert_eq!(report.applied, 1);
+        assert_eq!(
+            report.unmatched[0].reason,
+            "Exciter needs a GENROU or GENSAL machine"
+        );
+
+        // Malformed parameters of a supported model fail the import
+        let text = "1 'GENROU' 1  6.5 0.05 0.2 /";
+        let mut network = self::network();
+        let machines = MachineMap::sequential(&network);
+        let file = DyrFile::parse(text).unwrap();
+        assert!(file.apply(&mut network, &machines).is_err());
+
+        // ... and leave the network as it was, even after earlier records
+        let text = "\
+1 'GENROU' 1  6.5 0.05 0.2 0.05  4.0 0.0  1.8 1.75 0.3 0.55 0.25 0.15  0.1 0.3 /
+1 'TGOV1' 1  0.05 0.5 /
+";
+        let file = DyrFile::parse(text).unwrap();
+        assert!(file.apply(&mut network, &machines).is_err());
+        assert!(network.generators[0].dynamics.is_none());
+    }
+}
@@ -13,4 +13,7 @@ pub enum IoError {
 
     #[error("Invalid data: {0}")]
     InvalidData(String),
+
+    #[error("Simulation error: {0}")]
+    SimulationError(#[from] qsim_core::CoreError),
 }
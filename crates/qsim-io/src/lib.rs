@@ -7,9 +7,19 @@
 //! - Load/save networks from JSON
 //! - Checkpoint simulation state
 //! - Export results to JSON/CSV
+//! - Read load/generation profiles from CSV/JSON
+//! - Read PSS/E `.dyr` dynamic data onto generators
 
 mod network;
 mod error;
+mod checkpoint;
+mod output;
+mod profile;
+mod dyr;
 
 pub use network::*;
 pub use error::*;
+pub use checkpoint::*;
+pub use output::*;
+pub use profile::*;
+pub use dyr::*;
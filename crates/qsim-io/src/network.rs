@@ -1,8 +1,12 @@
 //! Network serialization
 
-use qsim_elements::{Branch, Bus, Generator, Load};
+use qsim_elements::{
+    Branch, Bus, Generator, HvdcLink, Load, Network, SwitchedShunt, ThreeWindingTransformer,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::IoError;
+
 /// Network definition for JSON serialization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkData {
@@ -22,6 +26,19 @@ pub struct NetworkData {
     /// Loads
     #[serde(default)]
     pub loads: Vec<Load>,
+    /// Switched shunts
+    #[serde(default)]
+    pub switched_shunts: Vec<SwitchedShunt>,
+    /// Three-winding transformers
+    ///
+    /// Leave out `star_bus` and `branches` to have [`NetworkData::to_network`]
+    /// add the star bus and branches; when given, they must name the star
+    /// bus and the branches above that run from each winding bus to it.
+    #[serde(default)]
+    pub three_winding_transformers: Vec<ThreeWindingTransformer>,
+    /// HVDC links
+    #[serde(default)]
+    pub hvdc_links: Vec<HvdcLink>,
 }
 
 fn default_base_mva() -> f64 {
@@ -38,6 +55,9 @@ impl NetworkData {
             branches: Vec::new(),
             generators: Vec::new(),
             loads: Vec::new(),
+            switched_shunts: Vec::new(),
+            three_winding_transformers: Vec::new(),
+            hvdc_links: Vec::new(),
         }
     }
 
@@ -51,6 +71,27 @@ impl NetworkData {
         serde_json::to_string_pretty(self)
     }
 
+    /// Build a solver-ready network from this definition
+    ///
+    /// Three-winding transformers are expanded into their star equivalents
+    /// and their indices validated.
+    pub fn to_network(&self) -> Result<Network, IoError> {
+        let mut network = Network {
+            base_mva: self.base_mva,
+            buses: self.buses.clone(),
+            branches: self.branches.clone(),
+            generators: self.generators.clone(),
+            loads: self.loads.clone(),
+            switched_shunts: self.switched_shunts.clone(),
+            three_winding_transformers: self.three_winding_transformers.clone(),
+            hvdc_links: self.hvdc_links.clone(),
+        };
+        network
+            .expand_three_winding_transformers()
+            .map_err(|e| IoError::InvalidData(e.to_string()))?;
+        Ok(network)
+    }
+
     /// Load network from file
     pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, std::io::Error> {
         let json = std::fs::read_to_string(path)?;
@@ -70,3 +111,68 @@ impl Default for NetworkData {
         Self::new("Unnamed Network")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> NetworkData {
+        let mut data = NetworkData::new("Three-winding");
+        data.buses = vec![
+            Bus::slack(1.0),
+            Bus::pq(-50.0, -20.0),
+            Bus::pq(-25.0, -10.0),
+        ];
+        data.three_winding_transformers = vec![ThreeWindingTransformer::from_pairwise(
+            [0, 1, 2],
+            (0.01, 0.1),
+            (0.02, 0.15),
+            (0.012, 0.12),
+        )];
+        data
+    }
+
+    #[test]
+    fn test_three_winding_transformer_is_expanded() {
+        // The star bus and branches are not written out
+        let mut json: serde_json::Value = serde_json::to_value(data()).unwrap();
+        let transformer = json["three_winding_transformers"][0]
+            .as_object_mut()
+            .unwrap();
+        transformer.remove("star_bus");
+        transformer.remove("branches");
+        let data = NetworkData::from_json(&json.to_string()).unwrap();
+        assert_eq!(data.three_winding_transformers[0].star_bus, None);
+
+        let network = data.to_network().unwrap();
+        assert_eq!(network.bus_count(), 4);
+        assert_eq!(network.branch_count(), 3);
+        let transformer = &network.three_winding_transformers[0];
+        assert_eq!(transformer.star_bus, Some(3));
+        assert_eq!(transformer.branches, Some([0, 1, 2]));
+        assert!(network.branches.iter().all(|b| b.to_bus == 3));
+
+        // An expanded network round-trips without another star
+        let mut saved = data.clone();
+        saved.buses = network.buses.clone();
+        saved.branches = network.branches.clone();
+        saved.three_winding_transformers = network.three_winding_transformers.clone();
+        let reloaded = NetworkData::from_json(&saved.to_json().unwrap()).unwrap();
+        assert_eq!(reloaded.to_network().unwrap().bus_count(), 4);
+    }
+
+    #[test]
+    fn test_invalid_three_winding_transformer_is_rejected() {
+        let mut data = data();
+        data.three_winding_transformers[0].buses[2] = 7;
+        assert!(data.to_network().is_err());
+
+        // Star indices must name a star bus and branches reaching it
+        let mut data = self::data();
+        data.three_winding_transformers[0].star_bus = Some(0);
+        data.three_winding_transformers[0].branches = Some([0, 1, 2]);
+        assert!(data.to_network().is_err());
+        data.three_winding_transformers[0].branches = None;
+        assert!(data.to_network().is_err());
+    }
+}
@@ -0,0 +1,639 @@
+//! Result exporters implementing [`OutputHandler`]
+//!
+//! All handlers record the quantities chosen by an [`OutputSelection`] as
+//! named columns: bus quantities as `vm:<bus>`, `va:<bus>`, `p:<bus>` and
+//! `q:<bus>`, branch flows as `p_from:<branch>`, `q_from:<branch>`,
+//! `p_to:<branch>`, `q_to:<branch>` and `losses:<branch>`.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use qsim_core::{OutputHandler, StateStore};
+use qsim_elements::Network;
+use qsim_solvers::ac_branch_flows;
+
+use crate::IoError;
+
+/// Bus quantity taken from the [`StateStore`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusQuantity {
+    /// Voltage magnitude (per-unit)
+    VoltageMagnitude,
+    /// Voltage angle (radians)
+    VoltageAngle,
+    /// Active power injection (MW)
+    ActivePower,
+    /// Reactive power injection (MVAr)
+    ReactivePower,
+}
+
+impl BusQuantity {
+    /// All bus quantities
+    pub const ALL: [BusQuantity; 4] = [
+        BusQuantity::VoltageMagnitude,
+        BusQuantity::VoltageAngle,
+        BusQuantity::ActivePower,
+        BusQuantity::ReactivePower,
+    ];
+
+    /// Column name prefix
+    pub fn name(&self) -> &'static str {
+        match self {
+            BusQuantity::VoltageMagnitude => "vm",
+            BusQuantity::VoltageAngle => "va",
+            BusQuantity::ActivePower => "p",
+            BusQuantity::ReactivePower => "q",
+        }
+    }
+
+    fn values<'a>(&self, state: &'a StateStore) -> &'a [f64] {
+        match self {
+            BusQuantity::VoltageMagnitude => &state.voltage_magnitude,
+            BusQuantity::VoltageAngle => &state.voltage_angle,
+            BusQuantity::ActivePower => &state.active_power,
+            BusQuantity::ReactivePower => &state.reactive_power,
+        }
+    }
+}
+
+/// Branch flow quantity computed from the bus voltages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchQuantity {
+    /// Active power at the from end (MW)
+    ActivePowerFrom,
+    /// Reactive power at the from end (MVAr)
+    ReactivePowerFrom,
+    /// Active power at the to end (MW)
+    ActivePowerTo,
+    /// Reactive power at the to end (MVAr)
+    ReactivePowerTo,
+    /// Active power losses (MW)
+    Losses,
+}
+
+impl BranchQuantity {
+    /// All branch quantities
+    pub const ALL: [BranchQuantity; 5] = [
+        BranchQuantity::ActivePowerFrom,
+        BranchQuantity::ReactivePowerFrom,
+        BranchQuantity::ActivePowerTo,
+        BranchQuantity::ReactivePowerTo,
+        BranchQuantity::Losses,
+    ];
+
+    /// Column name prefix
+    pub fn name(&self) -> &'static str {
+        match self {
+            BranchQuantity::ActivePowerFrom => "p_from",
+            BranchQuantity::ReactivePowerFrom => "q_from",
+            BranchQuantity::ActivePowerTo => "p_to",
+            BranchQuantity::ReactivePowerTo => "q_to",
+            BranchQuantity::Losses => "losses",
+        }
+    }
+}
+
+/// Quantities, buses and branches recorded by an output handler
+#[derive(Debug, Clone)]
+pub struct OutputSelection {
+    /// Recorded bus quantities
+    pub bus_quantities: Vec<BusQuantity>,
+    /// Recorded buses (None = all)
+    pub buses: Option<Vec<usize>>,
+    /// Recorded branch quantities (requires a network)
+    pub branch_quantities: Vec<BranchQuantity>,
+    /// Recorded branches (None = all)
+    pub branches: Option<Vec<usize>>,
+    /// Network used to compute branch flows
+    pub network: Option<Network>,
+}
+
+impl OutputSelection {
+    /// Record all bus quantities at all buses and no branch flows
+    pub fn new() -> Self {
+        Self {
+            bus_quantities: BusQuantity::ALL.to_vec(),
+            buses: None,
+            branch_quantities: Vec::new(),
+            branches: None,
+            network: None,
+        }
+    }
+
+    /// Builder: set the recorded bus quantities
+    pub fn with_bus_quantities(mut self, quantities: &[BusQuantity]) -> Self {
+        self.bus_quantities = quantities.to_vec();
+        self
+    }
+
+    /// Builder: record only these buses
+    pub fn with_buses(mut self, buses: &[usize]) -> Self {
+        self.buses = Some(buses.to_vec());
+        self
+    }
+
+    /// Builder: record AC branch flows computed on `network`
+    ///
+    /// The flows use this copy of the network throughout, so a
+    /// [`TimeSeriesRunner`](qsim_core::TimeSeriesRunner) rejects the
+    /// selection for steppers that change the network (e.g. branch trips).
+    pub fn with_branch_flows(mut self, network: Network, quantities: &[BranchQuantity]) -> Self {
+        self.network = Some(network);
+        self.branch_quantities = quantities.to_vec();
+        self
+    }
+
+    /// Builder: record only these branches
+    pub fn with_branches(mut self, branches: &[usize]) -> Self {
+        self.branches = Some(branches.to_vec());
+        self
+    }
+
+    /// Whether branch flows are recorded on the stored network
+    pub fn records_branch_flows(&self) -> bool {
+        self.network.is_some() && !self.branch_quantities.is_empty()
+    }
+
+    fn bus_indices(&self, state: &StateStore) -> Vec<usize> {
+        match &self.buses {
+            Some(buses) => buses.clone(),
+            None => (0..state.bus_count()).collect(),
+        }
+    }
+
+    fn branch_indices(&self) -> Vec<usize> {
+        match (&self.branches, &self.network) {
+            (_, None) => Vec::new(),
+            (Some(branches), Some(_)) => branches.clone(),
+            (None, Some(network)) => (0..network.branches.len()).collect(),
+        }
+    }
+
+    /// Column names for a state
+    pub fn columns(&self, state: &StateStore) -> Vec<String> {
+        let buses = self.bus_indices(state);
+        let branches = self.branch_indices();
+        let bus_columns = self
+            .bus_quantities
+            .iter()
+            .flat_map(|q| buses.iter().map(move |b| format!("{}:{b}", q.name())));
+        let branch_columns = self
+            .branch_quantities
+            .iter()
+            .flat_map(|q| branches.iter().map(move |b| format!("{}:{b}", q.name())));
+        bus_columns.chain(branch_columns).collect()
+    }
+
+    /// Values of the columns for a state; missing buses or branches give NaN
+    pub fn sample(&self, state: &StateStore) -> Vec<f64> {
+        let buses = self.bus_indices(state);
+        let mut values = Vec::new();
+        for quantity in &self.bus_quantities {
+            let source = quantity.values(state);
+            values.extend(
+                buses
+                    .iter()
+                    .map(|&b| source.get(b).copied().unwrap_or(f64::NAN)),
+            );
+        }
+
+        if let (false, Some(network)) = (self.branch_quantities.is_empty(), &self.network) {
+            let flows = ac_branch_flows(network, state);
+            let branches = self.branch_indices();
+            for quantity in &self.branch_quantities {
+                values.extend(branches.iter().map(|&b| {
+                    flows.get(b).map_or(f64::NAN, |f| match quantity {
+                        BranchQuantity::ActivePowerFrom => f.p_from,
+                        BranchQuantity::ReactivePowerFrom => f.q_from,
+                        BranchQuantity::ActivePowerTo => f.p_to,
+                        BranchQuantity::ReactivePowerTo => f.q_to,
+                        BranchQuantity::Losses => f.losses(),
+                    })
+                }));
+            }
+        }
+        values
+    }
+}
+
+impl Default for OutputSelection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Streams one CSV row per step: `step,time,<columns>`
+///
+/// Write errors cannot be returned from [`OutputHandler::on_step`]; the
+/// first one is kept and reported by [`CsvOutput::finish`].
+#[derive(Debug)]
+pub struct CsvOutput<W: Write + Send + Sync> {
+    writer: W,
+    selection: OutputSelection,
+    header_written: bool,
+    error: Option<IoError>,
+}
+
+impl<W: Write + Send + Sync> CsvOutput<W> {
+    /// Write to `writer`
+    pub fn new(writer: W, selection: OutputSelection) -> Self {
+        Self {
+            writer,
+            selection,
+            header_written: false,
+            error: None,
+        }
+    }
+
+    /// Flush and return the writer, or the first write error
+    pub fn finish(mut self) -> Result<W, IoError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_step(&mut self, step: usize, time: f64, state: &StateStore) -> Result<(), IoError> {
+        if !self.header_written {
+            let columns = self.selection.columns(state);
+            writeln!(self.writer, "step,time,{}", columns.join(","))?;
+            self.header_written = true;
+        }
+        write!(self.writer, "{step},{time}")?;
+        for value in self.selection.sample(state) {
+            write!(self.writer, ",{value}")?;
+        }
+        writeln!(self.writer)?;
+        Ok(())
+    }
+}
+
+impl CsvOutput<BufWriter<File>> {
+    /// Create (or truncate) a CSV file
+    pub fn to_file(
+        path: impl AsRef<std::path::Path>,
+        selection: OutputSelection,
+    ) -> Result<Self, IoError> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), selection))
+    }
+}
+
+impl<W: Write + Send + Sync> OutputHandler for CsvOutput<W> {
+    fn on_step(&mut self, step: usize, time: f64, state: &StateStore) {
+        if self.error.is_none() {
+            self.error = self.write_step(step, time, state).err();
+        }
+    }
+
+    fn on_complete(&mut self, _state: &StateStore) {
+        if self.error.is_none() {
+            self.error = self.writer.flush().err().map(IoError::from);
+        }
+    }
+
+    fn uses_fixed_network(&self) -> bool {
+        self.selection.records_branch_flows()
+    }
+}
+
+/// Streams one JSON object per line: `{"step":…,"time":…,"<column>":…}`
+///
+/// Non-finite values are written as `null`. Write errors are kept and
+/// reported by [`JsonLinesOutput::finish`].
+#[derive(Debug)]
+pub struct JsonLinesOutput<W: Write + Send + Sync> {
+    writer: W,
+    selection: OutputSelection,
+    columns: Vec<String>,
+    error: Option<IoError>,
+}
+
+impl<W: Write + Send + Sync> JsonLinesOutput<W> {
+    /// Write to `writer`
+    pub fn new(writer: W, selection: OutputSelection) -> Self {
+        Self {
+            writer,
+            selection,
+            columns: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Flush and return the writer, or the first write error
+    pub fn finish(mut self) -> Result<W, IoError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_step(&mut self, step: usize, time: f64, state: &StateStore) -> Result<(), IoError> {
+        if self.columns.is_empty() {
+            self.columns = self.selection.columns(state);
+        }
+        write!(
+            self.writer,
+            "{{\"step\":{step},\"time\":{}",
+            serde_json::Value::from(time)
+        )?;
+        for (column, value) in self.columns.iter().zip(self.selection.sample(state)) {
+            write!(
+                self.writer,
+                ",{}:{}",
+                serde_json::to_string(column)?,
+                serde_json::Value::from(value)
+            )?;
+        }
+        writeln!(self.writer, "}}")?;
+        Ok(())
+    }
+}
+
+impl JsonLinesOutput<BufWriter<File>> {
+    /// Create (or truncate) a JSON Lines file
+    pub fn to_file(
+        path: impl AsRef<std::path::Path>,
+        selection: OutputSelection,
+    ) -> Result<Self, IoError> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), selection))
+    }
+}
+
+impl<W: Write + Send + Sync> OutputHandler for JsonLinesOutput<W> {
+    fn on_step(&mut self, step: usize, time: f64, state: &StateStore) {
+        if self.error.is_none() {
+            self.error = self.write_step(step, time, state).err();
+        }
+    }
+
+    fn on_complete(&mut self, _state: &StateStore) {
+        if self.error.is_none() {
+            self.error = self.writer.flush().err().map(IoError::from);
+        }
+    }
+
+    fn uses_fixed_network(&self) -> bool {
+        self.selection.records_branch_flows()
+    }
+}
+
+/// Keeps the recorded columns in memory, one vector per column
+#[derive(Debug, Clone, Default)]
+pub struct MemoryRecorder {
+    /// Recorded quantities
+    pub selection: OutputSelection,
+    /// Column names
+    pub columns: Vec<String>,
+    /// Step numbers
+    pub steps: Vec<usize>,
+    /// Simulation times
+    pub times: Vec<f64>,
+    /// Values, indexed by column then step
+    pub data: Vec<Vec<f64>>,
+    /// Whether the simulation completed
+    pub completed: bool,
+}
+
+impl MemoryRecorder {
+    /// Create a recorder
+    pub fn new(selection: OutputSelection) -> Self {
+        Self {
+            selection,
+            ..Default::default()
+        }
+    }
+
+    /// Number of recorded steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether no step has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Values of a column by name, e.g. `"vm:3"`
+    pub fn column(&self, name: &str) -> Option<&[f64]> {
+        let index = self.columns.iter().position(|c| c == name)?;
+        Some(&self.data[index])
+    }
+
+    /// Time series of a bus quantity
+    pub fn bus_series(&self, quantity: BusQuantity, bus: usize) -> Option<&[f64]> {
+        self.column(&format!("{}:{bus}", quantity.name()))
+    }
+
+    /// Time series of a branch quantity
+    pub fn branch_series(&self, quantity: BranchQuantity, branch: usize) -> Option<&[f64]> {
+        self.column(&format!("{}:{branch}", quantity.name()))
+    }
+}
+
+impl OutputHandler for MemoryRecorder {
+    fn on_step(&mut self, step: usize, time: f64, state: &StateStore) {
+        if self.columns.is_empty() {
+            self.columns = self.selection.columns(state);
+            self.data = vec![Vec::new(); self.columns.len()];
+        }
+        self.steps.push(step);
+        self.times.push(time);
+        for (column, value) in self.data.iter_mut().zip(self.selection.sample(state)) {
+            column.push(value);
+        }
+    }
+
+    fn on_complete(&mut self, _state: &StateStore) {
+        self.completed = true;
+    }
+
+    fn uses_fixed_network(&self) -> bool {
+        self.selection.records_branch_flows()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_core::{TimeSeriesRunner, TimeStepper};
+    use qsim_elements::{Branch, Bus};
+
+    /// Stepper that keeps the state but claims to switch branches
+    struct Switching;
+
+    impl TimeStepper for Switching {
+        fn step(&mut self, _state: &mut StateStore, _dt: f64) -> qsim_core::Result<()> {
+            Ok(())
+        }
+
+        fn changes_network(&self) -> bool {
+            true
+        }
+    }
+
+    /// Writer that fails on every write, or only on flush
+    #[derive(Debug)]
+    struct FailingWriter {
+        fail_writes: bool,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.fail_writes {
+                Err(std::io::Error::other("disk full"))
+            } else {
+                Ok(buf.len())
+            }
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Err(std::io::Error::other("flush failed"))
+        }
+    }
+
+    fn state() -> StateStore {
+        let mut state = StateStore::new(2);
+        state.voltage_magnitude = vec![1.0, 0.5];
+        state.active_power = vec![50.0, -50.0];
+        state
+    }
+
+    /// Lossless line with X = 0.5: at V = (1.0, 0.5) it carries
+    /// Q_from = 100 MVAr and Q_to = −50 MVAr
+    fn network() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.5));
+        network
+    }
+
+    #[test]
+    fn test_csv_bus_filter_and_missing_bus() {
+        let selection = OutputSelection::new()
+            .with_bus_quantities(&[BusQuantity::VoltageMagnitude, BusQuantity::ActivePower])
+            .with_buses(&[1, 5]);
+        let mut output = CsvOutput::new(Vec::new(), selection);
+        output.on_step(0, 0.0, &state());
+        output.on_step(1, 0.5, &StateStore::new(2));
+        output.on_complete(&state());
+
+        let text = String::from_utf8(output.finish().unwrap()).unwrap();
+        assert_eq!(
+            text,
+            "step,time,vm:1,vm:5,p:1,p:5\n\
+             0,0,0.5,NaN,-50,NaN\n\
+             1,0.5,1,NaN,0,NaN\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_branch_filter() {
+        let selection = OutputSelection::new()
+            .with_bus_quantities(&[])
+            .with_branch_flows(
+                network(),
+                &[
+                    BranchQuantity::ReactivePowerFrom,
+                    BranchQuantity::ReactivePowerTo,
+                ],
+            )
+            .with_branches(&[0, 2]);
+        let mut output = CsvOutput::new(Vec::new(), selection);
+        output.on_step(3, 1.5, &state());
+
+        let text = String::from_utf8(output.finish().unwrap()).unwrap();
+        assert_eq!(
+            text,
+            "step,time,q_from:0,q_from:2,q_to:0,q_to:2\n\
+             3,1.5,100,NaN,-50,NaN\n"
+        );
+    }
+
+    #[test]
+    fn test_json_lines_non_finite_values_are_null() {
+        let selection = OutputSelection::new()
+            .with_bus_quantities(&[BusQuantity::VoltageMagnitude, BusQuantity::ReactivePower])
+            .with_buses(&[0, 4]);
+        let mut state = state();
+        state.reactive_power[0] = f64::INFINITY;
+        let mut output = JsonLinesOutput::new(Vec::new(), selection);
+        output.on_step(0, 0.0, &state);
+        output.on_step(1, 0.25, &StateStore::new(2));
+
+        let text = String::from_utf8(output.finish().unwrap()).unwrap();
+        assert_eq!(
+            text,
+            "{\"step\":0,\"time\":0.0,\"vm:0\":1.0,\"vm:4\":null,\"q:0\":null,\"q:4\":null}\n\
+             {\"step\":1,\"time\":0.25,\"vm:0\":1.0,\"vm:4\":null,\"q:0\":0.0,\"q:4\":null}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_errors_are_reported_by_finish() {
+        let mut csv = CsvOutput::new(FailingWriter { fail_writes: true }, OutputSelection::new());
+        csv.on_step(0, 0.0, &state());
+        csv.on_step(1, 0.1, &state());
+        csv.on_complete(&state());
+        let error = csv.finish().unwrap_err();
+        assert!(error.to_string().contains("disk full"), "{error}");
+
+        let mut json =
+            JsonLinesOutput::new(FailingWriter { fail_writes: true }, OutputSelection::new());
+        json.on_step(0, 0.0, &state());
+        assert!(json.finish().is_err());
+
+        // A flush failure at completion is kept as well
+        let mut csv = CsvOutput::new(FailingWriter { fail_writes: false }, OutputSelection::new());
+        csv.on_step(0, 0.0, &state());
+        csv.on_complete(&state());
+        let error = csv.finish().unwrap_err();
+        assert!(error.to_string().contains("flush failed"), "{error}");
+    }
+
+    #[test]
+    fn test_memory_recorder() {
+        let selection = OutputSelection::new()
+            .with_bus_quantities(&[BusQuantity::VoltageMagnitude])
+            .with_branch_flows(network(), &[BranchQuantity::ReactivePowerFrom]);
+        let mut recorder = MemoryRecorder::new(selection);
+        recorder.on_step(0, 0.0, &state());
+        recorder.on_step(1, 0.5, &StateStore::new(2));
+        recorder.on_complete(&state());
+
+        assert!(recorder.completed);
+        assert_eq!(recorder.columns, vec!["vm:0", "vm:1", "q_from:0"]);
+        assert_eq!(recorder.times, vec![0.0, 0.5]);
+        assert_eq!(
+            recorder.bus_series(BusQuantity::VoltageMagnitude, 1),
+            Some(&[0.5, 1.0][..])
+        );
+        assert_eq!(
+            recorder.branch_series(BranchQuantity::ReactivePowerFrom, 0),
+            Some(&[100.0, 0.0][..])
+        );
+        assert_eq!(recorder.column("vm:9"), None);
+    }
+
+    #[test]
+    fn test_branch_flows_need_a_fixed_network() {
+        let runner = TimeSeriesRunner::new(1.0, 3);
+        let flows = OutputSelection::new().with_branch_flows(network(), &BranchQuantity::ALL);
+        let mut recorder = MemoryRecorder::new(flows);
+        assert!(recorder.uses_fixed_network());
+        let mut state = state();
+        assert!(runner
+            .run(&mut Switching, &mut state, &mut [&mut recorder])
+            .is_err());
+        assert!(recorder.is_empty());
+
+        // Bus quantities follow the state and are fine
+        let mut recorder = MemoryRecorder::new(OutputSelection::new());
+        runner
+            .run(&mut Switching, &mut state, &mut [&mut recorder])
+            .unwrap();
+        assert_eq!(recorder.len(), 3);
+    }
+}
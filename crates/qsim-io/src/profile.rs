@@ -0,0 +1,559 @@
+//! Load and generation profile files
+//!
+//! Profiles are time-indexed series keyed by element: `load:<index>` or
+//! `gen:<index>`, with an optional `:mw` suffix for absolute values instead
+//! of multipliers. Timestamps are RFC 3339 with an explicit offset
+//! (`2024-01-01T00:00:00+01:00` or `...Z`) and are stored as UTC seconds.
+//!
+//! CSV files have a `timestamp` column followed by one column per element:
+//!
+//! ```text
+//! timestamp,load:0,gen:1:mw
+//! 2024-01-01T00:00:00Z,0.8,120.0
+//! 2024-01-01T01:00:00Z,0.7,135.0
+//! ```
+//!
+//! JSON files hold a list of series:
+//!
+//! ```json
+//! {"series": [{"target": "load:0", "interpolation": "Linear",
+//!              "timestamps": ["2024-01-01T00:00:00Z"], "values": [0.8]}]}
+//! ```
+
+use qsim_elements::{Network, Profile, ProfileKind, ProfileTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::IoError;
+
+/// How values between samples are obtained
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interpolation {
+    /// Hold the most recent sample
+    #[default]
+    Step,
+    /// Interpolate linearly between neighbouring samples
+    Linear,
+}
+
+/// A time-indexed profile for one load or generator
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileSeries {
+    /// Driven element
+    pub target: ProfileTarget,
+    /// Interpretation of the values
+    pub kind: ProfileKind,
+    /// Sample times (seconds since the Unix epoch, UTC), strictly increasing
+    pub timestamps: Vec<i64>,
+    /// Sample values
+    pub values: Vec<f64>,
+    /// Interpolation between samples
+    pub interpolation: Interpolation,
+}
+
+impl ProfileSeries {
+    /// Create a series with step interpolation
+    ///
+    /// Fails if the series is empty, the lengths differ or the timestamps
+    /// are not strictly increasing.
+    pub fn new(
+        target: ProfileTarget,
+        kind: ProfileKind,
+        timestamps: Vec<i64>,
+        values: Vec<f64>,
+    ) -> Result<Self, IoError> {
+        if timestamps.is_empty() {
+            return Err(IoError::InvalidData(format!(
+                "Profile {target:?} has no samples"
+            )));
+        }
+        if timestamps.len() != values.len() {
+            return Err(IoError::InvalidData(format!(
+                "{} timestamps but {} values",
+                timestamps.len(),
+                values.len()
+            )));
+        }
+        if timestamps.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(IoError::InvalidData(
+                "Profile timestamps must be strictly increasing".to_string(),
+            ));
+        }
+        Ok(Self {
+            target,
+            kind,
+            timestamps,
+            values,
+            interpolation: Interpolation::default(),
+        })
+    }
+
+    /// Builder: set the interpolation
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Value at `time` (UTC seconds), holding the end values outside the series
+    ///
+    /// `None` only for a series without samples.
+    pub fn value_at(&self, time: i64) -> Option<f64> {
+        let last = self.timestamps.len().checked_sub(1)?;
+        let next = self.timestamps.partition_point(|&t| t <= time);
+        if next == 0 {
+            return Some(self.values[0]);
+        }
+        if next > last {
+            return Some(self.values[last]);
+        }
+        let prev = next - 1;
+        Some(match self.interpolation {
+            Interpolation::Step => self.values[prev],
+            Interpolation::Linear => {
+                let (t0, t1) = (self.timestamps[prev], self.timestamps[next]);
+                let fraction = (time - t0) as f64 / (t1 - t0) as f64;
+                self.values[prev] + fraction * (self.values[next] - self.values[prev])
+            }
+        })
+    }
+
+    /// Sample the series on a regular grid into a per-step [`Profile`]
+    ///
+    /// The profile has exactly `steps` values; a series without samples is
+    /// an error.
+    pub fn resample(&self, start: i64, time_step: i64, steps: usize) -> Result<Profile, IoError> {
+        let values = (0..steps)
+            .map(|k| self.value_at(start + k as i64 * time_step))
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                IoError::InvalidData(format!("Profile {:?} has no samples", self.target))
+            })?;
+        Ok(Profile::new(self.target, self.kind, values))
+    }
+}
+
+/// A collection of profile series read from one file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileSet {
+    /// Series, one per element
+    pub series: Vec<ProfileSeries>,
+}
+
+#[derive(Deserialize)]
+struct ProfileSetData {
+    series: Vec<ProfileSeriesData>,
+}
+
+#[derive(Deserialize)]
+struct ProfileSeriesData {
+    target: String,
+    #[serde(default)]
+    kind: Option<ProfileKind>,
+    #[serde(default)]
+    interpolation: Interpolation,
+    timestamps: Vec<String>,
+    values: Vec<f64>,
+}
+
+impl ProfileSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: set the interpolation of every series
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        for series in &mut self.series {
+            series.interpolation = interpolation;
+        }
+        self
+    }
+
+    /// Read profiles from CSV text
+    pub fn from_csv(text: &str) -> Result<Self, IoError> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'));
+        let header = lines
+            .next()
+            .ok_or_else(|| IoError::InvalidData("Empty profile file".to_string()))?;
+        let mut columns = header.split(',').map(str::trim);
+        if !columns
+            .next()
+            .is_some_and(|c| c.eq_ignore_ascii_case("timestamp"))
+        {
+            return Err(IoError::InvalidData(
+                "First profile column must be 'timestamp'".to_string(),
+            ));
+        }
+        let keys = columns.map(parse_key).collect::<Result<Vec<_>, _>>()?;
+
+        let mut timestamps = Vec::new();
+        let mut columns = vec![Vec::new(); keys.len()];
+        for (row, line) in lines.enumerate() {
+            let mut fields = line.split(',').map(str::trim);
+            timestamps.push(parse_timestamp(fields.next().unwrap_or_default())?);
+            for (column, values) in columns.iter_mut().enumerate() {
+                let field = fields.next().ok_or_else(|| {
+                    IoError::InvalidData(format!("Row {} has too few columns", row + 1))
+                })?;
+                values.push(field.parse().map_err(|_| {
+                    IoError::InvalidData(format!(
+                        "Invalid value '{field}' in row {}, column {}",
+                        row + 1,
+                        column + 2
+                    ))
+                })?);
+            }
+            if fields.next().is_some() {
+                return Err(IoError::InvalidData(format!(
+                    "Row {} has too many columns",
+                    row + 1
+                )));
+            }
+        }
+
+        let series = keys
+            .into_iter()
+            .zip(columns)
+            .map(|((target, kind), values)| {
+                ProfileSeries::new(target, kind, timestamps.clone(), values)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { series })
+    }
+
+    /// Read profiles from JSON text
+    pub fn from_json(json: &str) -> Result<Self, IoError> {
+        let data: ProfileSetData = serde_json::from_str(json)?;
+        let series = data
+            .series
+            .into_iter()
+            .map(|s| {
+                let (target, key_kind) = parse_key(&s.target)?;
+                let timestamps = s
+                    .timestamps
+                    .iter()
+                    .map(|t| parse_timestamp(t))
+                    .collect::<Result<_, _>>()?;
+                Ok(
+                    ProfileSeries::new(target, s.kind.unwrap_or(key_kind), timestamps, s.values)?
+                        .with_interpolation(s.interpolation),
+                )
+            })
+            .collect::<Result<_, IoError>>()?;
+        Ok(Self { series })
+    }
+
+    /// Read profiles from a `.csv` or `.json` file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, IoError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&text),
+            _ => Self::from_csv(&text),
+        }
+    }
+
+    /// Resample every series onto a regular grid and check it against `network`
+    ///
+    /// `start` is in UTC seconds (see [`parse_timestamp`]) and `time_step` in
+    /// seconds; the resulting profiles have one value per step.
+    pub fn bind(
+        &self,
+        network: &Network,
+        start: i64,
+        time_step: i64,
+        steps: usize,
+    ) -> Result<Vec<Profile>, IoError> {
+        if time_step <= 0 {
+            return Err(IoError::InvalidData(
+                "Profile time step must be positive".to_string(),
+            ));
+        }
+        self.series
+            .iter()
+            .map(|series| {
+                let profile = series.resample(start, time_step, steps)?;
+                if profile.is_bound(network) {
+                    Ok(profile)
+                } else {
+                    Err(IoError::InvalidData(format!(
+                        "Profile target {:?} is not in the network",
+                        series.target
+                    )))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parse an element key such as `load:3`, `gen:0` or `gen:0:mw`
+fn parse_key(key: &str) -> Result<(ProfileTarget, ProfileKind), IoError> {
+    let invalid = || IoError::InvalidData(format!("Invalid profile key '{key}'"));
+    let mut parts = key.split(':');
+    let element = parts.next().ok_or_else(invalid)?;
+    let index = parts
+        .next()
+        .and_then(|i| i.parse().ok())
+        .ok_or_else(invalid)?;
+    let kind = match parts.next() {
+        None => ProfileKind::Multiplier,
+        Some(unit) if unit.eq_ignore_ascii_case("mw") => ProfileKind::Absolute,
+        Some(_) => return Err(invalid()),
+    };
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    let target = match element.to_ascii_lowercase().as_str() {
+        "load" => ProfileTarget::Load(index),
+        "gen" | "generator" => ProfileTarget::Generator(index),
+        _ => return Err(invalid()),
+    };
+    Ok((target, kind))
+}
+
+/// Parse an RFC 3339 timestamp into seconds since the Unix epoch (UTC)
+///
+/// The offset is required (`Z` or `±HH:MM`); fractional seconds are
+/// truncated and a space may replace the `T` separator.
+pub fn parse_timestamp(text: &str) -> Result<i64, IoError> {
+    let invalid = || IoError::InvalidData(format!("Invalid timestamp '{text}'"));
+    let number = |s: &str| s.parse::<i64>().map_err(|_| invalid());
+    let (date, time) = text.split_once(['T', 't', ' ']).ok_or_else(invalid)?;
+
+    let mut date_parts = date.splitn(3, '-');
+    let (year, month, day) = match (date_parts.next(), date_parts.next(), date_parts.next()) {
+        (Some(y), Some(m), Some(d)) => (number(y)?, number(m)?, number(d)?),
+        _ => return Err(invalid()),
+    };
+
+    let (clock, offset) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let sign_at = time.rfind(['+', '-']).ok_or_else(invalid)?;
+        let (clock, offset) = time.split_at(sign_at);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':').ok_or_else(invalid)?;
+        let (hours, minutes) = (number(hours)?, number(minutes)?);
+        if !(0..=23).contains(&hours) || !(0..=59).contains(&minutes) {
+            return Err(invalid());
+        }
+        (clock, sign * (hours * 3600 + minutes * 60))
+    };
+    let clock = clock.split('.').next().unwrap_or_default();
+    let mut clock_parts = clock.split(':');
+    let hour = number(clock_parts.next().ok_or_else(invalid)?)?;
+    let minute = number(clock_parts.next().ok_or_else(invalid)?)?;
+    let second = clock_parts.next().map(number).transpose()?.unwrap_or(0);
+
+    if !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || !(0..=23).contains(&hour)
+        || !(0..=59).contains(&minute)
+        || !(0..=60).contains(&second)
+    {
+        return Err(invalid());
+    }
+    Ok(days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Length of a month in the proleptic Gregorian calendar
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_elements::{Bus, Load};
+
+    const CSV: &str = "\
+timestamp,load:0,gen:1:mw
+# comment lines are skipped
+2024-01-01T00:00:00Z,0.8,120.0
+2024-01-01T01:00:00Z,0.7,135.0
+";
+
+    fn series(interpolation: Interpolation) -> ProfileSeries {
+        ProfileSeries::new(
+            ProfileTarget::Load(0),
+            ProfileKind::Multiplier,
+            vec![0, 3600, 7200],
+            vec![1.0, 2.0, 0.5],
+        )
+        .unwrap()
+        .with_interpolation(interpolation)
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 1, 1), 10_957);
+        assert_eq!(days_from_civil(2024, 1, 1), 19_723);
+    }
+
+    #[test]
+    fn test_leap_years() {
+        let days = |y, m1, d1, m2, d2| days_from_civil(y, m2, d2) - days_from_civil(y, m1, d1);
+        assert_eq!(days(2024, 2, 28, 3, 1), 2);
+        assert_eq!(days(2023, 2, 28, 3, 1), 1);
+        // Century years are leap years only when divisible by 400
+        assert_eq!(days(2000, 2, 28, 3, 1), 2);
+        assert_eq!(days(1900, 2, 28, 3, 1), 1);
+
+        let feb29 = parse_timestamp("2024-02-29T00:00:00Z").unwrap();
+        let mar1 = parse_timestamp("2024-03-01T00:00:00Z").unwrap();
+        assert_eq!(mar1 - feb29, 86_400);
+
+        // Days beyond the end of the month are rejected, not rolled over
+        assert!(parse_timestamp("2000-02-29T00:00:00Z").is_ok());
+        for invalid in [
+            "2023-02-29T00:00:00Z",
+            "1900-02-29T00:00:00Z",
+            "2024-02-30T00:00:00Z",
+            "2024-04-31T00:00:00Z",
+            "2024-11-31T00:00:00Z",
+        ] {
+            assert!(parse_timestamp(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp_offsets() {
+        let utc = parse_timestamp("2024-01-01T00:00:00Z").unwrap();
+        assert_eq!(utc, 1_704_067_200);
+        assert_eq!(parse_timestamp("2024-01-01T01:00:00+01:00").unwrap(), utc);
+        assert_eq!(parse_timestamp("2023-12-31T18:30:00-05:30").unwrap(), utc);
+        assert_eq!(parse_timestamp("2024-01-01 00:00:00.750z").unwrap(), utc);
+        assert_eq!(parse_timestamp("2024-01-01T00:00Z").unwrap(), utc);
+
+        for invalid in [
+            "2024-01-01T00:00:00",
+            "2024-01-01",
+            "2024-13-01T00:00:00Z",
+            "2024-01-01T24:00:00Z",
+            "2024-01-01T00:00:00+0100",
+            "2024-01-01T00:00:00+24:00",
+            "2024-01-01T00:00:00-05:60",
+        ] {
+            assert!(parse_timestamp(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_csv() {
+        let set = ProfileSet::from_csv(CSV).unwrap();
+        let t0 = parse_timestamp("2024-01-01T00:00:00Z").unwrap();
+
+        assert_eq!(set.series.len(), 2);
+        assert_eq!(set.series[0].target, ProfileTarget::Load(0));
+        assert_eq!(set.series[0].kind, ProfileKind::Multiplier);
+        assert_eq!(set.series[0].timestamps, vec![t0, t0 + 3600]);
+        assert_eq!(set.series[0].values, vec![0.8, 0.7]);
+        assert_eq!(set.series[1].target, ProfileTarget::Generator(1));
+        assert_eq!(set.series[1].kind, ProfileKind::Absolute);
+        assert_eq!(set.series[1].values, vec![120.0, 135.0]);
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        let header = "timestamp,load:0\n";
+        for invalid in [
+            "",
+            "time,load:0\n2024-01-01T00:00:00Z,1.0",
+            "timestamp,bus:0\n2024-01-01T00:00:00Z,1.0",
+            "timestamp,load:0,gen:0\n2024-01-01T00:00:00Z,1.0",
+            "timestamp,load:0\n2024-01-01T00:00:00Z,1.0,2.0",
+            "timestamp,load:0\n2024-01-01T00:00:00Z,high",
+            "timestamp,load:0\n2024-01-01T01:00:00Z,1.0\n2024-01-01T00:00:00Z,1.0",
+            header,
+        ] {
+            assert!(ProfileSet::from_csv(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        let json = r#"{"series": [
+            {"target": "gen:2", "interpolation": "Linear",
+             "timestamps": ["2024-01-01T00:00:00Z", "2024-01-01T02:00:00+01:00"],
+             "values": [0.5, 1.5]},
+            {"target": "load:1", "kind": "Absolute",
+             "timestamps": ["2024-01-01T00:00:00Z"], "values": [42.0]}
+        ]}"#;
+        let set = ProfileSet::from_json(json).unwrap();
+        let t0 = parse_timestamp("2024-01-01T00:00:00Z").unwrap();
+
+        assert_eq!(set.series[0].target, ProfileTarget::Generator(2));
+        assert_eq!(set.series[0].interpolation, Interpolation::Linear);
+        assert_eq!(set.series[0].timestamps, vec![t0, t0 + 3600]);
+        assert_eq!(set.series[1].kind, ProfileKind::Absolute);
+        assert_eq!(set.series[1].interpolation, Interpolation::Step);
+
+        let empty = r#"{"series": [{"target": "load:0", "timestamps": [], "values": []}]}"#;
+        assert!(ProfileSet::from_json(empty).is_err());
+        let mismatched = r#"{"series": [{"target": "load:0",
+            "timestamps": ["2024-01-01T00:00:00Z"], "values": []}]}"#;
+        assert!(ProfileSet::from_json(mismatched).is_err());
+    }
+
+    #[test]
+    fn test_step_and_linear_interpolation() {
+        let step = series(Interpolation::Step);
+        let linear = series(Interpolation::Linear);
+
+        // End values hold outside the series
+        for s in [&step, &linear] {
+            assert_eq!(s.value_at(-100), Some(1.0));
+            assert_eq!(s.value_at(3600), Some(2.0));
+            assert_eq!(s.value_at(10_000), Some(0.5));
+        }
+        assert_eq!(step.value_at(900), Some(1.0));
+        assert_eq!(step.value_at(5400), Some(2.0));
+        assert_eq!(linear.value_at(900), Some(1.25));
+        assert_eq!(linear.value_at(5400), Some(1.25));
+
+        let profile = linear.resample(-1800, 1800, 7).unwrap();
+        assert_eq!(profile.values, vec![1.0, 1.0, 1.5, 2.0, 1.25, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_bind() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_load(Load::new(0, 10.0, 2.0));
+        let set = ProfileSet {
+            series: vec![series(Interpolation::Step)],
+        };
+
+        let profiles = set.bind(&network, 0, 3600, 4).unwrap();
+        assert_eq!(profiles[0].values, vec![1.0, 2.0, 0.5, 0.5]);
+
+        assert!(set.bind(&network, 0, 0, 4).is_err());
+        let mut unbound = set.clone();
+        unbound.series[0].target = ProfileTarget::Load(1);
+        assert!(unbound.bind(&network, 0, 3600, 4).is_err());
+        let mut empty = set.clone();
+        empty.series[0].timestamps.clear();
+        empty.series[0].values.clear();
+        assert!(empty.bind(&network, 0, 3600, 4).is_err());
+    }
+}
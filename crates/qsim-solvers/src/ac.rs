@@ -0,0 +1,903 @@
+//! AC Power Flow Solver
+//!
+//! Full nonlinear power flow in polar coordinates, solved with
+//! Newton-Raphson:
+//!
+//! ```text
+//! [ΔP]   [∂P/∂θ  ∂P/∂V] [Δθ]
+//! [ΔQ] = [∂Q/∂θ  ∂Q/∂V] [ΔV]
+//! ```
+//!
+//! Slack buses fix V and θ, PV buses fix P and V, PQ buses fix P and Q.
+//! Generators with a [`regulated_bus`](qsim_elements::Generator::regulated_bus)
+//! hold that bus's voltage instead of their terminal's; units regulating the
+//! same bus split the required reactive power by their
+//! [`reactive_share`](qsim_elements::Generator::reactive_share).
+//!
+//! Switched shunts, tap changers and phase shifters are adjusted in an
+//! outer loop (see [`AcPowerFlowSolver::solve_with_controls`]).
+//!
+//! Voltage-dependent loads (ZIP, exponential) make the specified injection a
+//! function of V; their derivative enters the ∂/∂V columns of the Jacobian.
+
+use nalgebra::{DMatrix, DVector};
+use num_complex::Complex64;
+use qsim_core::{CoreError, Result, SolverResult, StateStore};
+use qsim_elements::{BusType, LoadModel, Network};
+
+use crate::control::{adjust_controls, validate_controls};
+use crate::{ac_branch_flows, build_ybus, BranchFlow, NetworkSolver};
+
+/// AC Power Flow Solver
+///
+/// Newton-Raphson iteration on the bus power mismatch equations.
+#[derive(Debug, Clone)]
+pub struct AcPowerFlowSolver {
+    /// Maximum per-unit power mismatch for convergence
+    pub tolerance: f64,
+    /// Maximum number of Newton iterations
+    pub max_iterations: usize,
+    /// Maximum number of outer control iterations (0 = fixed settings)
+    pub max_control_iterations: usize,
+}
+
+impl AcPowerFlowSolver {
+    /// Create a new AC power flow solver
+    pub fn new() -> Self {
+        Self {
+            tolerance: 1e-8,
+            max_iterations: 20,
+            max_control_iterations: 20,
+        }
+    }
+
+    /// Create with custom tolerance
+    pub fn with_tolerance(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for AcPowerFlowSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bus classification used by the Newton iteration
+#[derive(Debug, Clone)]
+pub(crate) struct BusTypes {
+    pub slack: usize,
+    pub pv: Vec<usize>,
+    pub pq: Vec<usize>,
+    /// Buses regulated by generators elsewhere; their controller buses are
+    /// not listed in `pv`
+    pub remote: Vec<RemoteControl>,
+}
+
+/// Generator buses sharing control of one bus voltage
+#[derive(Debug, Clone)]
+pub(crate) struct RemoteControl {
+    /// Regulated bus
+    pub bus: usize,
+    /// Voltage setpoint (per-unit)
+    pub setpoint: f64,
+    /// Controller buses with their reactive sharing factors
+    pub controllers: Vec<(usize, f64)>,
+}
+
+impl BusTypes {
+    /// Classify buses; only the network slack bus is treated as slack
+    pub fn from_network(network: &Network) -> Option<Self> {
+        let slack = network.slack_bus()?;
+        let mut pv = Vec::new();
+        let mut pq = Vec::new();
+        for (i, bus) in network.buses.iter().enumerate() {
+            if i == slack {
+                continue;
+            }
+            match bus.bus_type {
+                BusType::Slack | BusType::PV => pv.push(i),
+                BusType::PQ => pq.push(i),
+            }
+        }
+        let remote = remote_controls(network, slack, &pv);
+        pv.retain(|&i| {
+            !remote
+                .iter()
+                .any(|r| r.bus == i || r.controllers.iter().any(|&(c, _)| c == i))
+        });
+        Some(Self {
+            slack,
+            pv,
+            pq,
+            remote,
+        })
+    }
+
+    /// The same classification with remote control treated as local
+    pub fn local_control(mut self) -> Self {
+        let controllers = self.controllers();
+        self.pv.extend(controllers.iter().map(|&(bus, _)| bus));
+        self.pv.sort_unstable();
+        self.pv.dedup();
+        self.remote.clear();
+        self
+    }
+
+    /// Controller buses of all groups, with their sharing factors
+    pub fn controllers(&self) -> Vec<(usize, f64)> {
+        self.remote
+            .iter()
+            .flat_map(|r| r.controllers.iter().copied())
+            .collect()
+    }
+}
+
+/// Group PV buses whose generators regulate another bus
+///
+/// Units at a regulated PV bus join its group. Remote control of the slack
+/// bus, of a bus that itself controls another one, or by units with a
+/// non-positive share is ignored; those units regulate their own terminal.
+fn remote_controls(network: &Network, slack: usize, pv: &[usize]) -> Vec<RemoteControl> {
+    let units = || {
+        network
+            .generators
+            .iter()
+            .filter(|g| g.in_service && g.reactive_share > 0.0 && pv.contains(&g.bus))
+    };
+
+    let mut groups: Vec<RemoteControl> = Vec::new();
+    for generator in units() {
+        let target = generator.controlled_bus();
+        if target == generator.bus || target == slack || target >= network.bus_count() {
+            continue;
+        }
+        let group = match groups.iter().position(|r| r.bus == target) {
+            Some(i) => i,
+            None => {
+                groups.push(RemoteControl {
+                    bus: target,
+                    setpoint: generator.voltage_setpoint,
+                    controllers: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        add_controller(&mut groups, group, generator.bus, generator.reactive_share);
+    }
+
+    // A regulated bus cannot also be a controller elsewhere
+    while let Some(chained) = groups.iter().position(|r| {
+        groups
+            .iter()
+            .any(|other| other.controllers.iter().any(|&(c, _)| c == r.bus))
+    }) {
+        groups.remove(chained);
+    }
+
+    for group in 0..groups.len() {
+        let bus = groups[group].bus;
+        if !pv.contains(&bus) {
+            continue;
+        }
+        let local: Vec<f64> = units()
+            .filter(|g| g.bus == bus && g.controlled_bus() == bus)
+            .map(|g| g.reactive_share)
+            .collect();
+        if local.is_empty() {
+            add_controller(&mut groups, group, bus, 1.0);
+        }
+        for share in local {
+            add_controller(&mut groups, group, bus, share);
+        }
+    }
+    groups
+}
+
+/// Add a controller bus to a group, or add to its share if already present
+///
+/// A bus controls at most one group; later requests are ignored.
+fn add_controller(groups: &mut [RemoteControl], group: usize, bus: usize, share: f64) {
+    let owner = groups
+        .iter()
+        .position(|r| r.controllers.iter().any(|&(c, _)| c == bus));
+    match owner {
+        Some(owner) if owner == group => {
+            if let Some(entry) = groups[group]
+                .controllers
+                .iter_mut()
+                .find(|(c, _)| *c == bus)
+            {
+                entry.1 += share;
+            }
+        }
+        Some(_) => {}
+        None => groups[group].controllers.push((bus, share)),
+    }
+}
+
+/// Voltage-dependent loads, as corrections to constant-power injections
+///
+/// The specified injections already subtract each load's nominal demand;
+/// at voltage V the correction adds back `S0·(1 − f(V))`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VoltageDependentLoads {
+    /// (bus, nominal demand in per-unit, model)
+    loads: Vec<(usize, Complex64, LoadModel)>,
+}
+
+impl VoltageDependentLoads {
+    pub fn from_network(network: &Network) -> Self {
+        let loads = network
+            .loads
+            .iter()
+            .filter(|l| l.in_service && l.model != LoadModel::ConstantPower)
+            .map(|l| {
+                let s0 = Complex64::new(l.active_power, l.reactive_power) / network.base_mva;
+                (l.bus, s0, l.model)
+            })
+            .collect();
+        Self { loads }
+    }
+
+    /// Specified injections at `vm`, starting from constant-power `s_spec`
+    pub fn injections(&self, s_spec: &[Complex64], vm: &[f64]) -> Vec<Complex64> {
+        let mut s = s_spec.to_vec();
+        for &(bus, s0, model) in &self.loads {
+            let (kp, kq) = model.factors(vm[bus]);
+            s[bus] += Complex64::new(s0.re * (1.0 - kp), s0.im * (1.0 - kq));
+        }
+        s
+    }
+
+    /// ∂S_spec/∂V at each bus
+    pub fn derivatives(&self, n: usize, vm: &[f64]) -> Vec<Complex64> {
+        let mut ds = vec![Complex64::new(0.0, 0.0); n];
+        for &(bus, s0, model) in &self.loads {
+            let (dp, dq) = model.derivatives(vm[bus]);
+            ds[bus] -= Complex64::new(s0.re * dp, s0.im * dq);
+        }
+        ds
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.loads.is_empty()
+    }
+}
+
+/// Complex power injections S = V · conj(Y · V) (per-unit)
+pub(crate) fn power_injections(
+    ybus: &DMatrix<Complex64>,
+    vm: &[f64],
+    va: &[f64],
+) -> Vec<Complex64> {
+    let v = voltages(vm, va);
+    let current = ybus * &v;
+    v.iter()
+        .zip(current.iter())
+        .map(|(v, i)| v * i.conj())
+        .collect()
+}
+
+pub(crate) fn voltages(vm: &[f64], va: &[f64]) -> DVector<Complex64> {
+    DVector::from_iterator(
+        vm.len(),
+        vm.iter().zip(va).map(|(&m, &a)| Complex64::from_polar(m, a)),
+    )
+}
+
+/// Outcome of a Newton-Raphson run
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NewtonOutcome {
+    pub iterations: usize,
+    pub mismatch: f64,
+    pub converged: bool,
+}
+
+/// Newton-Raphson iteration on `vm`/`va` for specified injections `s_spec`
+///
+/// `s_spec` holds per-unit constant-power injections, adjusted by `loads`
+/// at every iteration; entries for the slack bus (P, Q) and PV buses (Q)
+/// are ignored.
+///
+/// For remote control groups, the regulated bus voltage is held and each
+/// controller bus gains an unknown reactive output `q` on top of its
+/// specified injection, with `q_a / f_a = q_b / f_b` within the group.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn newton_raphson(
+    ybus: &DMatrix<Complex64>,
+    types: &BusTypes,
+    s_spec: &[Complex64],
+    loads: &VoltageDependentLoads,
+    vm: &mut [f64],
+    va: &mut [f64],
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<NewtonOutcome> {
+    // Unknowns: θ at non-slack buses, V at PQ and controller buses other
+    // than regulated ones, q at controller buses
+    let controllers = types.controllers();
+    let mut angle_buses: Vec<usize> = types
+        .pv
+        .iter()
+        .chain(&types.pq)
+        .chain(controllers.iter().map(|(bus, _)| bus))
+        .copied()
+        .collect();
+    angle_buses.sort_unstable();
+    let mut reactive_buses: Vec<usize> = types
+        .pq
+        .iter()
+        .chain(controllers.iter().map(|(bus, _)| bus))
+        .copied()
+        .collect();
+    reactive_buses.sort_unstable();
+    let magnitude_buses: Vec<usize> = reactive_buses
+        .iter()
+        .copied()
+        .filter(|&i| !types.remote.iter().any(|r| r.bus == i))
+        .collect();
+    let n_theta = angle_buses.len();
+    let n_v = magnitude_buses.len();
+    let q_rows: Vec<usize> = controllers
+        .iter()
+        .map(|(bus, _)| n_theta + reactive_buses.binary_search(bus).unwrap_or_default())
+        .collect();
+    // Consecutive controllers of a group form the sharing equations
+    let mut sharing = Vec::new();
+    let mut first = 0;
+    for group in &types.remote {
+        let count = group.controllers.len();
+        sharing.extend((first..first + count).zip(first + 1..first + count));
+        first += count;
+    }
+    let dim = n_theta + n_v + controllers.len();
+    let mut q = vec![0.0; controllers.len()];
+
+    let mut iterations = 0;
+    loop {
+        let s_calc = power_injections(ybus, vm, va);
+        let s_spec = loads.injections(s_spec, vm);
+        let mut mismatch = DVector::zeros(dim);
+        for (k, &i) in angle_buses.iter().enumerate() {
+            mismatch[k] = s_spec[i].re - s_calc[i].re;
+        }
+        for (k, &i) in reactive_buses.iter().enumerate() {
+            mismatch[n_theta + k] = s_spec[i].im - s_calc[i].im;
+        }
+        for (c, &row) in q_rows.iter().enumerate() {
+            mismatch[row] += q[c];
+        }
+        let sharing_row = n_theta + reactive_buses.len();
+        for (k, &(a, b)) in sharing.iter().enumerate() {
+            mismatch[sharing_row + k] = q[b] / controllers[b].1 - q[a] / controllers[a].1;
+        }
+
+        let error = mismatch.amax();
+        if !error.is_finite() {
+            return Ok(NewtonOutcome { iterations, mismatch: error, converged: false });
+        }
+        if error < tolerance {
+            return Ok(NewtonOutcome { iterations, mismatch: error, converged: true });
+        }
+        if iterations >= max_iterations {
+            return Ok(NewtonOutcome { iterations, mismatch: error, converged: false });
+        }
+
+        let block = build_jacobian(
+            ybus,
+            vm,
+            va,
+            &angle_buses,
+            &reactive_buses,
+            &magnitude_buses,
+        );
+        let mut jacobian = if controllers.is_empty() {
+            block
+        } else {
+            let mut full = DMatrix::zeros(dim, dim);
+            full.view_mut((0, 0), block.shape()).copy_from(&block);
+            for (c, &row) in q_rows.iter().enumerate() {
+                full[(row, n_theta + n_v + c)] = -1.0;
+            }
+            for (k, &(a, b)) in sharing.iter().enumerate() {
+                full[(sharing_row + k, n_theta + n_v + a)] = 1.0 / controllers[a].1;
+                full[(sharing_row + k, n_theta + n_v + b)] = -1.0 / controllers[b].1;
+            }
+            full
+        };
+        if !loads.is_empty() {
+            let ds_spec = loads.derivatives(vm.len(), vm);
+            for (c, &k) in magnitude_buses.iter().enumerate() {
+                if let Ok(r) = angle_buses.binary_search(&k) {
+                    jacobian[(r, n_theta + c)] -= ds_spec[k].re;
+                }
+                if let Ok(r) = reactive_buses.binary_search(&k) {
+                    jacobian[(n_theta + r, n_theta + c)] -= ds_spec[k].im;
+                }
+            }
+        }
+        let dx = jacobian
+            .lu()
+            .solve(&mismatch)
+            .ok_or_else(|| CoreError::SimulationError("Jacobian is singular".into()))?;
+
+        for (k, &i) in angle_buses.iter().enumerate() {
+            va[i] += dx[k];
+        }
+        for (k, &i) in magnitude_buses.iter().enumerate() {
+            vm[i] += dx[n_theta + k];
+        }
+        for (c, qc) in q.iter_mut().enumerate() {
+            *qc += dx[n_theta + n_v + c];
+        }
+        iterations += 1;
+    }
+}
+
+/// Derivatives of the complex bus injections with respect to the voltage
+/// angles and magnitudes of every bus
+///
+/// ```text
+/// ∂S/∂θ = j·diag(V)·conj(diag(I) − Y·diag(V))
+/// ∂S/∂V = diag(V)·conj(Y·diag(V/|V|)) + conj(diag(I))·diag(V/|V|)
+/// ```
+pub(crate) fn injection_derivatives(
+    ybus: &DMatrix<Complex64>,
+    vm: &[f64],
+    va: &[f64],
+) -> (DMatrix<Complex64>, DMatrix<Complex64>) {
+    let v = voltages(vm, va);
+    let current = ybus * &v;
+    let n = v.len();
+    let j = Complex64::new(0.0, 1.0);
+
+    let ds_dtheta = DMatrix::from_fn(n, n, |i, k| {
+        let diag = if i == k { current[i] } else { Complex64::new(0.0, 0.0) };
+        j * v[i] * (diag - ybus[(i, k)] * v[k]).conj()
+    });
+    let ds_dvm = DMatrix::from_fn(n, n, |i, k| {
+        let unit_k = v[k] / vm[k];
+        let mut value = v[i] * (ybus[(i, k)] * unit_k).conj();
+        if i == k {
+            value += current[i].conj() * unit_k;
+        }
+        value
+    });
+
+    (ds_dtheta, ds_dvm)
+}
+
+/// Power flow Jacobian in polar coordinates
+///
+/// Rows are P at `angle_buses` then Q at `reactive_buses`; columns are θ at
+/// `angle_buses` then V at `magnitude_buses`.
+pub(crate) fn build_jacobian(
+    ybus: &DMatrix<Complex64>,
+    vm: &[f64],
+    va: &[f64],
+    angle_buses: &[usize],
+    reactive_buses: &[usize],
+    magnitude_buses: &[usize],
+) -> DMatrix<f64> {
+    let (ds_dtheta, ds_dvm) = injection_derivatives(ybus, vm, va);
+    let n_theta = angle_buses.len();
+
+    let mut jacobian = DMatrix::zeros(
+        n_theta + reactive_buses.len(),
+        n_theta + magnitude_buses.len(),
+    );
+    for (r, &i) in angle_buses.iter().enumerate() {
+        for (c, &k) in angle_buses.iter().enumerate() {
+            jacobian[(r, c)] = ds_dtheta[(i, k)].re;
+        }
+        for (c, &k) in magnitude_buses.iter().enumerate() {
+            jacobian[(r, n_theta + c)] = ds_dvm[(i, k)].re;
+        }
+    }
+    for (r, &i) in reactive_buses.iter().enumerate() {
+        for (c, &k) in angle_buses.iter().enumerate() {
+            jacobian[(n_theta + r, c)] = ds_dtheta[(i, k)].im;
+        }
+        for (c, &k) in magnitude_buses.iter().enumerate() {
+            jacobian[(n_theta + r, n_theta + c)] = ds_dvm[(i, k)].im;
+        }
+    }
+
+    jacobian
+}
+
+impl AcPowerFlowSolver {
+    /// Solve with switched shunts, tap changers and phase shifters active
+    ///
+    /// After each converged solution, every control outside its band is
+    /// re-targeted and the power flow is solved again. The final shunt
+    /// steps, taps and phase shifts are written back to `network`.
+    ///
+    /// A tap changer regulating a bus outside the network is rejected with
+    /// [`CoreError::InvalidBusId`]. If the controls are still moving after
+    /// `max_control_iterations` re-solves, the result is reported as not
+    /// converged.
+    pub fn solve_with_controls(
+        &self,
+        network: &mut Network,
+        state: &mut StateStore,
+    ) -> Result<SolverResult> {
+        validate_controls(network)?;
+        let mut result = self.solve_fixed(network, state)?;
+        for _ in 0..self.max_control_iterations {
+            if !result.converged || !adjust_controls(network, state) {
+                return Ok(result);
+            }
+            result = self.solve_fixed(network, state)?;
+        }
+        // Out of iterations: the last settings only stand if nothing would move
+        let limited = self.max_control_iterations > 0;
+        if limited && result.converged && adjust_controls(&mut network.clone(), state) {
+            result.converged = false;
+        }
+        Ok(result)
+    }
+
+    /// Single power flow with all controls at their present settings
+    fn solve_fixed(&self, network: &Network, state: &mut StateStore) -> Result<SolverResult> {
+        let types = BusTypes::from_network(network)
+            .ok_or_else(|| CoreError::SimulationError("No buses in network".into()))?;
+
+        if state.bus_count() != network.bus_count() {
+            return Err(CoreError::SimulationError(
+                "Network and state bus count mismatch".into(),
+            ));
+        }
+
+        let base = network.base_mva;
+        let ybus = build_ybus(network);
+        let s_spec: Vec<Complex64> = state
+            .active_power
+            .iter()
+            .zip(&state.reactive_power)
+            .map(|(&p, &q)| Complex64::new(p, q) / base)
+            .collect();
+
+        // Voltage-controlled buses hold their setpoint
+        state.voltage_magnitude[types.slack] = network.voltage_setpoint(types.slack);
+        for &i in &types.pv {
+            state.voltage_magnitude[i] = network.voltage_setpoint(i);
+        }
+        for group in &types.remote {
+            state.voltage_magnitude[group.bus] = group.setpoint;
+        }
+
+        let outcome = newton_raphson(
+            &ybus,
+            &types,
+            &s_spec,
+            &VoltageDependentLoads::from_network(network),
+            &mut state.voltage_magnitude,
+            &mut state.voltage_angle,
+            self.tolerance,
+            self.max_iterations,
+        )?;
+
+        // Report the injections the solution implies at controlled buses
+        let s_calc = power_injections(&ybus, &state.voltage_magnitude, &state.voltage_angle);
+        state.active_power[types.slack] = s_calc[types.slack].re * base;
+        state.reactive_power[types.slack] = s_calc[types.slack].im * base;
+        for &i in types
+            .pv
+            .iter()
+            .chain(types.controllers().iter().map(|(i, _)| i))
+        {
+            state.reactive_power[i] = s_calc[i].im * base;
+        }
+
+        if outcome.converged {
+            Ok(SolverResult::converged(outcome.iterations, outcome.mismatch))
+        } else {
+            Ok(SolverResult::failed(outcome.iterations, outcome.mismatch))
+        }
+    }
+}
+
+impl NetworkSolver for AcPowerFlowSolver {
+    fn solve_network(&self, network: &Network, state: &mut StateStore) -> Result<SolverResult> {
+        let controlled = network.switched_shunts.iter().any(|s| s.in_service)
+            || network
+                .branches
+                .iter()
+                .any(|b| b.in_service && b.control.is_some());
+        if controlled && self.max_control_iterations > 0 {
+            self.solve_with_controls(&mut network.clone(), state)
+        } else {
+            self.solve_fixed(network, state)
+        }
+    }
+
+    fn branch_flows(&self, network: &Network, state: &StateStore) -> Vec<BranchFlow> {
+        ac_branch_flows(network, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_elements::{Branch, Bus, Generator, Load, SwitchedShunt, ThreeWindingTransformer};
+
+    /// IEEE 9-bus case (MATPOWER case9)
+    fn ieee9() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        for _ in 3..9 {
+            network.add_bus(Bus::pq(0.0, 0.0));
+        }
+        network.add_generator(Generator::new(0, 0.0, 1.0));
+        network.add_generator(Generator::new(1, 163.0, 1.0));
+        network.add_generator(Generator::new(2, 85.0, 1.0));
+        network.add_load(Load::new(4, 90.0, 30.0));
+        network.add_load(Load::new(6, 100.0, 35.0));
+        network.add_load(Load::new(8, 125.0, 50.0));
+        for (f, t, r, x, b) in [
+            (0, 3, 0.0, 0.0576, 0.0),
+            (3, 4, 0.017, 0.092, 0.158),
+            (4, 5, 0.039, 0.17, 0.358),
+            (2, 5, 0.0, 0.0586, 0.0),
+            (5, 6, 0.0119, 0.1008, 0.209),
+            (6, 7, 0.0085, 0.072, 0.149),
+            (7, 1, 0.0, 0.0625, 0.0),
+            (7, 8, 0.032, 0.161, 0.306),
+            (8, 3, 0.01, 0.085, 0.176),
+        ] {
+            network.add_branch(Branch::line_with_charging(f, t, r, x, b));
+        }
+        network
+    }
+
+    #[test]
+    fn test_ieee9_converges() {
+        let network = ieee9();
+        let mut state = network.initial_state();
+        let result = AcPowerFlowSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap();
+
+        assert!(result.converged);
+        assert!(result.iterations <= 6);
+        // Reference values from MATPOWER runpf(case9)
+        assert!((state.active_power[0] - 71.95).abs() < 0.01);
+        assert!((state.voltage_magnitude[4] - 0.975).abs() < 1e-3);
+        assert!((state.voltage_angle[8].to_degrees() + 4.350).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_branch_flow_balance() {
+        let network = ieee9();
+        let solver = AcPowerFlowSolver::new();
+        let mut state = network.initial_state();
+        solver.solve_network(&network, &mut state).unwrap();
+
+        let flows = solver.branch_flows(&network, &state);
+        let losses: f64 = flows.iter().map(|f| f.losses()).sum();
+        let generation: f64 = state.active_power[0] + 163.0 + 85.0;
+        assert!((generation - 315.0 - losses).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_voltage_dependent_loads() {
+        let solve = |model: LoadModel| {
+            let mut network = Network::new();
+            network.add_bus(Bus::slack(1.0));
+            network.add_bus(Bus::pq(0.0, 0.0));
+            network.add_branch(Branch::line(0, 1, 0.05, 0.2));
+            network.add_load(Load::new(1, 80.0, 30.0).with_model(model));
+            let mut state = network.initial_state();
+            let result = AcPowerFlowSolver::new()
+                .solve_network(&network, &mut state)
+                .unwrap();
+            assert!(result.converged && result.iterations <= 6);
+            let (p, _) = network.load_demand(&state);
+            (network, state, p[1])
+        };
+
+        let (_, constant, p_constant) = solve(LoadModel::ConstantPower);
+        let impedance = LoadModel::Zip {
+            active: [1.0, 0.0, 0.0],
+            reactive: [1.0, 0.0, 0.0],
+        };
+        let (network, state, p_impedance) = solve(impedance);
+        let vm = state.voltage_magnitude[1];
+
+        // Undervoltage reduces constant-impedance demand as V²
+        assert_eq!(p_constant, 80.0);
+        assert!((p_impedance - 80.0 * vm * vm).abs() < 1e-9);
+        assert!(vm > constant.voltage_magnitude[1]);
+
+        let losses: f64 = AcPowerFlowSolver::new()
+            .branch_flows(&network, &state)
+            .iter()
+            .map(|f| f.losses())
+            .sum();
+        assert!((state.active_power[0] - p_impedance - losses).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bus_shunt_matches_impedance_load() {
+        let two_bus = |bus: Bus, load: Load| {
+            let mut network = Network::new();
+            network.add_bus(Bus::slack(1.0));
+            network.add_bus(bus);
+            network.add_branch(Branch::line(0, 1, 0.02, 0.1));
+            network.add_load(load);
+            let mut state = network.initial_state();
+            AcPowerFlowSolver::new()
+                .solve_network(&network, &mut state)
+                .unwrap();
+            state
+        };
+
+        // A 20 MVAr reactor behaves as a constant-impedance reactive load
+        let shunt = two_bus(
+            Bus::pq(0.0, 0.0).with_shunt(0.0, -20.0),
+            Load::new(1, 50.0, 0.0),
+        );
+        let model = LoadModel::Zip {
+            active: [0.0, 0.0, 1.0],
+            reactive: [1.0, 0.0, 0.0],
+        };
+        let load = two_bus(
+            Bus::pq(0.0, 0.0),
+            Load::new(1, 50.0, 20.0).with_model(model),
+        );
+        assert!((shunt.voltage_magnitude[1] - load.voltage_magnitude[1]).abs() < 1e-9);
+        assert!((shunt.reactive_power[0] - load.reactive_power[0]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_switched_shunt_holds_band() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.02, 0.1));
+        network.add_load(Load::new(1, 60.0, 40.0));
+        network.add_switched_shunt(SwitchedShunt::new(1, 10.0, 8, 0.97, 0.99));
+
+        let solver = AcPowerFlowSolver::new();
+        let mut state = network.initial_state();
+        let result = solver
+            .solve_with_controls(&mut network, &mut state)
+            .unwrap();
+
+        assert!(result.converged);
+        let vm = state.voltage_magnitude[1];
+        assert!((0.97..=0.99).contains(&vm));
+        let step = network.switched_shunts[0].step;
+        assert!(step > 0 && step < 8);
+
+        // One step fewer leaves the voltage below the band
+        network.switched_shunts[0].step = step - 1;
+        let mut fixed = AcPowerFlowSolver::new();
+        fixed.max_control_iterations = 0;
+        let mut below = network.initial_state();
+        fixed.solve_network(&network, &mut below).unwrap();
+        assert!(below.voltage_magnitude[1] < 0.97);
+    }
+
+    #[test]
+    fn test_three_winding_transformer_star_equivalent() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        let mut transformer = ThreeWindingTransformer::from_pairwise(
+            [0, 1, 2],
+            (0.01, 0.1),
+            (0.02, 0.15),
+            (0.012, 0.12),
+        );
+        transformer.taps = [1.0, 0.975, 1.0];
+        transformer.ratings = [100.0, 60.0, 40.0];
+        let index = network.add_three_winding_transformer(transformer);
+        network.add_load(Load::new(1, 50.0, 20.0));
+        network.add_load(Load::new(2, 25.0, 10.0));
+
+        // The star bus and windings are part of the network matrices
+        assert_eq!(network.bus_count(), 4);
+        assert_eq!(network.branches.len(), 3);
+        let branches = network.three_winding_transformers[index].branches.unwrap();
+        let x = |w: usize| network.branches[branches[w]].reactance;
+        assert!((x(0) + x(1) - 0.1).abs() < 1e-12);
+        assert!((x(1) + x(2) - 0.15).abs() < 1e-12);
+        assert!((x(2) + x(0) - 0.12).abs() < 1e-12);
+        assert_eq!(network.branches[branches[2]].rating, 40.0);
+
+        let solver = AcPowerFlowSolver::new();
+        let mut state = network.initial_state();
+        assert!(
+            solver
+                .solve_network(&network, &mut state)
+                .unwrap()
+                .converged
+        );
+        let results = solver.three_winding_flows(&network, &state);
+
+        let [primary, secondary, tertiary] = results[0].windings;
+        assert!((secondary.p_from + 50.0).abs() < 1e-6);
+        assert!((tertiary.p_from + 25.0).abs() < 1e-6);
+        assert!((primary.p_from - state.active_power[0]).abs() < 1e-6);
+        assert!((primary.p_from - 75.0 - results[0].losses()).abs() < 1e-6);
+        assert!(results[0].star_voltage_magnitude < 1.0);
+
+        // Re-expanding refreshes the star instead of adding another
+        network.three_winding_transformers[index].in_service = false;
+        network.three_winding_transformers[index].taps[1] = 1.0;
+        network.expand_three_winding_transformers().unwrap();
+        assert_eq!(network.bus_count(), 4);
+        assert_eq!(network.branches.len(), 3);
+        assert!(network.branches.iter().all(|b| !b.in_service));
+        assert_eq!(network.branches[branches[1]].tap_ratio, 1.0);
+
+        // A star branch that does not reach the star bus is rejected
+        network.branches[branches[2]].to_bus = 1;
+        assert!(network.expand_three_winding_transformers().is_err());
+    }
+
+    #[test]
+    fn test_remote_voltage_regulation() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 80.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::transformer(1, 2, 0.0, 0.1, 1.0));
+        network.add_branch(Branch::line(2, 0, 0.01, 0.08));
+        network.add_branch(Branch::line(2, 3, 0.02, 0.1));
+        network.add_generator(Generator::new(1, 80.0, 1.02).with_regulated_bus(2));
+        network.add_load(Load::new(3, 60.0, 40.0));
+
+        let mut state = network.initial_state();
+        let result = AcPowerFlowSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap();
+
+        assert!(result.converged);
+        assert!((state.voltage_magnitude[2] - 1.02).abs() < 1e-8);
+        assert!(state.voltage_magnitude[1] > 1.02);
+        assert!(state.reactive_power[1] > 0.0);
+    }
+
+    #[test]
+    fn test_shared_remote_voltage_control() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 50.0));
+        network.add_bus(Bus::pv(1.0, 50.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::transformer(1, 3, 0.0, 0.08, 1.0));
+        network.add_branch(Branch::transformer(2, 3, 0.0, 0.12, 1.0));
+        network.add_branch(Branch::line(3, 0, 0.01, 0.1));
+        network.add_branch(Branch::line(3, 4, 0.02, 0.08));
+        network.add_generator(Generator::new(1, 50.0, 1.03).with_regulated_bus(3));
+        network.add_generator(
+            Generator::new(2, 50.0, 1.03)
+                .with_regulated_bus(3)
+                .with_reactive_share(2.0),
+        );
+        network.add_load(Load::new(4, 120.0, 60.0));
+
+        let mut state = network.initial_state();
+        let result = AcPowerFlowSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap();
+
+        assert!(result.converged);
+        assert!((state.voltage_magnitude[3] - 1.03).abs() < 1e-8);
+        let (q1, q2) = (state.reactive_power[1], state.reactive_power[2]);
+        assert!(q1 > 0.0);
+        assert!((q2 - 2.0 * q1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,366 @@
+//! Bad-data detection and identification for state estimation
+//!
+//! Detection uses the chi-square test on the WLS objective: with `m`
+//! measurements and `n` state variables, J(x) follows a χ² distribution
+//! with `m − n` degrees of freedom when all errors are Gaussian.
+//!
+//! Identification uses the largest normalized residual test:
+//!
+//! ```text
+//! Ω = R − H·G⁻¹·Hᵀ          (residual covariance)
+//! rᴺᵢ = |rᵢ| / √Ωᵢᵢ
+//! ```
+//!
+//! The measurement with the largest rᴺ above the threshold is removed and
+//! the estimation repeated until the set passes both tests.
+
+use nalgebra::DMatrix;
+use qsim_core::{CoreError, Result, StateStore};
+use qsim_elements::{Measurement, Network};
+
+use crate::{build_ybus, evaluate_measurements, EstimationResult, StateIndex, WlsEstimator};
+
+/// A measurement rejected as bad data
+#[derive(Debug, Clone)]
+pub struct RejectedMeasurement {
+    /// Index into the original measurement list
+    pub index: usize,
+    /// The rejected measurement
+    pub measurement: Measurement,
+    /// Normalized residual at the time of rejection
+    pub normalized_residual: f64,
+}
+
+/// Outcome of bad-data processing
+#[derive(Debug, Clone)]
+pub struct BadDataReport {
+    /// Estimation with the final (cleaned) measurement set
+    pub estimation: EstimationResult,
+    /// Indices (into the original list) of the measurements kept
+    pub retained: Vec<usize>,
+    /// Measurements removed, in order of rejection
+    pub rejected: Vec<RejectedMeasurement>,
+    /// Normalized residuals of the retained measurements
+    pub normalized_residuals: Vec<f64>,
+    /// χ² threshold for the final degrees of freedom
+    pub chi_square_threshold: f64,
+    /// Whether the final objective passes the χ² test
+    pub chi_square_passed: bool,
+    /// Whether removal stopped because dropping the next suspect left the
+    /// system unobservable; the report then describes the last estimate
+    /// that succeeded
+    pub stopped_unobservable: bool,
+}
+
+/// Bad-data detector wrapping a WLS estimator
+#[derive(Debug, Clone)]
+pub struct BadDataDetector {
+    /// Estimator used for every pass
+    pub estimator: WlsEstimator,
+    /// Confidence level of the χ² test (e.g. 0.95)
+    pub confidence: f64,
+    /// Normalized residual above which a measurement is suspect
+    pub normalized_threshold: f64,
+    /// Maximum number of measurements to remove
+    pub max_removals: usize,
+}
+
+impl BadDataDetector {
+    /// Create a detector with 95 % confidence and a threshold of 3.0
+    pub fn new() -> Self {
+        Self {
+            estimator: WlsEstimator::new(),
+            confidence: 0.95,
+            normalized_threshold: 3.0,
+            max_removals: 10,
+        }
+    }
+}
+
+impl Default for BadDataDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Inverse of the standard normal CDF (Acklam's rational approximation)
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// χ² quantile for `dof` degrees of freedom (Wilson-Hilferty approximation)
+pub fn chi_square_threshold(dof: usize, confidence: f64) -> f64 {
+    if dof == 0 {
+        return 0.0;
+    }
+    let k = dof as f64;
+    let z = normal_quantile(confidence);
+    let a = 2.0 / (9.0 * k);
+    k * (1.0 - a + z * a.sqrt()).powi(3)
+}
+
+/// Normalized residuals |rᵢ| / √Ωᵢᵢ at the estimated `state`
+///
+/// Critical measurements (Ωᵢᵢ ≈ 0) get a normalized residual of 0.0, since
+/// their errors cannot be detected.
+pub fn normalized_residuals(
+    network: &Network,
+    measurements: &[Measurement],
+    state: &StateStore,
+    residuals: &[f64],
+) -> Result<Vec<f64>> {
+    let index = StateIndex::new(network, measurements)
+        .ok_or_else(|| CoreError::SimulationError("No buses in network".into()))?;
+    let ybus = build_ybus(network);
+    let (_, jacobian) = evaluate_measurements(
+        network,
+        &ybus,
+        measurements,
+        &index,
+        &state.voltage_magnitude,
+        &state.voltage_angle,
+    );
+
+    let weights: Vec<f64> = measurements.iter().map(|m| m.weight()).collect();
+    let weighted_h = DMatrix::from_fn(jacobian.nrows(), jacobian.ncols(), |r, c| {
+        jacobian[(r, c)] * weights[r]
+    });
+    let gain_inv = (jacobian.transpose() * &weighted_h)
+        .try_inverse()
+        .ok_or_else(|| CoreError::NotObservable("gain matrix is singular".into()))?;
+
+    Ok((0..measurements.len())
+        .map(|i| {
+            let h_i = jacobian.row(i);
+            let projected = (h_i * &gain_inv * h_i.transpose())[0];
+            let omega = measurements[i].std_dev.powi(2) - projected;
+            let scale = 1e-10 * measurements[i].std_dev.powi(2);
+            if omega > scale {
+                residuals[i].abs() / omega.sqrt()
+            } else {
+                0.0
+            }
+        })
+        .collect())
+}
+
+impl BadDataDetector {
+    /// Estimate the state, removing bad measurements one at a time
+    ///
+    /// `state` provides the initial guess for every pass and receives the
+    /// final estimate. If removing a suspect makes the system unobservable,
+    /// the suspect is kept and the previous estimate is returned with
+    /// `stopped_unobservable` set. Any other error is returned as is.
+    pub fn run(
+        &self,
+        network: &Network,
+        measurements: &[Measurement],
+        state: &mut StateStore,
+    ) -> Result<BadDataReport> {
+        let initial = state.clone();
+        let mut retained: Vec<usize> = (0..measurements.len()).collect();
+        let mut rejected = Vec::new();
+        let mut previous: Option<(BadDataReport, StateStore)> = None;
+
+        loop {
+            let active: Vec<Measurement> =
+                retained.iter().map(|&i| measurements[i].clone()).collect();
+            *state = initial.clone();
+            let pass = self
+                .estimator
+                .estimate(network, &active, state)
+                .and_then(|estimation| {
+                    normalized_residuals(network, &active, state, &estimation.residuals)
+                        .map(|normalized| (estimation, normalized))
+                });
+            let (estimation, normalized) = match (pass, previous.take()) {
+                (Ok(pass), _) => pass,
+                (Err(CoreError::NotObservable(_)), Some((mut report, last_state))) => {
+                    report.stopped_unobservable = true;
+                    *state = last_state;
+                    return Ok(report);
+                }
+                (Err(e), _) => return Err(e),
+            };
+
+            let state_len = StateIndex::new(network, &active).map_or(0, |index| index.len);
+            let dof = active.len().saturating_sub(state_len);
+            let threshold = chi_square_threshold(dof, self.confidence);
+            let chi_square_passed = estimation.objective <= threshold;
+
+            let worst = normalized
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(k, &r)| (k, r));
+
+            let suspect = match worst {
+                Some((k, r)) if r > self.normalized_threshold => Some((k, r)),
+                _ => None,
+            };
+            let done = chi_square_passed && suspect.is_none();
+            let report = BadDataReport {
+                estimation,
+                retained: retained.clone(),
+                rejected: rejected.clone(),
+                normalized_residuals: normalized,
+                chi_square_threshold: threshold,
+                chi_square_passed,
+                stopped_unobservable: false,
+            };
+
+            match suspect {
+                Some((k, r)) if !done && rejected.len() < self.max_removals => {
+                    previous = Some((report, state.clone()));
+                    let index = retained.remove(k);
+                    rejected.push(RejectedMeasurement {
+                        index,
+                        measurement: measurements[index].clone(),
+                        normalized_residual: r,
+                    });
+                }
+                _ => return Ok(report),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ac_branch_flows;
+    use crate::estimation::tests::{four_bus, measurements};
+    use qsim_elements::{Branch, BranchEnd, Bus, Generator, Load, MeasurementKind};
+
+    #[test]
+    fn test_chi_square_threshold() {
+        // Tabulated χ² 0.95 quantiles
+        assert!((chi_square_threshold(10, 0.95) - 18.307).abs() < 0.05);
+        assert!((chi_square_threshold(30, 0.95) - 43.773).abs() < 0.05);
+        assert!((chi_square_threshold(1, 0.99) - 6.635).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_gross_error_identified() {
+        let network = four_bus();
+        let (truth, mut measurements) = measurements(&network);
+        let bad = 7;
+        measurements[bad].value += 25.0 * measurements[bad].std_dev;
+
+        let mut state = StateStore::new(network.bus_count());
+        let report = BadDataDetector::new()
+            .run(&network, &measurements, &mut state)
+            .unwrap();
+
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].index, bad);
+        assert!(report.chi_square_passed);
+        assert!(!report.retained.contains(&bad));
+        for i in 0..network.bus_count() {
+            assert!((state.voltage_magnitude[i] - truth.voltage_magnitude[i]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_clean_set_passes() {
+        let network = four_bus();
+        let (_, measurements) = measurements(&network);
+        let mut state = StateStore::new(network.bus_count());
+        let report = BadDataDetector::new()
+            .run(&network, &measurements, &mut state)
+            .unwrap();
+
+        assert!(report.rejected.is_empty());
+        assert!(report.chi_square_passed);
+        assert_eq!(report.retained.len(), measurements.len());
+    }
+
+    #[test]
+    fn test_unobservable_removal_keeps_last_report() {
+        // Lossless line: at flat start the Q flows carry no angle
+        // information, so dropping the P flow leaves θ₁ unobservable
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_generator(Generator::new(0, 0.0, 1.0));
+        network.add_load(Load::new(1, 40.0, 10.0));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.1));
+        let (truth, mut measurements) = measurements(&network);
+        let q_to = ac_branch_flows(&network, &truth)[0].q_to;
+        measurements.push(Measurement::reactive_flow(0, BranchEnd::To, q_to, 0.8));
+        measurements.retain(|m| {
+            matches!(
+                m.kind,
+                MeasurementKind::VoltageMagnitude { .. }
+                    | MeasurementKind::ActiveFlow {
+                        end: BranchEnd::From,
+                        ..
+                    }
+                    | MeasurementKind::ReactiveFlow { .. }
+            )
+        });
+        let bad = measurements
+            .iter()
+            .position(|m| matches!(m.kind, MeasurementKind::ActiveFlow { .. }))
+            .unwrap();
+        measurements[bad].value += 30.0 * measurements[bad].std_dev;
+
+        let detector = BadDataDetector {
+            normalized_threshold: 2.0,
+            ..BadDataDetector::new()
+        };
+        let mut state = StateStore::new(network.bus_count());
+        let report = detector.run(&network, &measurements, &mut state).unwrap();
+
+        assert!(report.stopped_unobservable);
+        assert!(report.rejected.is_empty());
+        assert!(report.retained.contains(&bad));
+        assert!(report.normalized_residuals[bad] > detector.normalized_threshold);
+        // `state` holds the last successful estimate, not the flat start
+        assert!(state.voltage_angle[1] < 0.0);
+    }
+}
@@ -0,0 +1,473 @@
+//! Contingency analysis
+//!
+//! Solves a base case and a set of outage scenarios (N-1, N-k) and reports
+//! the limit violations each one causes. Scenarios are independent and are
+//! solved in parallel.
+//!
+//! Outages that split the network into more islands than the base case has
+//! are reported as [`ContingencyOutcome::Islanded`] instead of being solved.
+
+use qsim_core::{CoreError, Result, StateStore};
+use qsim_elements::{BusType, Network};
+use rayon::prelude::*;
+
+use crate::NetworkSolver;
+
+/// A single element taken out of service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outage {
+    /// Branch outage (index into `Network::branches`)
+    Branch(usize),
+    /// Generator outage (index into `Network::generators`)
+    Generator(usize),
+}
+
+/// A named set of simultaneous outages
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contingency {
+    /// Contingency name
+    pub name: String,
+    /// Elements taken out of service
+    pub outages: Vec<Outage>,
+}
+
+impl Contingency {
+    /// Create a contingency from a list of outages
+    pub fn new(name: impl Into<String>, outages: Vec<Outage>) -> Self {
+        Self {
+            name: name.into(),
+            outages,
+        }
+    }
+
+    /// Single branch outage
+    pub fn branch(index: usize) -> Self {
+        Self::new(format!("Branch {index}"), vec![Outage::Branch(index)])
+    }
+
+    /// Single generator outage
+    pub fn generator(index: usize) -> Self {
+        Self::new(format!("Generator {index}"), vec![Outage::Generator(index)])
+    }
+
+    /// Copy of `network` with the outaged elements out of service
+    ///
+    /// A PV bus left without any in-service generator becomes a PQ bus.
+    pub fn apply(&self, network: &Network) -> Result<Network> {
+        let mut network = network.clone();
+
+        for outage in &self.outages {
+            match *outage {
+                Outage::Branch(i) => {
+                    let branch = network
+                        .branches
+                        .get_mut(i)
+                        .ok_or(CoreError::InvalidBranchId(i))?;
+                    branch.in_service = false;
+                }
+                Outage::Generator(i) => {
+                    let generator = network.generators.get_mut(i).ok_or_else(|| {
+                        CoreError::SimulationError(format!("Invalid generator index: {i}"))
+                    })?;
+                    generator.in_service = false;
+                    let bus = generator.bus;
+                    let regulated = network
+                        .generators
+                        .iter()
+                        .any(|g| g.in_service && g.bus == bus);
+                    let bus = network
+                        .buses
+                        .get_mut(bus)
+                        .ok_or(CoreError::InvalidBusId(bus))?;
+                    if !regulated && bus.bus_type == BusType::PV {
+                        bus.bus_type = BusType::PQ;
+                    }
+                }
+            }
+        }
+
+        Ok(network)
+    }
+}
+
+/// All single outages of in-service branches and generators
+pub fn n_minus_1(network: &Network) -> Vec<Contingency> {
+    let branches = network
+        .branches
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.in_service)
+        .map(|(i, _)| Contingency::branch(i));
+    let generators = network
+        .generators
+        .iter()
+        .enumerate()
+        .filter(|(_, g)| g.in_service)
+        .map(|(i, _)| Contingency::generator(i));
+
+    branches.chain(generators).collect()
+}
+
+/// All combinations of `k` simultaneous in-service branch outages
+pub fn n_minus_k_branches(network: &Network, k: usize) -> Vec<Contingency> {
+    let candidates: Vec<usize> = network
+        .branches
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.in_service)
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut contingencies = Vec::new();
+    if k == 0 || k > candidates.len() {
+        return contingencies;
+    }
+
+    let mut combination: Vec<usize> = (0..k).collect();
+    loop {
+        let outages: Vec<Outage> = combination
+            .iter()
+            .map(|&c| Outage::Branch(candidates[c]))
+            .collect();
+        let name = combination
+            .iter()
+            .map(|&c| candidates[c].to_string())
+            .collect::<Vec<_>>()
+            .join("+");
+        contingencies.push(Contingency::new(format!("Branches {name}"), outages));
+
+        // Advance to the next combination in lexicographic order
+        let Some(pos) = (0..k).rev().find(|&i| combination[i] < candidates.len() - k + i) else {
+            break;
+        };
+        combination[pos] += 1;
+        for i in pos + 1..k {
+            combination[i] = combination[i - 1] + 1;
+        }
+    }
+
+    contingencies
+}
+
+/// A limit violation found in a solved case
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// Branch apparent power flow above its rating
+    BranchOverload {
+        branch: usize,
+        /// Flow (MVA)
+        flow: f64,
+        /// Rating (MVA)
+        rating: f64,
+        /// Loading (percent of rating)
+        loading: f64,
+    },
+    /// Bus voltage below its minimum
+    UnderVoltage { bus: usize, voltage: f64, limit: f64 },
+    /// Bus voltage above its maximum
+    OverVoltage { bus: usize, voltage: f64, limit: f64 },
+}
+
+/// How a contingency case ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContingencyOutcome {
+    /// Power flow solved; see the violations
+    Solved,
+    /// Outage split the network into islands (bus indices per island)
+    Islanded { islands: Vec<Vec<usize>> },
+    /// Power flow did not converge
+    NotConverged { mismatch: f64 },
+    /// Power flow failed (e.g. singular matrix)
+    Failed(String),
+}
+
+/// Result of a single contingency case
+#[derive(Debug, Clone)]
+pub struct ContingencyResult {
+    /// The contingency that was studied
+    pub contingency: Contingency,
+    /// How the case ended
+    pub outcome: ContingencyOutcome,
+    /// Limit violations (empty unless the case solved)
+    pub violations: Vec<Violation>,
+}
+
+impl ContingencyResult {
+    /// Whether the case solved without violations
+    pub fn is_secure(&self) -> bool {
+        self.outcome == ContingencyOutcome::Solved && self.violations.is_empty()
+    }
+
+    /// Whether the outage caused islanding
+    pub fn is_islanded(&self) -> bool {
+        matches!(self.outcome, ContingencyOutcome::Islanded { .. })
+    }
+}
+
+/// Violation report for a contingency study
+#[derive(Debug, Clone)]
+pub struct ContingencyReport {
+    /// Violations present in the base case
+    pub base_violations: Vec<Violation>,
+    /// One result per contingency, in input order
+    pub results: Vec<ContingencyResult>,
+}
+
+impl ContingencyReport {
+    /// Contingencies that are not secure
+    pub fn insecure(&self) -> impl Iterator<Item = &ContingencyResult> {
+        self.results.iter().filter(|r| !r.is_secure())
+    }
+
+    /// Contingencies that caused islanding
+    pub fn islanded(&self) -> impl Iterator<Item = &ContingencyResult> {
+        self.results.iter().filter(|r| r.is_islanded())
+    }
+}
+
+/// Contingency analysis driver
+///
+/// Works with any [`NetworkSolver`], so the same study can run with
+/// [`DcPowerFlowSolver`](crate::DcPowerFlowSolver) for speed or
+/// [`AcPowerFlowSolver`](crate::AcPowerFlowSolver) for voltage checks.
+#[derive(Debug, Clone)]
+pub struct ContingencyAnalysis<S> {
+    /// Power flow solver used for every case
+    pub solver: S,
+    /// Branch loading above which an overload is reported (percent)
+    pub loading_limit: f64,
+}
+
+impl<S: NetworkSolver> ContingencyAnalysis<S> {
+    /// Create an analysis with a 100 % loading limit
+    pub fn new(solver: S) -> Self {
+        Self {
+            solver,
+            loading_limit: 100.0,
+        }
+    }
+
+    /// Solve the base case and all contingencies
+    ///
+    /// Fails only if the base case itself cannot be solved.
+    pub fn run(&self, network: &Network, contingencies: &[Contingency]) -> Result<ContingencyReport> {
+        let mut base_state = network.initial_state();
+        let base = self.solver.solve_network(network, &mut base_state)?;
+        if !base.converged {
+            return Err(CoreError::SimulationError(format!(
+                "Base case did not converge (mismatch {:.3e})",
+                base.convergence_error
+            )));
+        }
+        let base_violations = self.check_violations(network, &base_state);
+
+        let results = contingencies
+            .par_iter()
+            .map(|c| self.run_contingency(network, c, Some(&base_state)))
+            .collect();
+
+        Ok(ContingencyReport {
+            base_violations,
+            results,
+        })
+    }
+
+    /// Solve a single contingency, optionally warm-starting from `base_state`
+    pub fn run_contingency(
+        &self,
+        network: &Network,
+        contingency: &Contingency,
+        base_state: Option<&StateStore>,
+    ) -> ContingencyResult {
+        let result = |outcome, violations| ContingencyResult {
+            contingency: contingency.clone(),
+            outcome,
+            violations,
+        };
+
+        let case = match contingency.apply(network) {
+            Ok(case) => case,
+            Err(e) => return result(ContingencyOutcome::Failed(e.to_string()), Vec::new()),
+        };
+
+        let islands = case.topology().islands();
+        if islands.len() > network.topology().islands().len() {
+            let islands = islands
+                .into_iter()
+                .map(|island| island.into_iter().map(|b| b.0).collect())
+                .collect();
+            return result(ContingencyOutcome::Islanded { islands }, Vec::new());
+        }
+
+        let mut state = case.initial_state();
+        if let Some(base) = base_state {
+            for (i, bus) in case.buses.iter().enumerate() {
+                if bus.bus_type == BusType::PQ {
+                    state.voltage_magnitude[i] = base.voltage_magnitude[i];
+                }
+            }
+            state.voltage_angle.copy_from_slice(&base.voltage_angle);
+        }
+
+        match self.solver.solve_network(&case, &mut state) {
+            Ok(solved) if solved.converged => {
+                let violations = self.check_violations(&case, &state);
+                result(ContingencyOutcome::Solved, violations)
+            }
+            Ok(solved) => result(
+                ContingencyOutcome::NotConverged {
+                    mismatch: solved.convergence_error,
+                },
+                Vec::new(),
+            ),
+            Err(e) => result(ContingencyOutcome::Failed(e.to_string()), Vec::new()),
+        }
+    }
+
+    /// Branch overloads and voltage violations in a solved state
+    pub fn check_violations(&self, network: &Network, state: &StateStore) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for flow in self.solver.branch_flows(network, state) {
+            let rating = network.branches[flow.branch].rating;
+            if rating <= 0.0 {
+                continue;
+            }
+            let loading = flow.s_max() / rating * 100.0;
+            if loading > self.loading_limit {
+                violations.push(Violation::BranchOverload {
+                    branch: flow.branch,
+                    flow: flow.s_max(),
+                    rating,
+                    loading,
+                });
+            }
+        }
+
+        for (i, bus) in network.buses.iter().enumerate() {
+            let voltage = state.voltage_magnitude[i];
+            if voltage < bus.v_min {
+                violations.push(Violation::UnderVoltage {
+                    bus: i,
+                    voltage,
+                    limit: bus.v_min,
+                });
+            } else if voltage > bus.v_max {
+                violations.push(Violation::OverVoltage {
+                    bus: i,
+                    voltage,
+                    limit: bus.v_max,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AcPowerFlowSolver, DcPowerFlowSolver};
+    use qsim_elements::{Branch, Bus, Generator, Load};
+
+    /// Triangle 0-1-2 with a radial spur 2-3
+    fn ring_with_spur() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_load(Load::new(1, 60.0, 10.0));
+        network.add_load(Load::new(3, 40.0, 10.0));
+        for (f, t) in [(0, 1), (1, 2), (0, 2), (2, 3)] {
+            let mut branch = Branch::line(f, t, 0.01, 0.1);
+            branch.rating = 80.0;
+            network.add_branch(branch);
+        }
+        network
+    }
+
+    #[test]
+    fn test_n_minus_1_dc() {
+        let network = ring_with_spur();
+        let contingencies = n_minus_1(&network);
+        assert_eq!(contingencies.len(), 4);
+
+        let report = ContingencyAnalysis::new(DcPowerFlowSolver::new())
+            .run(&network, &contingencies)
+            .unwrap();
+        assert!(report.base_violations.is_empty());
+
+        // Losing 0-2 forces all 100 MW through 0-1
+        let outage = &report.results[2];
+        assert_eq!(outage.outcome, ContingencyOutcome::Solved);
+        assert!(matches!(
+            outage.violations[..],
+            [Violation::BranchOverload { branch: 0, .. }]
+        ));
+
+        // Losing the spur islands bus 3
+        let spur = &report.results[3];
+        assert_eq!(
+            spur.outcome,
+            ContingencyOutcome::Islanded {
+                islands: vec![vec![0, 1, 2], vec![3]]
+            }
+        );
+        assert_eq!(report.islanded().count(), 1);
+    }
+
+    #[test]
+    fn test_n_minus_1_ac_voltages() {
+        let mut network = ring_with_spur();
+        for bus in &mut network.buses {
+            bus.v_min = 0.95;
+        }
+        let report = ContingencyAnalysis::new(AcPowerFlowSolver::new())
+            .run(&network, &[Contingency::branch(0)])
+            .unwrap();
+
+        let result = &report.results[0];
+        assert_eq!(result.outcome, ContingencyOutcome::Solved);
+        assert!(result
+            .violations
+            .iter()
+            .any(|v| matches!(v, Violation::UnderVoltage { bus: 1, .. })));
+    }
+
+    #[test]
+    fn test_n_minus_k_combinations() {
+        let network = ring_with_spur();
+        let pairs = n_minus_k_branches(&network, 2);
+        assert_eq!(pairs.len(), 6);
+        assert_eq!(pairs[0].outages, vec![Outage::Branch(0), Outage::Branch(1)]);
+        assert_eq!(pairs[5].outages, vec![Outage::Branch(2), Outage::Branch(3)]);
+    }
+
+    #[test]
+    fn test_islanding_is_relative_to_base_case() {
+        // An isolated bus already makes two islands before any outage
+        let mut network = ring_with_spur();
+        network.add_bus(Bus::pq(0.0, 0.0));
+        let analysis = ContingencyAnalysis::new(DcPowerFlowSolver::new());
+
+        let ring = analysis.run_contingency(&network, &Contingency::branch(2), None);
+        assert!(!matches!(ring.outcome, ContingencyOutcome::Islanded { .. }));
+        let spur = analysis.run_contingency(&network, &Contingency::branch(3), None);
+        assert_eq!(
+            spur.outcome,
+            ContingencyOutcome::Islanded {
+                islands: vec![vec![0, 1, 2], vec![3], vec![4]]
+            }
+        );
+    }
+
+    #[test]
+    fn test_generator_on_invalid_bus_is_an_error() {
+        let mut network = ring_with_spur();
+        network.add_generator(Generator::new(9, 10.0, 1.0));
+        let outage = Contingency::generator(0).apply(&network);
+        assert!(matches!(outage, Err(CoreError::InvalidBusId(9))));
+    }
+}
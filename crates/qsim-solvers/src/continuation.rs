@@ -0,0 +1,499 @@
+//! Continuation power flow for voltage stability margins
+//!
+//! Injections are scaled along a direction vector `d`:
+//!
+//! ```text
+//! S_spec(λ) = S_0 + λ·d
+//! ```
+//!
+//! and the solution curve is traced with predictor-corrector steps. The
+//! tangent predictor solves
+//!
+//! ```text
+//! [ J   −d ] [t_x]   [0]
+//! [   e_k  ] [t_λ] = [±1]
+//! ```
+//!
+//! and the corrector holds the continuation parameter `k` at its predicted
+//! value. After each step, `k` switches to the largest tangent component, so
+//! λ parameterizes the upper branch and a voltage takes over near the nose,
+//! where the ordinary Jacobian becomes singular.
+//!
+//! Base-case loads follow their voltage models (ZIP or exponential); the
+//! increments `λ·d` are constant power. Remote voltage control is treated
+//! as local.
+
+use nalgebra::{DMatrix, DVector};
+use num_complex::Complex64;
+use qsim_core::{CoreError, Result};
+use qsim_elements::Network;
+
+use crate::{
+    build_jacobian, build_ybus, newton_raphson, power_injections, BusTypes, VoltageDependentLoads,
+};
+
+/// Injection change per unit of the loading parameter λ
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadingDirection {
+    /// Active injection change per bus (MW, negative = more load)
+    pub active: Vec<f64>,
+    /// Reactive injection change per bus (MVAr, negative = more load)
+    pub reactive: Vec<f64>,
+}
+
+impl LoadingDirection {
+    /// Scale loads and generation together (λ = 1 doubles every injection)
+    pub fn proportional(network: &Network) -> Self {
+        let state = network.initial_state();
+        Self {
+            active: state.active_power,
+            reactive: state.reactive_power,
+        }
+    }
+
+    /// Scale loads at constant power factor; the slack bus covers the increase
+    pub fn loads(network: &Network) -> Self {
+        let mut active = vec![0.0; network.bus_count()];
+        let mut reactive = vec![0.0; network.bus_count()];
+        for load in network.loads.iter().filter(|l| l.in_service) {
+            active[load.bus] -= load.active_power;
+            reactive[load.bus] -= load.reactive_power;
+        }
+        Self { active, reactive }
+    }
+
+    /// Increase reactive demand at one bus by 1 MVAr per unit λ (QV curve)
+    pub fn reactive_load(network: &Network, bus: usize) -> Self {
+        let mut reactive = vec![0.0; network.bus_count()];
+        reactive[bus] = -1.0;
+        Self {
+            active: vec![0.0; network.bus_count()],
+            reactive,
+        }
+    }
+}
+
+/// A solved point on the continuation curve
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContinuationPoint {
+    /// Loading parameter
+    pub lambda: f64,
+    /// Bus voltage magnitudes (per-unit)
+    pub voltage_magnitude: Vec<f64>,
+    /// Bus voltage angles (radians)
+    pub voltage_angle: Vec<f64>,
+}
+
+/// Traced curve and loadability margin
+#[derive(Debug, Clone)]
+pub struct ContinuationResult {
+    /// Solved points in tracing order, starting at the base case (λ = 0)
+    pub points: Vec<ContinuationPoint>,
+    /// Index of the nose point (largest λ)
+    pub nose: usize,
+    /// Whether the curve was traced past the nose
+    pub nose_found: bool,
+    /// Loadability margin per bus at the nose, `λ_max·|d|` (MVA)
+    pub margins: Vec<f64>,
+}
+
+impl ContinuationResult {
+    /// Loading parameter at the nose point
+    pub fn max_lambda(&self) -> f64 {
+        self.points[self.nose].lambda
+    }
+
+    /// (λ, V) pairs for one bus along the curve
+    pub fn pv_curve(&self, bus: usize) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .map(|p| (p.lambda, p.voltage_magnitude[bus]))
+            .collect()
+    }
+}
+
+/// Continuation power flow with local parameterization
+#[derive(Debug, Clone)]
+pub struct ContinuationPowerFlow {
+    /// Predictor step length (in the normalized tangent direction)
+    pub step_size: f64,
+    /// Smallest step before tracing stops
+    pub min_step_size: f64,
+    /// Corrector convergence tolerance (per-unit mismatch)
+    pub tolerance: f64,
+    /// Maximum corrector iterations per point
+    pub max_iterations: usize,
+    /// Maximum number of points on the curve
+    pub max_points: usize,
+}
+
+impl ContinuationPowerFlow {
+    /// Create a solver with default settings
+    pub fn new() -> Self {
+        Self {
+            step_size: 0.1,
+            min_step_size: 1e-4,
+            tolerance: 1e-8,
+            max_iterations: 10,
+            max_points: 500,
+        }
+    }
+
+    /// Builder: set the predictor step length
+    pub fn with_step_size(mut self, step_size: f64) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl Default for ContinuationPowerFlow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fixed problem data for one trace
+struct Problem {
+    ybus: DMatrix<Complex64>,
+    angle_buses: Vec<usize>,
+    magnitude_buses: Vec<usize>,
+    /// Base and direction injections (per-unit)
+    s_base: Vec<Complex64>,
+    direction: Vec<Complex64>,
+    /// Voltage dependence of the base-case loads
+    loads: VoltageDependentLoads,
+}
+
+impl Problem {
+    fn dim(&self) -> usize {
+        self.angle_buses.len() + self.magnitude_buses.len()
+    }
+
+    /// Pack voltages and λ into the continuation vector
+    fn pack(&self, vm: &[f64], va: &[f64], lambda: f64) -> DVector<f64> {
+        let n_theta = self.angle_buses.len();
+        let mut x = DVector::zeros(self.dim() + 1);
+        for (k, &i) in self.angle_buses.iter().enumerate() {
+            x[k] = va[i];
+        }
+        for (k, &i) in self.magnitude_buses.iter().enumerate() {
+            x[n_theta + k] = vm[i];
+        }
+        x[self.dim()] = lambda;
+        x
+    }
+
+    /// Write the continuation vector back to voltages, returning λ
+    fn unpack(&self, x: &DVector<f64>, vm: &mut [f64], va: &mut [f64]) -> f64 {
+        let n_theta = self.angle_buses.len();
+        for (k, &i) in self.angle_buses.iter().enumerate() {
+            va[i] = x[k];
+        }
+        for (k, &i) in self.magnitude_buses.iter().enumerate() {
+            vm[i] = x[n_theta + k];
+        }
+        x[self.dim()]
+    }
+
+    /// Mismatch `S_0 + λ·d − S(V)` on the P and Q rows
+    fn mismatch(&self, vm: &[f64], va: &[f64], lambda: f64) -> DVector<f64> {
+        let n_theta = self.angle_buses.len();
+        let s_calc = power_injections(&self.ybus, vm, va);
+        let s_base = self.loads.injections(&self.s_base, vm);
+        let spec = |i: usize| s_base[i] + self.direction[i] * lambda - s_calc[i];
+        let mut f = DVector::zeros(self.dim());
+        for (k, &i) in self.angle_buses.iter().enumerate() {
+            f[k] = spec(i).re;
+        }
+        for (k, &i) in self.magnitude_buses.iter().enumerate() {
+            f[n_theta + k] = spec(i).im;
+        }
+        f
+    }
+
+    /// Jacobian augmented with the λ column and the parameter row `e_k`
+    fn augmented_jacobian(&self, vm: &[f64], va: &[f64], parameter: usize) -> DMatrix<f64> {
+        let n_theta = self.angle_buses.len();
+        let dim = self.dim();
+        let jacobian = build_jacobian(
+            &self.ybus,
+            vm,
+            va,
+            &self.angle_buses,
+            &self.magnitude_buses,
+            &self.magnitude_buses,
+        );
+        let mut augmented = DMatrix::zeros(dim + 1, dim + 1);
+        augmented.view_mut((0, 0), (dim, dim)).copy_from(&jacobian);
+        if !self.loads.is_empty() {
+            let ds_spec = self.loads.derivatives(vm.len(), vm);
+            for (c, &k) in self.magnitude_buses.iter().enumerate() {
+                if let Ok(r) = self.angle_buses.binary_search(&k) {
+                    augmented[(r, n_theta + c)] -= ds_spec[k].re;
+                }
+                augmented[(n_theta + c, n_theta + c)] -= ds_spec[k].im;
+            }
+        }
+        for (k, &i) in self.angle_buses.iter().enumerate() {
+            augmented[(k, dim)] = -self.direction[i].re;
+        }
+        for (k, &i) in self.magnitude_buses.iter().enumerate() {
+            augmented[(n_theta + k, dim)] = -self.direction[i].im;
+        }
+        augmented[(dim, parameter)] = 1.0;
+        augmented
+    }
+}
+
+impl ContinuationPowerFlow {
+    /// Trace the solution curve of `network` along `direction`
+    ///
+    /// Starts from the base-case power flow and stops when λ falls back
+    /// below zero on the lower branch, the step size drops below
+    /// `min_step_size`, or `max_points` points have been traced.
+    pub fn trace(
+        &self,
+        network: &Network,
+        direction: &LoadingDirection,
+    ) -> Result<ContinuationResult> {
+        let n = network.bus_count();
+        if direction.active.len() != n || direction.reactive.len() != n {
+            return Err(CoreError::SimulationError(format!(
+                "Loading direction has {} buses, network has {n}",
+                direction.active.len()
+            )));
+        }
+        let types = BusTypes::from_network(network)
+            .ok_or_else(|| CoreError::SimulationError("No buses in network".into()))?
+            .local_control();
+
+        let base = network.base_mva;
+        let mut state = network.initial_state();
+        let s_base: Vec<Complex64> = (0..n)
+            .map(|i| Complex64::new(state.active_power[i], state.reactive_power[i]) / base)
+            .collect();
+        let mut angle_buses: Vec<usize> = types.pv.iter().chain(&types.pq).copied().collect();
+        angle_buses.sort_unstable();
+        let mut magnitude_buses = types.pq.clone();
+        magnitude_buses.sort_unstable();
+        let problem = Problem {
+            ybus: build_ybus(network),
+            angle_buses,
+            magnitude_buses,
+            s_base,
+            direction: (0..n)
+                .map(|i| Complex64::new(direction.active[i], direction.reactive[i]) / base)
+                .collect(),
+            loads: VoltageDependentLoads::from_network(network),
+        };
+
+        // Base case
+        state.voltage_magnitude[types.slack] = network.voltage_setpoint(types.slack);
+        for &i in &types.pv {
+            state.voltage_magnitude[i] = network.voltage_setpoint(i);
+        }
+        let mut vm = state.voltage_magnitude;
+        let mut va = state.voltage_angle;
+        let outcome = newton_raphson(
+            &problem.ybus,
+            &types,
+            &problem.s_base,
+            &problem.loads,
+            &mut vm,
+            &mut va,
+            self.tolerance,
+            self.max_iterations.max(20),
+        )?;
+        if !outcome.converged {
+            return Err(CoreError::SimulationError(
+                "Base case power flow did not converge".into(),
+            ));
+        }
+
+        let dim = problem.dim();
+        let mut points = vec![ContinuationPoint {
+            lambda: 0.0,
+            voltage_magnitude: vm.clone(),
+            voltage_angle: va.clone(),
+        }];
+        let mut x = problem.pack(&vm, &va, 0.0);
+        let mut parameter = dim;
+        let mut sign = 1.0;
+        let mut step = self.step_size;
+
+        while points.len() < self.max_points {
+            // Tangent predictor, then switch to its largest component
+            let mut rhs = DVector::zeros(dim + 1);
+            rhs[dim] = sign;
+            let tangent = problem
+                .augmented_jacobian(&vm, &va, parameter)
+                .lu()
+                .solve(&rhs)
+                .ok_or_else(|| CoreError::SimulationError("Singular tangent system".into()))?;
+            let tangent = tangent.normalize();
+            parameter = tangent.iamax();
+            sign = tangent[parameter].signum();
+
+            // Corrector at the predicted value of the continuation parameter
+            let corrected = loop {
+                let mut candidate = &x + &tangent * step;
+                let (mut trial_vm, mut trial_va) = (vm.clone(), va.clone());
+                if self.correct(
+                    &problem,
+                    &mut candidate,
+                    parameter,
+                    &mut trial_vm,
+                    &mut trial_va,
+                ) {
+                    break Some((candidate, trial_vm, trial_va));
+                }
+                step /= 2.0;
+                if step < self.min_step_size {
+                    break None;
+                }
+            };
+            let Some((solution, solved_vm, solved_va)) = corrected else {
+                break;
+            };
+
+            x = solution;
+            vm = solved_vm;
+            va = solved_va;
+            let lambda = x[dim];
+            if lambda < 0.0 {
+                break;
+            }
+            points.push(ContinuationPoint {
+                lambda,
+                voltage_magnitude: vm.clone(),
+                voltage_angle: va.clone(),
+            });
+            step = (step * 2.0).min(self.step_size);
+        }
+
+        let nose = points
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.lambda.total_cmp(&b.1.lambda))
+            .map(|(i, _)| i)
+            .unwrap_or_default();
+        let max_lambda = points[nose].lambda;
+        let margins = (0..n)
+            .map(|i| max_lambda * direction.active[i].hypot(direction.reactive[i]))
+            .collect();
+        Ok(ContinuationResult {
+            nose_found: nose + 1 < points.len(),
+            points,
+            nose,
+            margins,
+        })
+    }
+
+    /// Newton corrector with the continuation parameter held fixed
+    ///
+    /// `vm`/`va` supply the fixed slack and PV values and receive the result.
+    fn correct(
+        &self,
+        problem: &Problem,
+        x: &mut DVector<f64>,
+        parameter: usize,
+        vm: &mut [f64],
+        va: &mut [f64],
+    ) -> bool {
+        let dim = problem.dim();
+        for _ in 0..=self.max_iterations {
+            let lambda = problem.unpack(x, vm, va);
+            let f = problem.mismatch(vm, va, lambda);
+            if f.amax() < self.tolerance {
+                return true;
+            }
+            let mut rhs = DVector::zeros(dim + 1);
+            rhs.rows_mut(0, dim).copy_from(&f);
+            let Some(dx) = problem
+                .augmented_jacobian(vm, va, parameter)
+                .lu()
+                .solve(&rhs)
+            else {
+                return false;
+            };
+            *x += dx;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_elements::{Branch, Bus, Load, LoadModel};
+
+    /// Lossless two-bus system with X = 0.1 p.u.
+    fn two_bus(p: f64, q: f64) -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.1));
+        network.add_load(Load::new(1, p, q));
+        network
+    }
+
+    #[test]
+    fn test_pv_curve_nose_matches_analytic_limit() {
+        // Unity power factor: P_max = V²/(2X) = 500 MW at V = 1/√2
+        let network = two_bus(100.0, 0.0);
+        let result = ContinuationPowerFlow::new()
+            .trace(&network, &LoadingDirection::loads(&network))
+            .unwrap();
+
+        assert!(result.nose_found);
+        assert!((result.max_lambda() - 4.0).abs() < 1e-2);
+        assert!((result.margins[1] - 400.0).abs() < 1.0);
+        let nose_voltage = result.points[result.nose].voltage_magnitude[1];
+        assert!((nose_voltage - 0.5f64.sqrt()).abs() < 0.05);
+
+        // Past the nose the lower branch is traced with falling λ
+        let curve = result.pv_curve(1);
+        let (lambda, voltage) = curve[curve.len() - 1];
+        assert!(lambda < result.max_lambda());
+        assert!(voltage < 0.5f64.sqrt());
+    }
+
+    #[test]
+    fn test_qv_curve_nose_matches_analytic_limit() {
+        // Purely reactive demand: Q_max = V²/(4X) = 250 MVAr at V = 0.5
+        let network = two_bus(0.0, 0.0);
+        let result = ContinuationPowerFlow::new()
+            .with_step_size(5.0)
+            .trace(&network, &LoadingDirection::reactive_load(&network, 1))
+            .unwrap();
+
+        assert!(result.nose_found);
+        assert!((result.max_lambda() - 250.0).abs() < 0.5);
+        let nose_voltage = result.points[result.nose].voltage_magnitude[1];
+        assert!((nose_voltage - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_base_load_model_is_kept() {
+        // Constant-impedance base load (1 p.u. at 1.0 V) plus constant-power
+        // increments of 100 MW: λ(V) = 10·V·√(1 − V²) − V² peaks at 4.525
+        let mut network = two_bus(0.0, 0.0);
+        network.loads[0] = Load::new(1, 100.0, 0.0).with_model(LoadModel::Zip {
+            active: [1.0, 0.0, 0.0],
+            reactive: [1.0, 0.0, 0.0],
+        });
+        let direction = LoadingDirection {
+            active: vec![0.0, -100.0],
+            reactive: vec![0.0; 2],
+        };
+        let result = ContinuationPowerFlow::new()
+            .trace(&network, &direction)
+            .unwrap();
+
+        // Base case: 1 Ω load behind j0.1 gives V = 1/√1.01
+        let base = result.points[0].voltage_magnitude[1];
+        assert!((base - 1.01f64.sqrt().recip()).abs() < 1e-8);
+        assert!(result.nose_found);
+        assert!((result.max_lambda() - 4.525).abs() < 1e-2);
+    }
+}
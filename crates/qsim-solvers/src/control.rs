@@ -0,0 +1,218 @@
+//! Outer-loop controls for the AC power flow
+//!
+//! After each converged Newton solution the discrete and slow controls are
+//! re-targeted, and the power flow is solved again until none of them move:
+//!
+//! - switched shunts step once towards their voltage band
+//! - tap changers move by `Δt ≈ t·ΔV/V`, rounded to whole steps
+//! - phase shifters move by `Δφ ≈ ΔP / b`, using the branch susceptance
+//!
+//! Tap changers assume the regulated bus lies on the to side (or beyond),
+//! where raising the tap lowers the voltage; a regulated from bus moves the
+//! opposite way.
+
+use qsim_core::{CoreError, Result, StateStore};
+use qsim_elements::{Branch, Network, TransformerControl};
+
+use crate::{ac_branch_flows, dc_susceptance, tap_magnitude};
+
+/// Check that every in-service tap changer regulates a bus in the network
+pub(crate) fn validate_controls(network: &Network) -> Result<()> {
+    for branch in network.branches.iter().filter(|b| b.in_service) {
+        if let Some(TransformerControl::Voltage { bus, .. }) = branch.control {
+            if bus >= network.bus_count() {
+                return Err(CoreError::InvalidBusId(bus));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Re-target every control once; returns whether any setting changed
+pub(crate) fn adjust_controls(network: &mut Network, state: &StateStore) -> bool {
+    let mut moved = false;
+    for shunt in &mut network.switched_shunts {
+        moved |= shunt.regulate(state.voltage_magnitude[shunt.bus]);
+    }
+
+    let flows = ac_branch_flows(network, state);
+    for (branch, flow) in network.branches.iter_mut().zip(&flows) {
+        if !branch.in_service {
+            continue;
+        }
+        moved |= match branch.control {
+            Some(TransformerControl::Voltage { .. }) => adjust_tap(branch, state),
+            Some(TransformerControl::ActiveFlow { .. }) => {
+                adjust_phase_shift(branch, flow.p_from, network.base_mva)
+            }
+            None => false,
+        };
+    }
+    moved
+}
+
+/// Move an on-load tap changer towards the middle of its voltage band
+fn adjust_tap(branch: &mut Branch, state: &StateStore) -> bool {
+    let Some(TransformerControl::Voltage {
+        bus,
+        v_min,
+        v_max,
+        tap_min,
+        tap_max,
+        tap_step,
+    }) = branch.control
+    else {
+        return false;
+    };
+
+    let vm = state.voltage_magnitude[bus];
+    if (v_min..=v_max).contains(&vm) {
+        return false;
+    }
+    let deviation = vm - (v_min + v_max) / 2.0;
+    let sign = if bus == branch.from_bus { -1.0 } else { 1.0 };
+    let tap = tap_magnitude(branch);
+    let mut delta = sign * deviation / vm * tap;
+    if tap_step > 0.0 {
+        let steps = (delta / tap_step).round();
+        let steps = if steps == 0.0 { delta.signum() } else { steps };
+        delta = steps * tap_step;
+    }
+
+    let updated = (tap + delta).clamp(tap_min, tap_max);
+    if (updated - tap).abs() < 1e-12 {
+        return false;
+    }
+    branch.tap_ratio = updated;
+    true
+}
+
+/// Move a phase shifter towards its active flow target
+fn adjust_phase_shift(branch: &mut Branch, p_from: f64, base_mva: f64) -> bool {
+    let Some(TransformerControl::ActiveFlow {
+        target,
+        tolerance,
+        shift_min,
+        shift_max,
+    }) = branch.control
+    else {
+        return false;
+    };
+
+    let error = p_from - target;
+    let b = dc_susceptance(branch);
+    if error.abs() <= tolerance || b == 0.0 {
+        return false;
+    }
+    // P ≈ b·(θf − θt − φ), so dP/dφ ≈ −b
+    let updated = (branch.phase_shift + error / base_mva / b).clamp(shift_min, shift_max);
+    if (updated - branch.phase_shift).abs() < 1e-12 {
+        return false;
+    }
+    branch.phase_shift = updated;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AcPowerFlowSolver, NetworkSolver};
+    use qsim_core::CoreError;
+    use qsim_elements::{Branch, Bus, Load, Network, TransformerControl};
+
+    fn oltc_network(bus: usize, tap_step: f64) -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        for _ in 0..3 {
+            network.add_bus(Bus::pq(0.0, 0.0));
+        }
+        network.add_branch(Branch::line(0, 1, 0.01, 0.05));
+        let oltc = TransformerControl::Voltage {
+            bus,
+            v_min: 0.99,
+            v_max: 1.01,
+            tap_min: 0.9,
+            tap_max: 1.1,
+            tap_step,
+        };
+        network.add_branch(Branch::transformer(1, 2, 0.0, 0.08, 1.0).with_control(oltc));
+        network.add_branch(Branch::line(2, 3, 0.02, 0.06));
+        network.add_load(Load::new(3, 40.0, 15.0));
+        network
+    }
+
+    #[test]
+    fn test_tap_changer_regulates_remote_bus() {
+        let mut network = oltc_network(3, 0.00625);
+        let mut state = network.initial_state();
+        let result = AcPowerFlowSolver::new()
+            .solve_with_controls(&mut network, &mut state)
+            .unwrap();
+
+        assert!(result.converged);
+        assert!((0.99..=1.01).contains(&state.voltage_magnitude[3]));
+        let tap = network.branches[1].tap_ratio;
+        assert!(tap < 1.0);
+        assert!(((tap - 1.0) / 0.00625 - ((tap - 1.0) / 0.00625).round()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_phase_shifter_holds_flow() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.01, 0.1));
+        let shifter = TransformerControl::ActiveFlow {
+            target: 70.0,
+            tolerance: 0.1,
+            shift_min: -0.5,
+            shift_max: 0.5,
+        };
+        network.add_branch(Branch::transformer(0, 1, 0.0, 0.1, 1.0).with_control(shifter));
+        network.add_load(Load::new(1, 100.0, 20.0));
+
+        // Uncontrolled, the parallel paths share the load about equally
+        let mut fixed = AcPowerFlowSolver::new();
+        fixed.max_control_iterations = 0;
+        let mut state = network.initial_state();
+        fixed.solve_network(&network, &mut state).unwrap();
+        let flows = fixed.branch_flows(&network, &state);
+        assert!(flows[1].p_from < 60.0);
+
+        let solver = AcPowerFlowSolver::new();
+        let mut controlled = network.clone();
+        let mut state = network.initial_state();
+        solver
+            .solve_with_controls(&mut controlled, &mut state)
+            .unwrap();
+        let flows = solver.branch_flows(&controlled, &state);
+        assert!((flows[1].p_from - 70.0).abs() <= 0.1);
+        assert!(controlled.branches[1].phase_shift < 0.0);
+    }
+
+    #[test]
+    fn test_regulated_bus_out_of_range() {
+        let network = oltc_network(7, 0.00625);
+        let mut state = network.initial_state();
+        let err = AcPowerFlowSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap_err();
+        assert!(matches!(err, CoreError::InvalidBusId(7)));
+    }
+
+    #[test]
+    fn test_unsettled_controls_report_failure() {
+        // A band narrower than one tap step makes the changer hunt
+        let mut network = oltc_network(3, 0.05);
+        if let Some(TransformerControl::Voltage { v_min, v_max, .. }) =
+            &mut network.branches[1].control
+        {
+            *v_min = 0.999;
+            *v_max = 1.001;
+        }
+        let mut state = network.initial_state();
+        let result = AcPowerFlowSolver::new()
+            .solve_with_controls(&mut network, &mut state)
+            .unwrap();
+        assert!(!result.converged);
+    }
+}
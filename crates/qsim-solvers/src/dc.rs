@@ -9,6 +9,9 @@
 
 use nalgebra::{DMatrix, DVector};
 use qsim_core::{CoreError, Result, Solver, SolverResult, StateStore, Topology};
+use qsim_elements::Network;
+
+use crate::{build_bbus, dc_branch_flows, phase_shift_injections, BranchFlow, NetworkSolver};
 
 /// DC Power Flow Solver
 ///
@@ -46,6 +49,43 @@ impl DcPowerFlowSolver {
         
         b_matrix
     }
+
+    /// Build the B matrix from network branch reactances
+    pub fn build_network_b_matrix(&self, network: &Network) -> DMatrix<f64> {
+        build_bbus(network)
+    }
+
+    /// Solve the reduced system B' × θ' = P' with `slack` as angle reference
+    fn solve_angles(
+        b_matrix: &DMatrix<f64>,
+        p_vector: &DVector<f64>,
+        slack: usize,
+        slack_angle: f64,
+    ) -> Result<DVector<f64>> {
+        let n = p_vector.len();
+        let mut theta = DVector::from_element(n, slack_angle);
+        if n == 1 {
+            return Ok(theta);
+        }
+
+        let others: Vec<usize> = (0..n).filter(|&i| i != slack).collect();
+        let b_reduced = b_matrix.select_rows(&others).select_columns(&others);
+        let p_reduced = DVector::from_iterator(
+            others.len(),
+            others
+                .iter()
+                .map(|&i| p_vector[i] - b_matrix[(i, slack)] * slack_angle),
+        );
+
+        let solution = b_reduced
+            .lu()
+            .solve(&p_reduced)
+            .ok_or_else(|| CoreError::SimulationError("B matrix is singular".into()))?;
+        for (k, &i) in others.iter().enumerate() {
+            theta[i] = solution[k];
+        }
+        Ok(theta)
+    }
 }
 
 impl Default for DcPowerFlowSolver {
@@ -108,6 +148,53 @@ impl Solver for DcPowerFlowSolver {
     }
 }
 
+impl NetworkSolver for DcPowerFlowSolver {
+    fn solve_network(&self, network: &Network, state: &mut StateStore) -> Result<SolverResult> {
+        let n = network.bus_count();
+        let slack = network
+            .slack_bus()
+            .ok_or_else(|| CoreError::SimulationError("No buses in network".into()))?;
+
+        if state.bus_count() != n {
+            return Err(CoreError::SimulationError(
+                "Network and state bus count mismatch".into(),
+            ));
+        }
+
+        let b_matrix = self.build_network_b_matrix(network);
+        let shift_injections = phase_shift_injections(network);
+        // Shunt conductances consume active power at 1.0 p.u.
+        let shunts = network.shunt_admittance();
+        let p_vector = DVector::from_fn(n, |i, _| {
+            (state.active_power[i] - shunts[i].0) / network.base_mva
+        }) - &shift_injections;
+
+        let slack_angle = network.buses[slack].voltage_angle;
+        let theta = Self::solve_angles(&b_matrix, &p_vector, slack, slack_angle)?;
+
+        // Slack bus picks up the imbalance
+        let p_slack = (b_matrix.row(slack) * &theta)[0] + shift_injections[slack];
+        state.active_power[slack] = p_slack * network.base_mva + shunts[slack].0;
+        state.voltage_angle.copy_from_slice(theta.as_slice());
+        state.voltage_magnitude.fill(1.0);
+
+        let mismatch = (&b_matrix * &theta - &p_vector)
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != slack)
+            .fold(0.0_f64, |acc, (_, v)| acc.max(v.abs()));
+        if mismatch > self.tolerance {
+            return Ok(SolverResult::failed(1, mismatch));
+        }
+
+        Ok(SolverResult::converged(1, mismatch))
+    }
+
+    fn branch_flows(&self, network: &Network, state: &StateStore) -> Vec<BranchFlow> {
+        dc_branch_flows(network, state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,487 @@
+//! Weighted least-squares state estimation
+//!
+//! Finds the bus voltages `x` that minimize
+//!
+//! ```text
+//! J(x) = Σ (zᵢ − hᵢ(x))² / σᵢ²
+//! ```
+//!
+//! with Gauss-Newton iterations on the normal equations:
+//!
+//! ```text
+//! (Hᵀ·W·H)·Δx = Hᵀ·W·(z − h(x))
+//! ```
+//!
+//! The state holds voltage magnitudes at every bus and voltage angles at
+//! every bus except the slack, which serves as angle reference. When PMU
+//! angle measurements are present all angles are estimated.
+
+use nalgebra::{DMatrix, DVector};
+use num_complex::Complex64;
+use qsim_core::{CoreError, Result, StateStore};
+use qsim_elements::{BranchEnd, Measurement, MeasurementKind, Network};
+
+use crate::{branch_admittance, build_ybus, injection_derivatives, power_injections, voltages};
+
+/// Weighted least-squares state estimator
+#[derive(Debug, Clone)]
+pub struct WlsEstimator {
+    /// Largest state update for convergence (per-unit / radians)
+    pub tolerance: f64,
+    /// Maximum number of Gauss-Newton iterations
+    pub max_iterations: usize,
+}
+
+impl WlsEstimator {
+    /// Create a new estimator
+    pub fn new() -> Self {
+        Self {
+            tolerance: 1e-6,
+            max_iterations: 20,
+        }
+    }
+
+    /// Create with custom tolerance
+    pub fn with_tolerance(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for WlsEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Result of a state estimation run
+#[derive(Debug, Clone)]
+pub struct EstimationResult {
+    /// Number of Gauss-Newton iterations
+    pub iterations: usize,
+    /// Whether the iteration converged
+    pub converged: bool,
+    /// Weighted sum of squared residuals J(x)
+    pub objective: f64,
+    /// Measurement residuals z − h(x), in measurement units
+    pub residuals: Vec<f64>,
+}
+
+/// Position of each bus voltage in the estimated state vector
+#[derive(Debug, Clone)]
+pub(crate) struct StateIndex {
+    pub angle: Vec<Option<usize>>,
+    pub magnitude: Vec<usize>,
+    pub len: usize,
+}
+
+impl StateIndex {
+    pub fn new(network: &Network, measurements: &[Measurement]) -> Option<Self> {
+        let slack = network.slack_bus()?;
+        let has_pmu = measurements
+            .iter()
+            .any(|m| matches!(m.kind, MeasurementKind::VoltageAngle { .. }));
+
+        let n = network.bus_count();
+        let mut len = 0;
+        let angle = (0..n)
+            .map(|i| {
+                if i == slack && !has_pmu {
+                    None
+                } else {
+                    len += 1;
+                    Some(len - 1)
+                }
+            })
+            .collect();
+        let magnitude = (len..len + n).collect();
+
+        Some(Self {
+            angle,
+            magnitude,
+            len: len + n,
+        })
+    }
+}
+
+/// Check that every measurement refers to an existing bus or branch
+pub(crate) fn validate_measurements(network: &Network, measurements: &[Measurement]) -> Result<()> {
+    for m in measurements {
+        match m.kind {
+            MeasurementKind::VoltageMagnitude { bus }
+            | MeasurementKind::VoltageAngle { bus }
+            | MeasurementKind::ActiveInjection { bus }
+            | MeasurementKind::ReactiveInjection { bus } => {
+                if bus >= network.bus_count() {
+                    return Err(CoreError::InvalidBusId(bus));
+                }
+            }
+            MeasurementKind::ActiveFlow { branch, .. }
+            | MeasurementKind::ReactiveFlow { branch, .. } => {
+                if branch >= network.branch_count() {
+                    return Err(CoreError::InvalidBranchId(branch));
+                }
+            }
+        }
+        if m.std_dev <= 0.0 {
+            return Err(CoreError::SimulationError(format!(
+                "Measurement {:?} has non-positive standard deviation",
+                m.kind
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Branch flow at one end and its derivatives with respect to the voltages
+/// at the measured end (`near`) and the opposite end (`far`)
+struct FlowSensitivity {
+    flow: Complex64,
+    near: usize,
+    far: usize,
+    d_near_angle: Complex64,
+    d_near_magnitude: Complex64,
+    d_far_angle: Complex64,
+    d_far_magnitude: Complex64,
+}
+
+fn flow_sensitivity(
+    network: &Network,
+    branch: usize,
+    end: BranchEnd,
+    v: &DVector<Complex64>,
+) -> FlowSensitivity {
+    let branch = &network.branches[branch];
+    let y = branch_admittance(branch);
+    let (near, far, y_near, y_far) = match end {
+        BranchEnd::From => (branch.from_bus, branch.to_bus, y.yff, y.yft),
+        BranchEnd::To => (branch.to_bus, branch.from_bus, y.ytt, y.ytf),
+    };
+    let zero = Complex64::new(0.0, 0.0);
+    if !branch.in_service {
+        return FlowSensitivity {
+            flow: zero,
+            near,
+            far,
+            d_near_angle: zero,
+            d_near_magnitude: zero,
+            d_far_angle: zero,
+            d_far_magnitude: zero,
+        };
+    }
+
+    let j = Complex64::new(0.0, 1.0);
+    let (vn, vf) = (v[near], v[far]);
+    let current = y_near * vn + y_far * vf;
+    let d_near = |dv: Complex64| dv * current.conj() + vn * (y_near * dv).conj();
+    let d_far = |dv: Complex64| vn * (y_far * dv).conj();
+
+    FlowSensitivity {
+        flow: vn * current.conj(),
+        near,
+        far,
+        d_near_angle: d_near(j * vn),
+        d_near_magnitude: d_near(vn / vn.norm()),
+        d_far_angle: d_far(j * vf),
+        d_far_magnitude: d_far(vf / vf.norm()),
+    }
+}
+
+/// Measurement function h(x) and Jacobian H in measurement units
+pub(crate) fn evaluate_measurements(
+    network: &Network,
+    ybus: &DMatrix<Complex64>,
+    measurements: &[Measurement],
+    index: &StateIndex,
+    vm: &[f64],
+    va: &[f64],
+) -> (DVector<f64>, DMatrix<f64>) {
+    let base = network.base_mva;
+    let m = measurements.len();
+    let v = voltages(vm, va);
+    let injections = power_injections(ybus, vm, va);
+    let (ds_dtheta, ds_dvm) = injection_derivatives(ybus, vm, va);
+
+    let mut h = DVector::zeros(m);
+    let mut jacobian = DMatrix::zeros(m, index.len);
+    let part = |c: Complex64, active: bool| if active { c.re } else { c.im };
+
+    for (r, measurement) in measurements.iter().enumerate() {
+        match measurement.kind {
+            MeasurementKind::VoltageMagnitude { bus } => {
+                h[r] = vm[bus];
+                jacobian[(r, index.magnitude[bus])] = 1.0;
+            }
+            MeasurementKind::VoltageAngle { bus } => {
+                h[r] = va[bus];
+                if let Some(c) = index.angle[bus] {
+                    jacobian[(r, c)] = 1.0;
+                }
+            }
+            MeasurementKind::ActiveInjection { bus }
+            | MeasurementKind::ReactiveInjection { bus } => {
+                let active = matches!(measurement.kind, MeasurementKind::ActiveInjection { .. });
+                h[r] = part(injections[bus], active) * base;
+                for k in 0..network.bus_count() {
+                    if let Some(c) = index.angle[k] {
+                        jacobian[(r, c)] = part(ds_dtheta[(bus, k)], active) * base;
+                    }
+                    jacobian[(r, index.magnitude[k])] = part(ds_dvm[(bus, k)], active) * base;
+                }
+            }
+            MeasurementKind::ActiveFlow { branch, end }
+            | MeasurementKind::ReactiveFlow { branch, end } => {
+                let active = matches!(measurement.kind, MeasurementKind::ActiveFlow { .. });
+                let s = flow_sensitivity(network, branch, end, &v);
+                h[r] = part(s.flow, active) * base;
+                if let Some(c) = index.angle[s.near] {
+                    jacobian[(r, c)] += part(s.d_near_angle, active) * base;
+                }
+                if let Some(c) = index.angle[s.far] {
+                    jacobian[(r, c)] += part(s.d_far_angle, active) * base;
+                }
+                jacobian[(r, index.magnitude[s.near])] += part(s.d_near_magnitude, active) * base;
+                jacobian[(r, index.magnitude[s.far])] += part(s.d_far_magnitude, active) * base;
+            }
+        }
+    }
+
+    (h, jacobian)
+}
+
+impl WlsEstimator {
+    /// Estimate bus voltages from `measurements`
+    ///
+    /// `state` provides the initial guess and receives the estimated voltages
+    /// and the bus injections (MW/MVAr) they imply.
+    pub fn estimate(
+        &self,
+        network: &Network,
+        measurements: &[Measurement],
+        state: &mut StateStore,
+    ) -> Result<EstimationResult> {
+        let index = StateIndex::new(network, measurements)
+            .ok_or_else(|| CoreError::SimulationError("No buses in network".into()))?;
+        if state.bus_count() != network.bus_count() {
+            return Err(CoreError::SimulationError(
+                "Network and state bus count mismatch".into(),
+            ));
+        }
+        validate_measurements(network, measurements)?;
+        if measurements.len() < index.len {
+            return Err(CoreError::NotObservable(format!(
+                "{} measurements for {} state variables",
+                measurements.len(),
+                index.len
+            )));
+        }
+
+        let ybus = build_ybus(network);
+        let z = DVector::from_iterator(measurements.len(), measurements.iter().map(|m| m.value));
+        let weights =
+            DVector::from_iterator(measurements.len(), measurements.iter().map(|m| m.weight()));
+
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < self.max_iterations {
+            let (h, jacobian) = evaluate_measurements(
+                network,
+                &ybus,
+                measurements,
+                &index,
+                &state.voltage_magnitude,
+                &state.voltage_angle,
+            );
+            let residual = &z - h;
+            let weighted_h = DMatrix::from_fn(jacobian.nrows(), jacobian.ncols(), |r, c| {
+                jacobian[(r, c)] * weights[r]
+            });
+            let gain = jacobian.transpose() * &weighted_h;
+            let rhs = weighted_h.transpose() * &residual;
+            let dx = gain
+                .cholesky()
+                .map(|c| c.solve(&rhs))
+                .ok_or_else(|| CoreError::NotObservable("gain matrix is singular".into()))?;
+
+            for (bus, angle) in index.angle.iter().enumerate() {
+                if let Some(c) = angle {
+                    state.voltage_angle[bus] += dx[*c];
+                }
+            }
+            for (bus, &c) in index.magnitude.iter().enumerate() {
+                state.voltage_magnitude[bus] += dx[c];
+            }
+            iterations += 1;
+
+            if dx.amax() < self.tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        let (h, _) = evaluate_measurements(
+            network,
+            &ybus,
+            measurements,
+            &index,
+            &state.voltage_magnitude,
+            &state.voltage_angle,
+        );
+        let residuals: Vec<f64> = z.iter().zip(h.iter()).map(|(z, h)| z - h).collect();
+        let objective = residuals
+            .iter()
+            .zip(weights.iter())
+            .map(|(r, w)| r * r * w)
+            .sum();
+
+        let injections = power_injections(&ybus, &state.voltage_magnitude, &state.voltage_angle);
+        for (i, s) in injections.iter().enumerate() {
+            state.active_power[i] = s.re * network.base_mva;
+            state.reactive_power[i] = s.im * network.base_mva;
+        }
+
+        Ok(EstimationResult {
+            iterations,
+            converged,
+            objective,
+            residuals,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{ac_branch_flows, AcPowerFlowSolver, NetworkSolver};
+    use qsim_elements::{Branch, Bus, Generator, Load};
+
+    pub(crate) fn four_bus() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_generator(Generator::new(0, 0.0, 1.02));
+        network.add_generator(Generator::new(2, 60.0, 1.01));
+        network.add_load(Load::new(1, 80.0, 30.0));
+        network.add_load(Load::new(3, 70.0, 25.0));
+        for (f, t, r, x, b) in [
+            (0, 1, 0.01, 0.08, 0.02),
+            (1, 2, 0.02, 0.10, 0.02),
+            (0, 3, 0.015, 0.09, 0.02),
+            (2, 3, 0.01, 0.07, 0.02),
+            (1, 3, 0.02, 0.12, 0.0),
+        ] {
+            network.add_branch(Branch::line_with_charging(f, t, r, x, b));
+        }
+        network
+    }
+
+    /// Exact measurements taken from a solved power flow
+    pub(crate) fn measurements(network: &Network) -> (StateStore, Vec<Measurement>) {
+        let mut truth = network.initial_state();
+        AcPowerFlowSolver::new()
+            .solve_network(network, &mut truth)
+            .unwrap();
+        let flows = ac_branch_flows(network, &truth);
+
+        let mut measurements = Vec::new();
+        for bus in 0..network.bus_count() {
+            measurements.push(Measurement::voltage_magnitude(
+                bus,
+                truth.voltage_magnitude[bus],
+                0.004,
+            ));
+            measurements.push(Measurement::active_injection(
+                bus,
+                truth.active_power[bus],
+                1.0,
+            ));
+            measurements.push(Measurement::reactive_injection(
+                bus,
+                truth.reactive_power[bus],
+                1.0,
+            ));
+        }
+        for flow in &flows {
+            measurements.push(Measurement::active_flow(
+                flow.branch,
+                BranchEnd::From,
+                flow.p_from,
+                0.8,
+            ));
+            measurements.push(Measurement::reactive_flow(
+                flow.branch,
+                BranchEnd::From,
+                flow.q_from,
+                0.8,
+            ));
+            measurements.push(Measurement::active_flow(
+                flow.branch,
+                BranchEnd::To,
+                flow.p_to,
+                0.8,
+            ));
+        }
+        (truth, measurements)
+    }
+
+    #[test]
+    fn test_exact_measurements_recover_state() {
+        let network = four_bus();
+        let (truth, measurements) = measurements(&network);
+
+        let mut state = StateStore::new(network.bus_count());
+        let result = WlsEstimator::new()
+            .estimate(&network, &measurements, &mut state)
+            .unwrap();
+
+        assert!(result.converged);
+        assert!(result.objective < 1e-8);
+        for i in 0..network.bus_count() {
+            assert!((state.voltage_magnitude[i] - truth.voltage_magnitude[i]).abs() < 1e-6);
+            assert!((state.voltage_angle[i] - truth.voltage_angle[i]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_noisy_measurements_and_pmu() {
+        let network = four_bus();
+        let (truth, mut measurements) = measurements(&network);
+        for (k, m) in measurements.iter_mut().enumerate() {
+            // Deterministic error within one standard deviation
+            m.value += m.std_dev * ((k as f64) * 1.7).sin() * 0.5;
+        }
+        measurements.push(Measurement::voltage_angle(0, 0.0, 1e-4));
+        measurements.push(Measurement::voltage_angle(3, truth.voltage_angle[3], 1e-4));
+
+        let mut state = StateStore::new(network.bus_count());
+        let result = WlsEstimator::new()
+            .estimate(&network, &measurements, &mut state)
+            .unwrap();
+
+        assert!(result.converged);
+        assert_eq!(result.residuals.len(), measurements.len());
+        assert!(result.objective < measurements.len() as f64);
+        for i in 0..network.bus_count() {
+            assert!((state.voltage_magnitude[i] - truth.voltage_magnitude[i]).abs() < 5e-3);
+            assert!((state.voltage_angle[i] - truth.voltage_angle[i]).abs() < 5e-3);
+        }
+    }
+
+    #[test]
+    fn test_unobservable_measurement_set() {
+        let network = four_bus();
+        let measurements = vec![
+            Measurement::voltage_magnitude(0, 1.0, 0.01),
+            Measurement::active_injection(1, -80.0, 1.0),
+        ];
+        let mut state = StateStore::new(network.bus_count());
+        let err = WlsEstimator::new()
+            .estimate(&network, &measurements, &mut state)
+            .unwrap_err();
+        assert!(matches!(err, CoreError::NotObservable(_)));
+    }
+}
@@ -0,0 +1,150 @@
+//! Branch flow calculation
+
+use num_complex::Complex64;
+use qsim_core::StateStore;
+use qsim_elements::Network;
+
+use crate::{branch_admittance, dc_susceptance};
+
+/// Power flowing through a branch, measured at both ends
+///
+/// Values are in MW/MVAr; positive means power leaving the bus into the
+/// branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BranchFlow {
+    /// Branch index
+    pub branch: usize,
+    /// Active power at the from end (MW)
+    pub p_from: f64,
+    /// Reactive power at the from end (MVAr)
+    pub q_from: f64,
+    /// Active power at the to end (MW)
+    pub p_to: f64,
+    /// Reactive power at the to end (MVAr)
+    pub q_to: f64,
+}
+
+impl BranchFlow {
+    /// Apparent power at the from end (MVA)
+    pub fn s_from(&self) -> f64 {
+        self.p_from.hypot(self.q_from)
+    }
+
+    /// Apparent power at the to end (MVA)
+    pub fn s_to(&self) -> f64 {
+        self.p_to.hypot(self.q_to)
+    }
+
+    /// Larger of the two end apparent powers (MVA)
+    pub fn s_max(&self) -> f64 {
+        self.s_from().max(self.s_to())
+    }
+
+    /// Active power losses (MW)
+    pub fn losses(&self) -> f64 {
+        self.p_from + self.p_to
+    }
+}
+
+/// AC branch flows from complex bus voltages
+pub fn ac_branch_flows(network: &Network, state: &StateStore) -> Vec<BranchFlow> {
+    let base = network.base_mva;
+    let voltage = |i: usize| {
+        Complex64::from_polar(state.voltage_magnitude[i], state.voltage_angle[i])
+    };
+
+    network
+        .branches
+        .iter()
+        .enumerate()
+        .map(|(i, branch)| {
+            if !branch.in_service {
+                return BranchFlow { branch: i, ..Default::default() };
+            }
+            let y = branch_admittance(branch);
+            let vf = voltage(branch.from_bus);
+            let vt = voltage(branch.to_bus);
+            let sf = vf * (y.yff * vf + y.yft * vt).conj() * base;
+            let st = vt * (y.ytf * vf + y.ytt * vt).conj() * base;
+            BranchFlow {
+                branch: i,
+                p_from: sf.re,
+                q_from: sf.im,
+                p_to: st.re,
+                q_to: st.im,
+            }
+        })
+        .collect()
+}
+
+/// Results for a three-winding transformer, mapped back from its star equivalent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThreeWindingFlow {
+    /// Transformer index
+    pub transformer: usize,
+    /// Flow per winding; the from end is the winding bus
+    pub windings: [BranchFlow; 3],
+    /// Star bus voltage magnitude (per-unit)
+    pub star_voltage_magnitude: f64,
+    /// Star bus voltage angle (radians)
+    pub star_voltage_angle: f64,
+}
+
+impl ThreeWindingFlow {
+    /// Total active power losses (MW)
+    pub fn losses(&self) -> f64 {
+        self.windings.iter().map(BranchFlow::losses).sum()
+    }
+}
+
+/// Per-winding results for every expanded three-winding transformer
+///
+/// `flows` are the flows of all branches, e.g. from
+/// [`NetworkSolver::branch_flows`](crate::NetworkSolver::branch_flows).
+pub fn three_winding_flows(
+    network: &Network,
+    state: &StateStore,
+    flows: &[BranchFlow],
+) -> Vec<ThreeWindingFlow> {
+    network
+        .three_winding_transformers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, transformer)| {
+            let star_bus = transformer.star_bus?;
+            Some(ThreeWindingFlow {
+                transformer: i,
+                windings: transformer.branches?.map(|b| flows[b]),
+                star_voltage_magnitude: state.voltage_magnitude[star_bus],
+                star_voltage_angle: state.voltage_angle[star_bus],
+            })
+        })
+        .collect()
+}
+
+/// DC branch flows from bus voltage angles (lossless, no reactive power)
+pub fn dc_branch_flows(network: &Network, state: &StateStore) -> Vec<BranchFlow> {
+    let base = network.base_mva;
+
+    network
+        .branches
+        .iter()
+        .enumerate()
+        .map(|(i, branch)| {
+            if !branch.in_service {
+                return BranchFlow { branch: i, ..Default::default() };
+            }
+            let angle = state.voltage_angle[branch.from_bus]
+                - state.voltage_angle[branch.to_bus]
+                - branch.phase_shift;
+            let p = dc_susceptance(branch) * angle * base;
+            BranchFlow {
+                branch: i,
+                p_from: p,
+                q_from: 0.0,
+                p_to: -p,
+                q_to: 0.0,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,465 @@
+//! Holomorphic embedding load flow (HELM)
+//!
+//! Bus voltages are embedded as power series in a complex parameter `s`,
+//! with the no-load solution as the germ (`s = 0`) and the actual power flow
+//! at `s = 1`:
+//!
+//! ```text
+//! PQ:     Σ Y_ik·V_k(s) = s·S_i* / V_i*(s*)
+//! PV:     Σ Y_ik·V_k(s) = (s·P_i − j·Q_i(s)) / V_i*(s*)
+//!         V_i(s)·V_i*(s*) = |V0_i|² + s·(|V_sp|² − |V0_i|²)
+//! slack:  V(s) = 1 + s·(V_sp − 1)
+//! ```
+//!
+//! Each order of the series follows from the lower ones by one linear solve
+//! with a constant matrix. The series are evaluated at `s = 1` with diagonal
+//! Padé approximants (Wynn's ε algorithm). By Stahl's theorem these converge
+//! to the high-voltage solution whenever it exists, so a case whose
+//! approximants fail to reach the tolerance by `max_order` has no solution
+//! (at that order). Unlike Newton-Raphson, the result does not depend on a
+//! starting point.
+//!
+//! Voltage-dependent loads (ZIP or exponential) consume `D_i(|V_i|)` in
+//! place of their nominal demand, embedded as `−s·D_i*(s) / V_i*(s*)` with
+//! `|V|^α = (V(s)·V*(s*))^(α/2)` expanded as a power series. Remote voltage
+//! control is treated as local.
+
+use nalgebra::{DMatrix, DVector};
+use num_complex::Complex64;
+use qsim_core::{CoreError, Result, SolverResult, StateStore};
+use qsim_elements::{LoadModel, Network};
+
+use crate::{
+    ac_branch_flows, build_ybus, power_injections, BranchFlow, BusTypes, NetworkSolver,
+    VoltageDependentLoads,
+};
+
+/// Holomorphic embedding load flow solver
+#[derive(Debug, Clone)]
+pub struct HolomorphicEmbeddingSolver {
+    /// Maximum per-unit power mismatch for convergence
+    pub tolerance: f64,
+    /// Highest order of the voltage series
+    pub max_order: usize,
+}
+
+impl HolomorphicEmbeddingSolver {
+    /// Create a solver with default settings
+    pub fn new() -> Self {
+        Self {
+            tolerance: 1e-8,
+            max_order: 80,
+        }
+    }
+
+    /// Whether the power flow of `network` has a solution
+    ///
+    /// Solves from [`Network::initial_state`]; `false` means the Padé
+    /// approximants did not converge within `max_order`.
+    pub fn has_solution(&self, network: &Network) -> Result<bool> {
+        let mut state = network.initial_state();
+        Ok(self.solve_network(network, &mut state)?.converged)
+    }
+}
+
+impl Default for HolomorphicEmbeddingSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Value at `s = 1` of a power series, by the diagonal Padé approximant
+///
+/// Uses Wynn's ε algorithm on the partial sums; the even columns of the ε
+/// table are Padé approximants.
+pub fn pade_at_one(coefficients: &[Complex64]) -> Complex64 {
+    let sums: Vec<Complex64> = coefficients
+        .iter()
+        .scan(Complex64::new(0.0, 0.0), |sum, &c| {
+            *sum += c;
+            Some(*sum)
+        })
+        .collect();
+    let Some(&last) = sums.last() else {
+        return Complex64::new(0.0, 0.0);
+    };
+
+    let mut best = last;
+    let mut previous = vec![Complex64::new(0.0, 0.0); sums.len() + 1];
+    let mut current = sums;
+    for column in 1..current.len() {
+        let mut next = Vec::with_capacity(current.len() - 1);
+        for j in 0..current.len() - 1 {
+            let difference = current[j + 1] - current[j];
+            if difference.norm() <= 1e-15 * current[j + 1].norm().max(1.0) {
+                return best;
+            }
+            next.push(previous[j + 1] + difference.inv());
+        }
+        previous = current;
+        current = next;
+        if column % 2 == 0 {
+            match current.last() {
+                Some(value) if value.is_finite() => best = *value,
+                _ => return best,
+            }
+        }
+    }
+    best
+}
+
+/// Voltage-dependent share of a load's demand, `demand·|V|^exponent`
+struct DemandTerm {
+    bus: usize,
+    exponent: f64,
+    /// Demand at 1.0 p.u. voltage (per-unit)
+    demand: Complex64,
+    /// Coefficients of |V(s)|^exponent
+    series: Vec<f64>,
+}
+
+/// Demand terms of the in-service voltage-dependent loads
+fn demand_terms(network: &Network) -> Vec<DemandTerm> {
+    let mut terms = Vec::new();
+    for load in network.loads.iter().filter(|l| l.in_service) {
+        let s0 = Complex64::new(load.active_power, load.reactive_power) / network.base_mva;
+        let (active, reactive) = match load.model {
+            LoadModel::ConstantPower => continue,
+            LoadModel::Zip { active, reactive } => (
+                vec![(2.0, active[0]), (1.0, active[1]), (0.0, active[2])],
+                vec![(2.0, reactive[0]), (1.0, reactive[1]), (0.0, reactive[2])],
+            ),
+            LoadModel::Exponential { active, reactive } => {
+                (vec![(active, 1.0)], vec![(reactive, 1.0)])
+            }
+        };
+        let parts = active
+            .into_iter()
+            .map(|(exponent, share)| (exponent, Complex64::new(s0.re * share, 0.0)))
+            .chain(
+                reactive
+                    .into_iter()
+                    .map(|(exponent, share)| (exponent, Complex64::new(0.0, s0.im * share))),
+            );
+        for (exponent, demand) in parts.filter(|(_, demand)| demand.norm() > 0.0) {
+            terms.push(DemandTerm {
+                bus: load.bus,
+                exponent,
+                demand,
+                series: Vec::new(),
+            });
+        }
+    }
+    terms
+}
+
+/// Next coefficient of `M(s)^a` from the coefficients of `M` and the
+/// lower coefficients of the power (J.C.P. Miller's recurrence)
+fn next_power_coefficient(m: &[f64], power: &[f64], a: f64) -> f64 {
+    let n = power.len();
+    if n == 0 {
+        return m[0].powf(a);
+    }
+    (1..=n)
+        .map(|k| (a * k as f64 - (n - k) as f64) * m[k] * power[n - k])
+        .sum::<f64>()
+        / (n as f64 * m[0])
+}
+
+impl NetworkSolver for HolomorphicEmbeddingSolver {
+    fn solve_network(&self, network: &Network, state: &mut StateStore) -> Result<SolverResult> {
+        let types = BusTypes::from_network(network)
+            .ok_or_else(|| CoreError::SimulationError("No buses in network".into()))?
+            .local_control();
+        let n = network.bus_count();
+        if state.bus_count() != n {
+            return Err(CoreError::SimulationError(
+                "Network and state bus count mismatch".into(),
+            ));
+        }
+
+        let base = network.base_mva;
+        let ybus = build_ybus(network);
+        let s_spec: Vec<Complex64> = (0..n)
+            .map(|i| Complex64::new(state.active_power[i], state.reactive_power[i]) / base)
+            .collect();
+        // Constant-power injections, with voltage-dependent demand added back
+        let mut terms = demand_terms(network);
+        let mut s_constant = s_spec.clone();
+        for term in &terms {
+            s_constant[term.bus] += term.demand;
+        }
+        let loads = VoltageDependentLoads::from_network(network);
+        let slack = types.slack;
+        let v_slack =
+            Complex64::from_polar(network.voltage_setpoint(slack), state.voltage_angle[slack]);
+        let mut buses: Vec<usize> = types.pv.iter().chain(&types.pq).copied().collect();
+        buses.sort_unstable();
+        let mut is_pv = vec![false; n];
+        for &i in &types.pv {
+            is_pv[i] = true;
+        }
+
+        // Germ: no-load voltages with the slack at 1∠0
+        let m = buses.len();
+        let y_nn = DMatrix::from_fn(m, m, |a, b| ybus[(buses[a], buses[b])]);
+        let y_ns = DVector::from_fn(m, |a, _| -ybus[(buses[a], slack)]);
+        let germ = y_nn
+            .lu()
+            .solve(&y_ns)
+            .ok_or_else(|| CoreError::SimulationError("Buses not connected to the slack".into()))?;
+
+        let mut v = vec![Vec::new(); n];
+        let mut w = vec![Vec::new(); n];
+        let mut q = vec![vec![0.0]; n];
+        // V(s)·V*(s*) and the voltage-dependent demand D(s) per bus
+        let mut m: Vec<Vec<f64>> = vec![Vec::new(); n];
+        let mut demand = vec![Vec::new(); n];
+        v[slack] = vec![Complex64::new(1.0, 0.0), v_slack - 1.0];
+        for (a, &i) in buses.iter().enumerate() {
+            v[i].push(germ[a]);
+            w[i].push(germ[a].inv());
+        }
+
+        // Real-valued system: (Re V, Im V) per bus, plus Q at PV buses
+        let mut offset = vec![0; n];
+        let mut dim = 0;
+        for &i in &buses {
+            offset[i] = dim;
+            dim += if is_pv[i] { 3 } else { 2 };
+        }
+        let mut matrix = DMatrix::zeros(dim, dim);
+        for &i in &buses {
+            let r = offset[i];
+            for &k in &buses {
+                let (c, y) = (offset[k], ybus[(i, k)]);
+                matrix[(r, c)] += y.re;
+                matrix[(r, c + 1)] -= y.im;
+                matrix[(r + 1, c)] += y.im;
+                matrix[(r + 1, c + 1)] += y.re;
+            }
+            if is_pv[i] {
+                // j·conj(W0)·Q on the left-hand side, and 2·Re(conj(V0)·V)
+                let cw = w[i][0].conj();
+                matrix[(r, r + 2)] = -cw.im;
+                matrix[(r + 1, r + 2)] = cw.re;
+                matrix[(r + 2, r)] = 2.0 * v[i][0].re;
+                matrix[(r + 2, r + 1)] = 2.0 * v[i][0].im;
+            }
+        }
+        let lu = matrix.lu();
+
+        let mut vm = vec![0.0; n];
+        let mut va = vec![0.0; n];
+        let mut mismatch = f64::INFINITY;
+        for order in 1..=self.max_order {
+            // Extend |V|² and the demand series to order − 1
+            let last = order - 1;
+            for &i in &buses {
+                let product = (0..=last).map(|k| (v[i][k] * v[i][last - k].conj()).re);
+                m[i].push(product.sum());
+                demand[i].push(Complex64::new(0.0, 0.0));
+            }
+            for term in terms.iter_mut().filter(|t| t.bus != slack) {
+                let next = next_power_coefficient(&m[term.bus], &term.series, term.exponent / 2.0);
+                term.series.push(next);
+                demand[term.bus][last] += term.demand * next;
+            }
+
+            let mut rhs = DVector::zeros(dim);
+            let v_slack_n = v[slack].get(order).copied().unwrap_or_default();
+            for &i in &buses {
+                let r = offset[i];
+                let mut current = -ybus[(i, slack)] * v_slack_n;
+                current -= (0..order)
+                    .map(|k| demand[i][k].conj() * w[i][last - k].conj())
+                    .sum::<Complex64>();
+                if is_pv[i] {
+                    current += s_constant[i].re * w[i][order - 1].conj();
+                    for k in 1..order {
+                        current -= Complex64::new(0.0, q[i][k]) * w[i][order - k].conj();
+                    }
+                    let target = if order == 1 {
+                        network.voltage_setpoint(i).powi(2) - v[i][0].norm_sqr()
+                    } else {
+                        0.0
+                    };
+                    let known: f64 = (1..order)
+                        .map(|k| (v[i][k] * v[i][order - k].conj()).re)
+                        .sum();
+                    rhs[r + 2] = target - known;
+                } else {
+                    current += s_constant[i].conj() * w[i][order - 1].conj();
+                }
+                rhs[r] = current.re;
+                rhs[r + 1] = current.im;
+            }
+            let x = lu
+                .solve(&rhs)
+                .ok_or_else(|| CoreError::SimulationError("Embedding matrix is singular".into()))?;
+
+            for &i in &buses {
+                let r = offset[i];
+                v[i].push(Complex64::new(x[r], x[r + 1]));
+                q[i].push(if is_pv[i] { x[r + 2] } else { 0.0 });
+                let next = -(1..=order)
+                    .map(|k| v[i][k] * w[i][order - k])
+                    .sum::<Complex64>()
+                    / v[i][0];
+                w[i].push(next);
+            }
+
+            // Evaluate at s = 1 and check the actual power flow equations
+            vm[slack] = v_slack.norm();
+            va[slack] = v_slack.arg();
+            for &i in &buses {
+                let (magnitude, angle) = pade_at_one(&v[i]).to_polar();
+                vm[i] = magnitude;
+                va[i] = angle;
+            }
+            let s_calc = power_injections(&ybus, &vm, &va);
+            let s_demand = loads.injections(&s_spec, &vm);
+            mismatch = buses
+                .iter()
+                .map(|&i| {
+                    if is_pv[i] {
+                        (s_demand[i].re - s_calc[i].re)
+                            .abs()
+                            .max((vm[i] - network.voltage_setpoint(i)).abs())
+                    } else {
+                        (s_demand[i] - s_calc[i]).l1_norm()
+                    }
+                })
+                .fold(0.0, f64::max);
+            if mismatch.is_finite() && mismatch < self.tolerance {
+                state.voltage_magnitude.copy_from_slice(&vm);
+                state.voltage_angle.copy_from_slice(&va);
+                state.active_power[slack] = s_calc[slack].re * base;
+                state.reactive_power[slack] = s_calc[slack].im * base;
+                for &i in &types.pv {
+                    state.reactive_power[i] = s_calc[i].im * base;
+                }
+                return Ok(SolverResult::converged(order, mismatch));
+            }
+        }
+        Ok(SolverResult::failed(self.max_order, mismatch))
+    }
+
+    fn branch_flows(&self, network: &Network, state: &StateStore) -> Vec<BranchFlow> {
+        ac_branch_flows(network, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AcPowerFlowSolver;
+    use qsim_elements::{Branch, Bus, Generator, Load};
+
+    fn two_bus(p: f64) -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.1));
+        network.add_load(Load::new(1, p, 0.0));
+        network
+    }
+
+    #[test]
+    fn test_matches_newton_raphson() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.02));
+        network.add_bus(Bus::pv(1.01, 60.0));
+        network.add_bus(Bus::pq(0.0, 0.0).with_shunt(0.0, 10.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_generator(Generator::new(1, 60.0, 1.01));
+        network.add_branch(Branch::line_with_charging(0, 2, 0.01, 0.08, 0.02));
+        network.add_branch(Branch::line(1, 2, 0.02, 0.1));
+        network.add_branch(Branch::transformer(2, 3, 0.0, 0.06, 0.98));
+        network.add_branch(Branch::line(0, 3, 0.03, 0.12));
+        network.add_load(Load::new(2, 80.0, 30.0));
+        network.add_load(Load::new(3, 70.0, 25.0));
+
+        let mut state = network.initial_state();
+        let result = HolomorphicEmbeddingSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+
+        let mut reference = network.initial_state();
+        AcPowerFlowSolver::new()
+            .solve_network(&network, &mut reference)
+            .unwrap();
+        for i in 0..network.bus_count() {
+            assert!((state.voltage_magnitude[i] - reference.voltage_magnitude[i]).abs() < 1e-7);
+            assert!((state.voltage_angle[i] - reference.voltage_angle[i]).abs() < 1e-7);
+        }
+        assert!((state.reactive_power[1] - reference.reactive_power[1]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_voltage_dependent_loads_match_newton_raphson() {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.02));
+        network.add_bus(Bus::pv(1.01, 60.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_generator(Generator::new(1, 60.0, 1.01));
+        network.add_branch(Branch::line_with_charging(0, 2, 0.01, 0.08, 0.02));
+        network.add_branch(Branch::line(1, 2, 0.02, 0.1));
+        network.add_branch(Branch::line(2, 3, 0.01, 0.06));
+        network.add_branch(Branch::line(0, 3, 0.03, 0.12));
+        network.add_load(Load::new(1, 20.0, 5.0).with_model(LoadModel::Exponential {
+            active: 1.5,
+            reactive: 2.5,
+        }));
+        network.add_load(Load::new(2, 80.0, 30.0).with_model(LoadModel::Zip {
+            active: [0.3, 0.3, 0.4],
+            reactive: [0.5, 0.2, 0.3],
+        }));
+        network.add_load(Load::new(3, 70.0, 25.0).with_model(LoadModel::Exponential {
+            active: 0.8,
+            reactive: 2.0,
+        }));
+
+        let mut state = network.initial_state();
+        let result = HolomorphicEmbeddingSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+
+        let mut reference = network.initial_state();
+        AcPowerFlowSolver::new()
+            .solve_network(&network, &mut reference)
+            .unwrap();
+        for i in 0..network.bus_count() {
+            assert!((state.voltage_magnitude[i] - reference.voltage_magnitude[i]).abs() < 1e-7);
+            assert!((state.voltage_angle[i] - reference.voltage_angle[i]).abs() < 1e-7);
+        }
+        assert!((state.active_power[0] - reference.active_power[0]).abs() < 1e-5);
+        assert!((state.reactive_power[1] - reference.reactive_power[1]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_existence_check() {
+        // Loadability limit of the two-bus system is 500 MW
+        let solver = HolomorphicEmbeddingSolver::new();
+        assert!(solver.has_solution(&two_bus(400.0)).unwrap());
+        assert!(!solver.has_solution(&two_bus(550.0)).unwrap());
+
+        // The high-voltage solution is found: V = sqrt((1 + sqrt(1 − 0.64)) / 2)
+        let network = two_bus(400.0);
+        let mut state = network.initial_state();
+        solver.solve_network(&network, &mut state).unwrap();
+        let expected = ((1.0 + (1.0f64 - 0.64).sqrt()) / 2.0).sqrt();
+        assert!((state.voltage_magnitude[1] - expected).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_pade_sums_geometric_series_beyond_radius() {
+        // 1 + 2s + 4s² + … = 1/(1 − 2s), which is −1 at s = 1
+        let coefficients: Vec<Complex64> =
+            (0..8).map(|k| Complex64::new(2f64.powi(k), 0.0)).collect();
+        assert!((pade_at_one(&coefficients) + 1.0).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,561 @@
+//! Sequential AC/DC power flow with point-to-point HVDC links
+//!
+//! Each converter appears to the AC network as a P/Q injection at its bus.
+//! The AC and DC problems are solved alternately:
+//!
+//! 1. DC operating points from the present AC bus voltages
+//! 2. converter injections added to the scheduled injections
+//! 3. AC power flow
+//!
+//! until the converter injections stop changing. VSC converters in voltage
+//! control turn their bus into a PV bus; one that runs into its reactive or
+//! MVA limit is fixed at the limit for the rest of the solution. At a bus
+//! whose voltage is already held (PV or slack), a converter in voltage
+//! control injects no reactive power and is reported as not regulating.
+//!
+//! DC operating points:
+//!
+//! ```text
+//! VSC:  P = (Vd_to + R·Id)·Id                  to converter holds Vd_to
+//! LCC:  Vd_r = Vd0_r·cos α − Rc_r·Id           rectifier in power control
+//!       Vd_i = Vd0_i·cos γ_min − Rc_i·Id       inverter at minimum γ
+//!       Vd_r = Vd_i + R·Id,  cos φ ≈ Vd / Vd0
+//! ```
+//!
+//! If the rectifier would need a firing angle below its minimum, it stays at
+//! the minimum and the transferred power drops. Converter losses follow the
+//! AC-side current.
+
+use qsim_core::{CoreError, Result, SolverResult, StateStore};
+use qsim_elements::{
+    BusType, HvdcConverters, HvdcLink, LccConverter, Network, ReactiveControl, VscConverter,
+};
+
+use crate::{ac_branch_flows, AcPowerFlowSolver, BranchFlow, NetworkSolver};
+
+/// Operating point of an HVDC link
+///
+/// AC injections are in MW/MVAr, positive into the AC bus.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HvdcResult {
+    /// Link index
+    pub link: usize,
+    /// Active injection at the from bus (MW)
+    pub p_from: f64,
+    /// Reactive injection at the from bus (MVAr)
+    pub q_from: f64,
+    /// Active injection at the to bus (MW)
+    pub p_to: f64,
+    /// Reactive injection at the to bus (MVAr)
+    pub q_to: f64,
+    /// DC current (kA, positive from → to)
+    pub dc_current: f64,
+    /// DC voltage at the from converter (kV)
+    pub dc_voltage_from: f64,
+    /// DC voltage at the to converter (kV)
+    pub dc_voltage_to: f64,
+    /// Rectifier firing angle (radians, LCC only)
+    pub firing_angle: f64,
+    /// Inverter extinction angle (radians, LCC only)
+    pub extinction_angle: f64,
+    /// Whether a power, angle or reactive limit is active
+    pub limited: bool,
+    /// Whether the from converter holds its AC voltage setpoint
+    pub regulating_from: bool,
+    /// Whether the to converter holds its AC voltage setpoint
+    pub regulating_to: bool,
+}
+
+impl HvdcResult {
+    /// Total converter and DC line losses (MW)
+    pub fn losses(&self) -> f64 {
+        -(self.p_from + self.p_to)
+    }
+}
+
+/// Sequential AC/DC power flow solver
+#[derive(Debug, Clone)]
+pub struct AcDcPowerFlowSolver {
+    /// AC power flow used for each AC solution
+    pub ac: AcPowerFlowSolver,
+    /// Convergence tolerance on the converter injections (MW/MVAr)
+    pub tolerance: f64,
+    /// Maximum number of AC/DC iterations
+    pub max_iterations: usize,
+}
+
+impl AcDcPowerFlowSolver {
+    /// Create a solver with default settings
+    pub fn new() -> Self {
+        Self {
+            ac: AcPowerFlowSolver::new(),
+            tolerance: 1e-6,
+            max_iterations: 30,
+        }
+    }
+}
+
+impl Default for AcDcPowerFlowSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A VSC converter in AC voltage control
+struct VoltageRegulator {
+    link: usize,
+    /// 0 = from converter, 1 = to converter
+    end: usize,
+    bus: usize,
+    converter: VscConverter,
+    /// Reactive injection, once fixed at a limit
+    fixed: Option<f64>,
+}
+
+impl AcDcPowerFlowSolver {
+    /// Solve the AC network together with its HVDC links
+    ///
+    /// `state` holds the scheduled injections without the links (as from
+    /// [`Network::initial_state`]); on return it includes the converter
+    /// injections. Returns the AC result and the operating point of every
+    /// link (default results for links out of service).
+    pub fn solve_with_links(
+        &self,
+        network: &Network,
+        state: &mut StateStore,
+    ) -> Result<(SolverResult, Vec<HvdcResult>)> {
+        let base_p = state.active_power.clone();
+        let base_q = state.reactive_power.clone();
+
+        // Voltage-controlling converters at PQ buses make the bus PV
+        let mut ac_network = network.clone();
+        let mut regulators = Vec::new();
+        for (i, link) in network.hvdc_links.iter().enumerate() {
+            let HvdcConverters::Vsc { from, to, .. } = link.converters else {
+                continue;
+            };
+            if !link.in_service {
+                continue;
+            }
+            for (end, converter, bus) in [(0, from, link.from_bus), (1, to, link.to_bus)] {
+                if let ReactiveControl::Voltage(setpoint) = converter.reactive_control {
+                    if ac_network.buses[bus].bus_type == BusType::PQ {
+                        ac_network.buses[bus].bus_type = BusType::PV;
+                        ac_network.buses[bus].voltage_magnitude = setpoint;
+                        regulators.push(VoltageRegulator {
+                            link: i,
+                            end,
+                            bus,
+                            converter,
+                            fixed: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut voltage_q = vec![[None; 2]; network.hvdc_links.len()];
+        let mut links = operating_points(network, state, &voltage_q)?;
+        mark_regulators(&regulators, &mut links);
+        let mut change = f64::INFINITY;
+        for iteration in 1..=self.max_iterations {
+            state.active_power.clone_from(&base_p);
+            state.reactive_power.clone_from(&base_q);
+            for (link, result) in network.hvdc_links.iter().zip(&links) {
+                state.active_power[link.from_bus] += result.p_from;
+                state.active_power[link.to_bus] += result.p_to;
+            }
+            for (i, (link, result)) in network.hvdc_links.iter().zip(&links).enumerate() {
+                for (end, bus, q) in [
+                    (0, link.from_bus, result.q_from),
+                    (1, link.to_bus, result.q_to),
+                ] {
+                    let regulating = regulators
+                        .iter()
+                        .any(|r| r.link == i && r.end == end && r.fixed.is_none());
+                    if !regulating {
+                        state.reactive_power[bus] += q;
+                    }
+                }
+            }
+
+            let result = self.ac.solve_network(&ac_network, state)?;
+            if !result.converged {
+                return Ok((result, links));
+            }
+
+            // Reactive output of voltage-controlling converters, with limits
+            let mut switched = false;
+            for regulator in &mut regulators {
+                if let Some(q) = regulator.fixed {
+                    voltage_q[regulator.link][regulator.end] = Some(q);
+                    continue;
+                }
+                let required = state.reactive_power[regulator.bus] - base_q[regulator.bus];
+                let p = if regulator.end == 0 {
+                    links[regulator.link].p_from
+                } else {
+                    links[regulator.link].p_to
+                };
+                let (q, limited) = vsc_reactive(&regulator.converter, p, Some(required));
+                voltage_q[regulator.link][regulator.end] = Some(q);
+                if limited {
+                    regulator.fixed = Some(q);
+                    ac_network.buses[regulator.bus].bus_type = BusType::PQ;
+                    switched = true;
+                }
+            }
+
+            let updated = operating_points(network, state, &voltage_q)?;
+            change = links
+                .iter()
+                .zip(&updated)
+                .flat_map(|(a, b)| {
+                    [
+                        a.p_from - b.p_from,
+                        a.q_from - b.q_from,
+                        a.p_to - b.p_to,
+                        a.q_to - b.q_to,
+                    ]
+                })
+                .fold(0.0, |max: f64, d| max.max(d.abs()));
+            links = updated;
+            mark_regulators(&regulators, &mut links);
+            if change < self.tolerance && !switched {
+                return Ok((SolverResult::converged(iteration, change), links));
+            }
+        }
+        Ok((SolverResult::failed(self.max_iterations, change), links))
+    }
+}
+
+impl NetworkSolver for AcDcPowerFlowSolver {
+    fn solve_network(&self, network: &Network, state: &mut StateStore) -> Result<SolverResult> {
+        self.solve_with_links(network, state)
+            .map(|(result, _)| result)
+    }
+
+    fn branch_flows(&self, network: &Network, state: &StateStore) -> Vec<BranchFlow> {
+        ac_branch_flows(network, state)
+    }
+}
+
+/// Flag the converters holding their voltage and those fixed at a limit
+fn mark_regulators(regulators: &[VoltageRegulator], links: &mut [HvdcResult]) {
+    for regulator in regulators {
+        let result = &mut links[regulator.link];
+        if regulator.fixed.is_some() {
+            result.limited = true;
+        } else if regulator.end == 0 {
+            result.regulating_from = true;
+        } else {
+            result.regulating_to = true;
+        }
+    }
+}
+
+/// Operating points of all links at the AC voltages in `state`
+///
+/// `voltage_q` gives the reactive output of VSC converters in voltage control.
+fn operating_points(
+    network: &Network,
+    state: &StateStore,
+    voltage_q: &[[Option<f64>; 2]],
+) -> Result<Vec<HvdcResult>> {
+    network
+        .hvdc_links
+        .iter()
+        .enumerate()
+        .map(|(i, link)| {
+            if !link.in_service {
+                return Ok(HvdcResult {
+                    link: i,
+                    ..Default::default()
+                });
+            }
+            let mut result = match link.converters {
+                HvdcConverters::Vsc {
+                    from,
+                    to,
+                    dc_voltage_kv,
+                } => {
+                    vsc_operating_point(network, state, link, from, to, dc_voltage_kv, voltage_q[i])
+                }
+                HvdcConverters::Lcc {
+                    rectifier,
+                    inverter,
+                } => lcc_operating_point(network, state, link, rectifier, inverter),
+            }
+            .map_err(|message| CoreError::SimulationError(format!("HVDC link {i}: {message}")))?;
+            result.link = i;
+            result.limited |= link.scheduled_power() != link.power_setpoint;
+            Ok(result)
+        })
+        .collect()
+}
+
+/// AC-side current of a converter (kA)
+fn ac_current(network: &Network, state: &StateStore, bus: usize, p: f64, q: f64) -> f64 {
+    let kv = network.buses[bus].base_voltage_kv * state.voltage_magnitude[bus];
+    p.hypot(q) / (3f64.sqrt() * kv)
+}
+
+/// Reactive output of a VSC within its limits, and whether a limit applies
+///
+/// Active power has priority within the MVA rating.
+fn vsc_reactive(converter: &VscConverter, p: f64, voltage_q: Option<f64>) -> (f64, bool) {
+    let requested = match converter.reactive_control {
+        ReactiveControl::Reactive(q) => q,
+        ReactiveControl::Voltage(_) => voltage_q.unwrap_or(0.0),
+    };
+    let mut q = requested.clamp(converter.q_min, converter.q_max);
+    if converter.rating > 0.0 {
+        let available = (converter.rating * converter.rating - p * p)
+            .max(0.0)
+            .sqrt();
+        q = q.clamp(-available, available);
+    }
+    (q, (q - requested).abs() > 1e-9)
+}
+
+fn vsc_operating_point(
+    network: &Network,
+    state: &StateStore,
+    link: &HvdcLink,
+    from: VscConverter,
+    to: VscConverter,
+    dc_voltage_kv: f64,
+    voltage_q: [Option<f64>; 2],
+) -> std::result::Result<HvdcResult, String> {
+    let p = link.scheduled_power();
+    let r = link.resistance;
+    // P = (Vd_to + R·Id)·Id
+    let current = if r > 0.0 {
+        let discriminant = dc_voltage_kv * dc_voltage_kv + 4.0 * r * p;
+        if discriminant < 0.0 {
+            return Err(format!("cannot deliver {p} MW at {dc_voltage_kv} kV"));
+        }
+        (discriminant.sqrt() - dc_voltage_kv) / (2.0 * r)
+    } else {
+        p / dc_voltage_kv
+    };
+    let p_dc_to = dc_voltage_kv * current;
+
+    let (q_from, limited_from) = vsc_reactive(&from, p, voltage_q[0]);
+    let (q_to, limited_to) = vsc_reactive(&to, p_dc_to, voltage_q[1]);
+    let loss_from = from
+        .losses
+        .at_current(ac_current(network, state, link.from_bus, p, q_from));
+    let loss_to = to
+        .losses
+        .at_current(ac_current(network, state, link.to_bus, p_dc_to, q_to));
+
+    Ok(HvdcResult {
+        p_from: -(p + loss_from),
+        q_from,
+        p_to: p_dc_to - loss_to,
+        q_to,
+        dc_current: current,
+        dc_voltage_from: dc_voltage_kv + r * current,
+        dc_voltage_to: dc_voltage_kv,
+        limited: limited_from || limited_to,
+        ..Default::default()
+    })
+}
+
+fn lcc_operating_point(
+    network: &Network,
+    state: &StateStore,
+    link: &HvdcLink,
+    rectifier: LccConverter,
+    inverter: LccConverter,
+) -> std::result::Result<HvdcResult, String> {
+    let p = link.scheduled_power();
+    if p < 0.0 {
+        return Err("an LCC link cannot reverse power".into());
+    }
+    let vd0_r = rectifier.no_load_voltage_kv * state.voltage_magnitude[link.from_bus];
+    let vd0_i = inverter.no_load_voltage_kv * state.voltage_magnitude[link.to_bus];
+    let (rc_r, rc_i, r) = (
+        rectifier.commutating_resistance,
+        inverter.commutating_resistance,
+        link.resistance,
+    );
+    let e_i = vd0_i * inverter.min_angle.cos();
+
+    // P = Vd_r·Id with Vd_r = E_i + (R − Rc_i)·Id
+    let slope = r - rc_i;
+    let mut current = if slope.abs() > 1e-12 {
+        let discriminant = e_i * e_i + 4.0 * slope * p;
+        if discriminant < 0.0 {
+            return Err(format!("no DC operating point for {p} MW"));
+        }
+        (discriminant.sqrt() - e_i) / (2.0 * slope)
+    } else {
+        p / e_i
+    };
+
+    let mut cos_alpha = (e_i + slope * current + rc_r * current) / vd0_r;
+    let mut limited = false;
+    if cos_alpha > rectifier.min_angle.cos() {
+        // Rectifier at minimum firing angle: transfer falls short of schedule
+        cos_alpha = rectifier.min_angle.cos();
+        current = ((vd0_r * cos_alpha - e_i) / (rc_r + slope)).max(0.0);
+        limited = true;
+    }
+    let vd_r = vd0_r * cos_alpha - rc_r * current;
+    let vd_i = e_i - rc_i * current;
+
+    // Reactive consumption from the displacement factor cos φ ≈ Vd / Vd0
+    let consumption = |p_dc: f64, vd: f64, vd0: f64| {
+        let cos_phi = (vd / vd0).clamp(1e-6, 1.0);
+        p_dc * (1.0 - cos_phi * cos_phi).sqrt() / cos_phi
+    };
+    let (p_r, p_i) = (vd_r * current, vd_i * current);
+    let (q_r, q_i) = (consumption(p_r, vd_r, vd0_r), consumption(p_i, vd_i, vd0_i));
+    let loss_r = rectifier
+        .losses
+        .at_current(ac_current(network, state, link.from_bus, p_r, q_r));
+    let loss_i = inverter
+        .losses
+        .at_current(ac_current(network, state, link.to_bus, p_i, q_i));
+
+    Ok(HvdcResult {
+        p_from: -(p_r + loss_r),
+        q_from: -q_r,
+        p_to: p_i - loss_i,
+        q_to: -q_i,
+        dc_current: current,
+        dc_voltage_from: vd_r,
+        dc_voltage_to: vd_i,
+        firing_angle: cos_alpha.acos(),
+        extinction_angle: inverter.min_angle,
+        limited,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_elements::{Branch, Bus, ConverterLosses, Load};
+
+    /// Three 400 kV buses in a triangle, with an HVDC link from bus 1 to 2
+    fn network(link: HvdcLink) -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        for bus in &mut network.buses {
+            bus.base_voltage_kv = 400.0;
+        }
+        network.add_branch(Branch::line(0, 1, 0.002, 0.02));
+        network.add_branch(Branch::line(0, 2, 0.004, 0.04));
+        network.add_branch(Branch::line(1, 2, 0.01, 0.1));
+        network.add_load(Load::new(1, 100.0, 30.0));
+        network.add_load(Load::new(2, 400.0, 100.0));
+        network.add_hvdc_link(link);
+        network
+    }
+
+    #[test]
+    fn test_vsc_link_power_balance_and_voltage_control() {
+        let losses = ConverterLosses::new(1.0, 0.5, 0.2);
+        let from = VscConverter::new(-200.0, 200.0).with_losses(losses);
+        let to = VscConverter::new(-200.0, 200.0)
+            .with_losses(losses)
+            .with_reactive_control(ReactiveControl::Voltage(1.0));
+        let network = network(HvdcLink::vsc(1, 2, 5.0, 300.0, 320.0, from, to));
+
+        let mut state = network.initial_state();
+        let (result, links) = AcDcPowerFlowSolver::new()
+            .solve_with_links(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+        let link = links[0];
+
+        // DC side: P = Vd_from·Id and the to end holds its DC voltage
+        assert!((link.dc_voltage_from * link.dc_current - 300.0).abs() < 1e-9);
+        assert!((link.dc_voltage_from - 320.0 - 5.0 * link.dc_current).abs() < 1e-9);
+        assert!(link.p_from < -301.0);
+        assert!(link.p_to < 300.0 - 5.0 * link.dc_current.powi(2) - 1.0);
+
+        // The inverter holds 1.0 p.u. and the AC network balances
+        assert!((state.voltage_magnitude[2] - 1.0).abs() < 1e-9);
+        assert!(!link.limited);
+        assert!(!link.regulating_from && link.regulating_to);
+        let ac_losses: f64 = ac_branch_flows(&network, &state)
+            .iter()
+            .map(BranchFlow::losses)
+            .sum();
+        assert!((state.active_power[0] - 500.0 - ac_losses - link.losses()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_vsc_voltage_control_respects_limits() {
+        let from = VscConverter::new(-50.0, 50.0);
+        let to = VscConverter::new(-20.0, 20.0)
+            .with_reactive_control(ReactiveControl::Voltage(1.05))
+            .with_rating(320.0);
+        let network = network(HvdcLink::vsc(1, 2, 5.0, 300.0, 320.0, from, to));
+
+        let mut state = network.initial_state();
+        let (result, links) = AcDcPowerFlowSolver::new()
+            .solve_with_links(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+        assert!(links[0].limited);
+        assert!(!links[0].regulating_to);
+        assert!((links[0].q_to - 20.0).abs() < 1e-9);
+        assert!(state.voltage_magnitude[2] < 1.05);
+    }
+
+    #[test]
+    fn test_vsc_voltage_control_at_held_bus() {
+        let from =
+            VscConverter::new(-200.0, 200.0).with_reactive_control(ReactiveControl::Voltage(1.02));
+        let to = VscConverter::new(-200.0, 200.0);
+        let network = network(HvdcLink::vsc(0, 2, 5.0, 100.0, 320.0, from, to));
+
+        // The slack bus keeps its own setpoint; the converter does not regulate
+        let mut state = network.initial_state();
+        let (result, links) = AcDcPowerFlowSolver::new()
+            .solve_with_links(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+        assert!(!links[0].regulating_from);
+        assert_eq!(links[0].q_from, 0.0);
+        assert!((state.voltage_magnitude[0] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lcc_link_consumes_reactive_power() {
+        let rectifier = LccConverter::new(600.0, 10.0, 5f64.to_radians());
+        let inverter = LccConverter::new(600.0, 10.0, 18f64.to_radians());
+        let network = network(HvdcLink::lcc(1, 2, 10.0, 200.0, rectifier, inverter));
+
+        let mut state = network.initial_state();
+        let (result, links) = AcDcPowerFlowSolver::new()
+            .solve_with_links(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+        let link = links[0];
+        assert!(!link.limited);
+        assert!((link.dc_voltage_from * link.dc_current - 200.0).abs() < 1e-6);
+        assert!(link.firing_angle > 5f64.to_radians());
+        assert!(link.q_from < 0.0 && link.q_to < 0.0);
+        assert!((link.losses() - 10.0 * link.dc_current.powi(2)).abs() < 1e-6);
+
+        // Beyond the rectifier's voltage capability the transfer is limited
+        let mut heavy = network.hvdc_links[0].clone();
+        heavy.power_setpoint = 2000.0;
+        let flat = network.initial_state();
+        let point = lcc_operating_point(&network, &flat, &heavy, rectifier, inverter).unwrap();
+        assert!(point.limited);
+        assert!((point.firing_angle - 5f64.to_radians()).abs() < 1e-9);
+        let e_i = 600.0 * 18f64.to_radians().cos();
+        let current = (600.0 * 5f64.to_radians().cos() - e_i) / 10.0;
+        assert!((point.dc_current - current).abs() < 1e-9);
+        assert!(-point.p_from < 2000.0);
+    }
+}
@@ -0,0 +1,550 @@
+//! Numerical integrators for dynamic models
+//!
+//! A [`DynamicModel`] describes a semi-explicit DAE system
+//!
+//! ```text
+//! dx/dt = f(t, x, y)
+//!     0 = g(t, x, y)
+//! ```
+//!
+//! with the differential states `x` kept in
+//! [`StateStore::dynamic_states`] and the algebraic variables `y` kept by
+//! the integrator. Every integrator wraps a model and implements
+//! [`TimeStepper`], so a model runs unchanged with any method:
+//!
+//! - [`ExplicitEuler`] — first order, one evaluation per step
+//! - [`RungeKutta4`] — classical fourth-order Runge–Kutta
+//! - [`Trapezoidal`] — implicit trapezoidal rule solved for `x` and `y`
+//!   together with Newton's method, with step-doubling error control
+//!
+//! Explicit methods obtain `y` from [`DynamicModel::solve_algebraic`] at
+//! every stage.
+
+use nalgebra::{DMatrix, DVector};
+use qsim_core::{CoreError, Result, StateStore, TimeStepper};
+
+/// A dynamic system `dx/dt = f(t, x, y)`, `0 = g(t, x, y)`
+pub trait DynamicModel: Send + Sync {
+    /// Number of differential states `x`
+    fn state_count(&self) -> usize;
+
+    /// Number of algebraic variables `y` (0 for an ODE)
+    fn algebraic_count(&self) -> usize {
+        0
+    }
+
+    /// Called at the start of every step, e.g. to apply events due at `time`
+    fn begin_step(&mut self, _time: f64) -> Result<()> {
+        Ok(())
+    }
+
+    /// Solve `g(t, x, y) = 0` for `y`, starting from the values in `y`
+    fn solve_algebraic(&self, _time: f64, _x: &[f64], _y: &mut [f64]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Evaluate `f(t, x, y)` into `dx`
+    fn derivatives(&self, time: f64, x: &[f64], y: &[f64], dx: &mut [f64]) -> Result<()>;
+
+    /// Evaluate `g(t, x, y)` into `g`
+    fn residuals(&self, _time: f64, _x: &[f64], _y: &[f64], _g: &mut [f64]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Store results other than `x` (e.g. bus voltages) after each step
+    fn store(&self, _time: f64, _x: &[f64], _y: &[f64], _state: &mut StateStore) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether [`begin_step`](Self::begin_step) may change the network
+    fn changes_network(&self) -> bool {
+        false
+    }
+}
+
+/// Check the state size, start the step and bring `y` in line with `x`
+fn begin<M: DynamicModel>(
+    model: &mut M,
+    time: f64,
+    state: &StateStore,
+    y: &mut Vec<f64>,
+) -> Result<Vec<f64>> {
+    let count = model.state_count();
+    if state.dynamic_states.len() != count {
+        return Err(CoreError::StateError(format!(
+            "Expected {count} dynamic states, found {}",
+            state.dynamic_states.len()
+        )));
+    }
+    model.begin_step(time)?;
+    y.resize(model.algebraic_count(), 0.0);
+    model.solve_algebraic(time, &state.dynamic_states, y)?;
+    Ok(state.dynamic_states.clone())
+}
+
+/// Store the end-of-step values
+fn finish<M: DynamicModel>(
+    model: &M,
+    time: f64,
+    x: Vec<f64>,
+    y: &[f64],
+    state: &mut StateStore,
+) -> Result<()> {
+    model.store(time, &x, y, state)?;
+    state.dynamic_states = x;
+    Ok(())
+}
+
+/// `f` at `x`, solving for `y` first
+fn evaluate<M: DynamicModel>(model: &M, time: f64, x: &[f64], y: &mut [f64]) -> Result<Vec<f64>> {
+    model.solve_algebraic(time, x, y)?;
+    let mut dx = vec![0.0; x.len()];
+    model.derivatives(time, x, y, &mut dx)?;
+    Ok(dx)
+}
+
+/// `x + h·k`
+fn axpy(x: &[f64], h: f64, k: &[f64]) -> Vec<f64> {
+    x.iter().zip(k).map(|(x, k)| x + h * k).collect()
+}
+
+/// Forward Euler integration
+#[derive(Debug, Clone)]
+pub struct ExplicitEuler<M: DynamicModel> {
+    /// Integrated model
+    pub model: M,
+    time: f64,
+    y: Vec<f64>,
+}
+
+impl<M: DynamicModel> ExplicitEuler<M> {
+    /// Integrate `model` from time zero
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            time: 0.0,
+            y: Vec::new(),
+        }
+    }
+
+    /// Builder: start at `time` instead of zero, e.g. when resuming
+    pub fn with_start_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Current time
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+}
+
+impl<M: DynamicModel> TimeStepper for ExplicitEuler<M> {
+    fn step(&mut self, state: &mut StateStore, dt: f64) -> Result<()> {
+        let x = begin(&mut self.model, self.time, state, &mut self.y)?;
+        let k = evaluate(&self.model, self.time, &x, &mut self.y)?;
+        let x = axpy(&x, dt, &k);
+        self.time += dt;
+        self.model.solve_algebraic(self.time, &x, &mut self.y)?;
+        finish(&self.model, self.time, x, &self.y, state)
+    }
+
+    fn simulation_time(&self) -> Option<f64> {
+        Some(self.time)
+    }
+
+    fn changes_network(&self) -> bool {
+        self.model.changes_network()
+    }
+}
+
+/// Classical fourth-order Runge–Kutta integration
+#[derive(Debug, Clone)]
+pub struct RungeKutta4<M: DynamicModel> {
+    /// Integrated model
+    pub model: M,
+    time: f64,
+    y: Vec<f64>,
+}
+
+impl<M: DynamicModel> RungeKutta4<M> {
+    /// Integrate `model` from time zero
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            time: 0.0,
+            y: Vec::new(),
+        }
+    }
+
+    /// Builder: start at `time` instead of zero, e.g. when resuming
+    pub fn with_start_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Current time
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+}
+
+impl<M: DynamicModel> TimeStepper for RungeKutta4<M> {
+    fn step(&mut self, state: &mut StateStore, dt: f64) -> Result<()> {
+        let x = begin(&mut self.model, self.time, state, &mut self.y)?;
+        let (t, half) = (self.time, 0.5 * dt);
+        let k1 = evaluate(&self.model, t, &x, &mut self.y)?;
+        let k2 = evaluate(&self.model, t + half, &axpy(&x, half, &k1), &mut self.y)?;
+        let k3 = evaluate(&self.model, t + half, &axpy(&x, half, &k2), &mut self.y)?;
+        let k4 = evaluate(&self.model, t + dt, &axpy(&x, dt, &k3), &mut self.y)?;
+        let x: Vec<f64> = (0..x.len())
+            .map(|i| x[i] + dt / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+            .collect();
+        self.time += dt;
+        self.model.solve_algebraic(self.time, &x, &mut self.y)?;
+        finish(&self.model, self.time, x, &self.y, state)
+    }
+
+    fn simulation_time(&self) -> Option<f64> {
+        Some(self.time)
+    }
+
+    fn changes_network(&self) -> bool {
+        self.model.changes_network()
+    }
+}
+
+/// Implicit trapezoidal rule for DAE systems
+///
+/// Each step solves
+///
+/// ```text
+/// x₁ − x₀ − h/2·(f(t₀, x₀, y₀) + f(t₁, x₁, y₁)) = 0
+///                                g(t₁, x₁, y₁) = 0
+/// ```
+///
+/// with Newton's method on a finite-difference Jacobian. With `adaptive`
+/// set, each [`TimeStepper::step`] is covered by internal steps whose
+/// local error, estimated by step doubling, meets the tolerances.
+#[derive(Debug, Clone)]
+pub struct Trapezoidal<M: DynamicModel> {
+    /// Integrated model
+    pub model: M,
+    /// Newton convergence tolerance (max-norm of the update)
+    pub tolerance: f64,
+    /// Maximum Newton iterations per step
+    pub max_iterations: usize,
+    /// Adapt the internal step size to the error tolerances
+    pub adaptive: bool,
+    /// Relative local error tolerance
+    pub relative_tolerance: f64,
+    /// Absolute local error tolerance
+    pub absolute_tolerance: f64,
+    /// Smallest internal step before giving up
+    pub min_step: f64,
+    time: f64,
+    y: Vec<f64>,
+    step_size: Option<f64>,
+    accepted: usize,
+    rejected: usize,
+}
+
+impl<M: DynamicModel> Trapezoidal<M> {
+    /// Integrate `model` from time zero with adaptive steps
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            tolerance: 1e-10,
+            max_iterations: 20,
+            adaptive: true,
+            relative_tolerance: 1e-6,
+            absolute_tolerance: 1e-8,
+            min_step: 1e-9,
+            time: 0.0,
+            y: Vec::new(),
+            step_size: None,
+            accepted: 0,
+            rejected: 0,
+        }
+    }
+
+    /// Builder: set the local error tolerances
+    pub fn with_tolerances(mut self, relative: f64, absolute: f64) -> Self {
+        self.relative_tolerance = relative;
+        self.absolute_tolerance = absolute;
+        self
+    }
+
+    /// Builder: take exactly one step of the requested size per call
+    pub fn with_fixed_step(mut self) -> Self {
+        self.adaptive = false;
+        self
+    }
+
+    /// Builder: start at `time` instead of zero, e.g. when resuming
+    pub fn with_start_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Current time
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Number of accepted internal steps
+    pub fn accepted_steps(&self) -> usize {
+        self.accepted
+    }
+
+    /// Number of internal steps rejected by the error control
+    pub fn rejected_steps(&self) -> usize {
+        self.rejected
+    }
+
+    /// One trapezoidal step from `(x0, y0)`; `None` if Newton fails
+    fn trapezoid(
+        &self,
+        t: f64,
+        h: f64,
+        x0: &[f64],
+        y0: &[f64],
+        f0: &[f64],
+    ) -> Result<Option<(Vec<f64>, Vec<f64>)>> {
+        let (nx, ny) = (x0.len(), y0.len());
+        let residual = |z: &[f64]| -> Result<Vec<f64>> {
+            let (x1, y1) = z.split_at(nx);
+            let mut f1 = vec![0.0; nx];
+            self.model.derivatives(t + h, x1, y1, &mut f1)?;
+            let mut r = vec![0.0; nx + ny];
+            for i in 0..nx {
+                r[i] = x1[i] - x0[i] - 0.5 * h * (f0[i] + f1[i]);
+            }
+            self.model.residuals(t + h, x1, y1, &mut r[nx..])?;
+            Ok(r)
+        };
+
+        // Explicit Euler predictor
+        let mut z: Vec<f64> = axpy(x0, h, f0);
+        z.extend_from_slice(y0);
+        for _ in 0..self.max_iterations {
+            let r = residual(&z)?;
+            let mut jacobian = DMatrix::zeros(z.len(), z.len());
+            for j in 0..z.len() {
+                let eps = 1e-7 * z[j].abs().max(1.0);
+                let mut perturbed = z.clone();
+                perturbed[j] += eps;
+                let rj = residual(&perturbed)?;
+                for i in 0..z.len() {
+                    jacobian[(i, j)] = (rj[i] - r[i]) / eps;
+                }
+            }
+            let Some(dz) = jacobian.lu().solve(&-DVector::from_vec(r)) else {
+                return Ok(None);
+            };
+            let scale = z.iter().fold(1.0_f64, |m, v| m.max(v.abs()));
+            for (z, dz) in z.iter_mut().zip(dz.iter()) {
+                *z += dz;
+            }
+            if dz.amax() < self.tolerance * scale {
+                let y1 = z.split_off(nx);
+                return Ok(Some((z, y1)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<M: DynamicModel> TimeStepper for Trapezoidal<M> {
+    fn step(&mut self, state: &mut StateStore, dt: f64) -> Result<()> {
+        let mut x = begin(&mut self.model, self.time, state, &mut self.y)?;
+        let mut y = std::mem::take(&mut self.y);
+        let end = self.time + dt;
+        let mut t = self.time;
+        let mut h = match (self.adaptive, self.step_size) {
+            (true, Some(h)) => h.min(dt),
+            _ => dt,
+        };
+
+        while end - t > 1e-12 * end.abs().max(1.0) {
+            h = h.min(end - t);
+            let mut f0 = vec![0.0; x.len()];
+            self.model.derivatives(t, &x, &y, &mut f0)?;
+
+            if !self.adaptive {
+                let (x1, y1) = self.trapezoid(t, h, &x, &y, &f0)?.ok_or_else(|| {
+                    CoreError::SimulationError(format!("Newton iteration failed at t = {t}"))
+                })?;
+                (x, y) = (x1, y1);
+                t += h;
+                self.accepted += 1;
+                continue;
+            }
+
+            let full = self.trapezoid(t, h, &x, &y, &f0)?;
+            let half = self.trapezoid(t, 0.5 * h, &x, &y, &f0)?;
+            let halves = match half {
+                Some((xm, ym)) => {
+                    let mut fm = vec![0.0; x.len()];
+                    self.model.derivatives(t + 0.5 * h, &xm, &ym, &mut fm)?;
+                    self.trapezoid(t + 0.5 * h, 0.5 * h, &xm, &ym, &fm)?
+                }
+                None => None,
+            };
+
+            let factor = match (full, halves) {
+                (Some((x_full, _)), Some((x_half, y_half))) => {
+                    // Richardson estimate for a second-order method
+                    let error = x_half
+                        .iter()
+                        .zip(&x_full)
+                        .map(|(a, b)| {
+                            (a - b).abs()
+                                / 3.0
+                                / (self.absolute_tolerance + self.relative_tolerance * a.abs())
+                        })
+                        .fold(0.0, f64::max);
+                    if error <= 1.0 {
+                        (x, y) = (x_half, y_half);
+                        t += h;
+                        self.accepted += 1;
+                    } else {
+                        self.rejected += 1;
+                    }
+                    if error > 0.0 {
+                        (0.9 * error.powf(-1.0 / 3.0)).clamp(0.2, 2.0)
+                    } else {
+                        2.0
+                    }
+                }
+                _ => {
+                    self.rejected += 1;
+                    0.25
+                }
+            };
+            h *= factor;
+            if h < self.min_step {
+                return Err(CoreError::SimulationError(format!(
+                    "Step size fell below {} at t = {t}",
+                    self.min_step
+                )));
+            }
+        }
+
+        if self.adaptive {
+            self.step_size = Some(h);
+        }
+        self.time = end;
+        self.y = y;
+        finish(&self.model, self.time, x, &self.y, state)
+    }
+
+    fn simulation_time(&self) -> Option<f64> {
+        Some(self.time)
+    }
+
+    fn changes_network(&self) -> bool {
+        self.model.changes_network()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// dx/dt = λ·(x − cos t) − sin t, exact solution x = cos t
+    struct Relaxation {
+        lambda: f64,
+    }
+
+    impl DynamicModel for Relaxation {
+        fn state_count(&self) -> usize {
+            1
+        }
+
+        fn derivatives(&self, t: f64, x: &[f64], _y: &[f64], dx: &mut [f64]) -> Result<()> {
+            dx[0] = self.lambda * (x[0] - t.cos()) - t.sin();
+            Ok(())
+        }
+    }
+
+    /// Pendulum-like oscillator with an algebraic coupling:
+    /// dx₀/dt = x₁, dx₁/dt = −y, 0 = y − x₀
+    struct Oscillator;
+
+    impl DynamicModel for Oscillator {
+        fn state_count(&self) -> usize {
+            2
+        }
+
+        fn algebraic_count(&self) -> usize {
+            1
+        }
+
+        fn solve_algebraic(&self, _t: f64, x: &[f64], y: &mut [f64]) -> Result<()> {
+            y[0] = x[0];
+            Ok(())
+        }
+
+        fn derivatives(&self, _t: f64, x: &[f64], y: &[f64], dx: &mut [f64]) -> Result<()> {
+            dx[0] = x[1];
+            dx[1] = -y[0];
+            Ok(())
+        }
+
+        fn residuals(&self, _t: f64, x: &[f64], y: &[f64], g: &mut [f64]) -> Result<()> {
+            g[0] = y[0] - x[0];
+            Ok(())
+        }
+    }
+
+    fn run(stepper: &mut dyn TimeStepper, x0: Vec<f64>, dt: f64, steps: usize) -> Vec<f64> {
+        let mut state = StateStore::new(0);
+        state.dynamic_states = x0;
+        for _ in 0..steps {
+            stepper.step(&mut state, dt).unwrap();
+        }
+        state.dynamic_states
+    }
+
+    #[test]
+    fn test_convergence_orders() {
+        let error = |stepper: &mut dyn TimeStepper, steps: usize| {
+            let x = run(stepper, vec![1.0], 1.0 / steps as f64, steps);
+            (x[0] - 1.0_f64.cos()).abs()
+        };
+        let model = || Relaxation { lambda: -1.0 };
+        let ratio = |e: &dyn Fn(usize) -> f64| e(20) / e(40);
+
+        let euler = ratio(&|n| error(&mut ExplicitEuler::new(model()), n));
+        let rk4 = ratio(&|n| error(&mut RungeKutta4::new(model()), n));
+        let trapezoidal = ratio(&|n| error(&mut Trapezoidal::new(model()).with_fixed_step(), n));
+        assert!((euler - 2.0).abs() < 0.2);
+        assert!((rk4 - 16.0).abs() < 2.0);
+        assert!((trapezoidal - 4.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_dae_oscillator() {
+        let exact = [1.0_f64.cos(), -1.0_f64.sin()];
+        let mut trapezoidal = Trapezoidal::new(Oscillator).with_tolerances(1e-8, 1e-10);
+        let x = run(&mut trapezoidal, vec![1.0, 0.0], 0.25, 4);
+        assert!((trapezoidal.time() - 1.0).abs() < 1e-12);
+        assert!((x[0] - exact[0]).abs() < 1e-6 && (x[1] - exact[1]).abs() < 1e-6);
+        assert!(trapezoidal.accepted_steps() > 4);
+
+        let x = run(&mut RungeKutta4::new(Oscillator), vec![1.0, 0.0], 0.01, 100);
+        assert!((x[0] - exact[0]).abs() < 1e-9 && (x[1] - exact[1]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trapezoidal_is_stable_on_stiff_problems() {
+        let stiff = || Relaxation { lambda: -1000.0 };
+        let euler = run(&mut ExplicitEuler::new(stiff()), vec![1.0], 0.01, 100);
+        assert!(euler[0].abs() > 1e6);
+
+        let mut trapezoidal = Trapezoidal::new(stiff()).with_tolerances(1e-4, 1e-6);
+        let x = run(&mut trapezoidal, vec![1.0], 0.01, 100);
+        assert!((x[0] - 1.0_f64.cos()).abs() < 1e-4);
+    }
+}
@@ -5,9 +5,66 @@
 //! ## Solvers
 //!
 //! - [`DcPowerFlowSolver`] — DC power flow (linear approximation)
-//! - [`AcPowerFlowSolver`] — AC power flow (Newton-Raphson) [TODO]
+//! - [`AcPowerFlowSolver`] — AC power flow (Newton-Raphson)
+//! - [`BackwardForwardSweepSolver`] — Backward/forward sweep for radial feeders
+//! - [`ThreePhasePowerFlowSolver`] — Unbalanced three-phase power flow
+//! - [`HolomorphicEmbeddingSolver`] — Holomorphic embedding with Padé existence check
+//! - [`AcDcPowerFlowSolver`] — Sequential AC/DC power flow with HVDC links
+//!
+//! ## Analysis
+//!
+//! - [`ContingencyAnalysis`] — N-1 / N-k contingency analysis
+//! - [`DcContingencyScreener`] — Fast DC outage screening and ranking
+//! - [`WlsEstimator`] — Weighted least-squares state estimation
+//! - [`BadDataDetector`] — χ² and largest normalized residual tests
+//! - [`numerical_observability`] / [`topological_observability`] — Measurement observability
+//! - [`ContinuationPowerFlow`] — PV/QV curves and loadability margins
+//! - [`ShortCircuitAnalysis`] — IEC 60909 three-phase short-circuit currents
+//! - [`UnbalancedFaultAnalysis`] — SLG, LL and LLG faults with sequence networks
+//! - [`QuasiStaticStepper`] — Profile-driven quasi-static time-series power flow
+//! - [`TransientStability`] — Electromechanical transient stability simulation
+//! - [`ExplicitEuler`] / [`RungeKutta4`] / [`Trapezoidal`] — Integrators for [`DynamicModel`]s
 
+mod ac;
+mod bad_data;
+mod contingency;
+mod continuation;
+mod control;
 mod dc;
-// mod ac; // TODO: Phase 5
+mod estimation;
+mod flows;
+mod helm;
+mod integrators;
+mod hvdc;
+mod machine_controls;
+mod observability;
+mod screening;
+mod short_circuit;
+mod stability;
+mod sweep;
+mod three_phase;
+mod time_series;
+mod traits;
+mod unbalanced_fault;
+mod ybus;
 
+pub use ac::*;
+pub use bad_data::*;
+pub use contingency::*;
+pub use continuation::*;
 pub use dc::*;
+pub use estimation::*;
+pub use flows::*;
+pub use helm::*;
+pub use integrators::*;
+pub use hvdc::*;
+pub use observability::*;
+pub use screening::*;
+pub use short_circuit::*;
+pub use stability::*;
+pub use sweep::*;
+pub use three_phase::*;
+pub use time_series::*;
+pub use traits::*;
+pub use unbalanced_fault::*;
+pub use ybus::*;
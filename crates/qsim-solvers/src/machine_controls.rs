@@ -0,0 +1,778 @@
+//! Exciter, governor and stabilizer equations for transient stability
+//!
+//! Every controller owns a contiguous slice of the machine's dynamic
+//! states. Blocks with a zero time constant are bypassed, as in PSS/E, and
+//! limited lags are non-windup. Inputs and outputs are per-unit on the
+//! machine base.
+
+use qsim_core::{CoreError, Result};
+use qsim_elements::{
+    Ac1a, Dc1a, Exciter, Governor, Hygov, Ieeeg1, Pss2a, St1a, Stabilizer, StabilizerInput, Tgov1,
+};
+
+/// Machine quantities seen by its controllers
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Signals {
+    pub terminal_voltage: f64,
+    pub speed_deviation: f64,
+    /// Electrical power on the machine base
+    pub electrical_power: f64,
+    /// Field current in per-unit of field voltage
+    pub field_current: f64,
+    /// Stabilizer output added to the voltage error
+    pub stabilizer: f64,
+}
+
+/// A controller with its own dynamic states
+pub(crate) trait ControlModel {
+    fn state_count(&self) -> usize;
+
+    /// Steady-state states `x` producing `output`; returns the reference
+    /// setting (voltage or power reference)
+    fn initialize(&self, output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64>;
+
+    /// Output for states `x`, writing the state derivatives to `dx`
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64;
+}
+
+/// Derivative of a first-order lag state; zero when bypassed
+fn lag(x: f64, input: f64, t: f64) -> f64 {
+    if t > 0.0 {
+        (input - x) / t
+    } else {
+        0.0
+    }
+}
+
+/// Output of a first-order lag
+fn lag_output(x: f64, input: f64, t: f64) -> f64 {
+    if t > 0.0 {
+        x
+    } else {
+        input
+    }
+}
+
+/// Derivative of a lag with non-windup limits
+fn limited_lag(x: f64, input: f64, t: f64, min: f64, max: f64) -> f64 {
+    let dx = lag(x, input, t);
+    if (x >= max && dx > 0.0) || (x <= min && dx < 0.0) {
+        0.0
+    } else {
+        dx
+    }
+}
+
+/// Output of a lag with non-windup limits
+fn limited_lag_output(x: f64, input: f64, t: f64, min: f64, max: f64) -> f64 {
+    lag_output(x, input, t).clamp(min, max.max(min))
+}
+
+/// Output of a lead-lag (1 + sTa)/(1 + sTb) whose state lags the input by Tb
+fn lead_lag(x: f64, input: f64, ta: f64, tb: f64) -> f64 {
+    if tb > 0.0 {
+        x + ta / tb * (input - x)
+    } else {
+        input
+    }
+}
+
+/// Output of a washout sT/(1 + sT) whose state lags the input by T
+fn washout(x: f64, input: f64, t: f64) -> f64 {
+    if t > 0.0 {
+        input - x
+    } else {
+        input
+    }
+}
+
+/// Rate feedback K·s/(1 + sT) whose state lags the input by T
+fn rate_feedback(x: f64, input: f64, k: f64, t: f64) -> f64 {
+    if t > 0.0 {
+        k * (input - x) / t
+    } else {
+        0.0
+    }
+}
+
+fn require_positive(value: f64, name: &str, model: &str) -> Result<()> {
+    if value > 0.0 {
+        Ok(())
+    } else {
+        Err(CoreError::SimulationError(format!(
+            "{model} needs a positive {name}"
+        )))
+    }
+}
+
+/// Rectifier regulation characteristic FEX(IN) of AC exciters
+fn rectifier_factor(load: f64) -> f64 {
+    if load <= 0.0 {
+        1.0
+    } else if load <= 0.433 {
+        1.0 - 0.577 * load
+    } else if load < 0.75 {
+        (0.75 - load * load).sqrt()
+    } else if load <= 1.0 {
+        1.732 * (1.0 - load)
+    } else {
+        0.0
+    }
+}
+
+/// Exciter output voltage after rectifier regulation
+fn rectified(ve: f64, kc: f64, field_current: f64) -> f64 {
+    if ve <= 0.0 {
+        return 0.0;
+    }
+    ve * rectifier_factor(kc * field_current / ve)
+}
+
+impl ControlModel for St1a {
+    fn state_count(&self) -> usize {
+        5
+    }
+
+    fn initialize(&self, output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        require_positive(self.ka, "KA", "ST1A")?;
+        let va = if self.vos == 2 {
+            output - signals.stabilizer
+        } else {
+            output
+        };
+        let error = va / self.ka;
+        x.copy_from_slice(&[signals.terminal_voltage, error, error, va, va]);
+        let stabilizer = if self.vos == 2 {
+            0.0
+        } else {
+            signals.stabilizer
+        };
+        Ok(error + signals.terminal_voltage - stabilizer)
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        let vc = lag_output(x[0], signals.terminal_voltage, self.tr);
+        dx[0] = lag(x[0], signals.terminal_voltage, self.tr);
+
+        // Rate feedback from the regulator state; needs TA > 0
+        let feedback = if self.ta > 0.0 {
+            rate_feedback(x[4], x[3], self.kf, self.tf)
+        } else {
+            0.0
+        };
+        let stabilizer = if self.vos == 2 {
+            0.0
+        } else {
+            signals.stabilizer
+        };
+        let error = (reference - vc + stabilizer - feedback).clamp(self.vimin, self.vimax);
+        let y1 = lead_lag(x[1], error, self.tc, self.tb);
+        dx[1] = lag(x[1], error, self.tb);
+        let y2 = lead_lag(x[2], y1, self.tc1, self.tb1);
+        dx[2] = lag(x[2], y1, self.tb1);
+
+        let mut va = limited_lag_output(x[3], self.ka * y2, self.ta, self.vamin, self.vamax);
+        dx[3] = limited_lag(x[3], self.ka * y2, self.ta, self.vamin, self.vamax);
+        dx[4] = lag(x[4], va, self.tf);
+        if self.vos == 2 {
+            va += signals.stabilizer;
+        }
+
+        let vt = signals.terminal_voltage;
+        let max = vt * self.vrmax - self.kc * signals.field_current;
+        va.max(vt * self.vrmin).min(max)
+    }
+}
+
+impl ControlModel for Ac1a {
+    fn state_count(&self) -> usize {
+        5
+    }
+
+    fn initialize(&self, output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        require_positive(self.ka, "KA", "AC1A")?;
+        require_positive(self.te, "TE", "AC1A")?;
+
+        // Exciter voltage delivering the field voltage through the rectifier
+        let ifd = signals.field_current;
+        let (mut low, mut high) = (0.0, output.max(0.0) + 2.0 * self.kc * ifd.abs() + 1.0);
+        for _ in 0..200 {
+            let mid = 0.5 * (low + high);
+            if rectified(mid, self.kc, ifd) < output {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        let ve = 0.5 * (low + high);
+        let vfe = (self.ke + self.saturation.value(ve)) * ve + self.kd * ifd;
+        let error = vfe / self.ka;
+        x.copy_from_slice(&[signals.terminal_voltage, error, vfe, ve, vfe]);
+        Ok(error + signals.terminal_voltage - signals.stabilizer)
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        let vc = lag_output(x[0], signals.terminal_voltage, self.tr);
+        dx[0] = lag(x[0], signals.terminal_voltage, self.tr);
+
+        let ve = x[3].max(0.0);
+        let vfe = (self.ke + self.saturation.value(ve)) * ve + self.kd * signals.field_current;
+        let feedback = rate_feedback(x[4], vfe, self.kf, self.tf);
+        dx[4] = lag(x[4], vfe, self.tf);
+
+        let error = reference - vc + signals.stabilizer - feedback;
+        let y = lead_lag(x[1], error, self.tc, self.tb);
+        dx[1] = lag(x[1], error, self.tb);
+        let va = limited_lag_output(x[2], self.ka * y, self.ta, self.vamin, self.vamax);
+        dx[2] = limited_lag(x[2], self.ka * y, self.ta, self.vamin, self.vamax);
+        let vr = va.clamp(self.vrmin, self.vrmax.max(self.vrmin));
+
+        dx[3] = (vr - vfe) / self.te;
+        if x[3] <= 0.0 && dx[3] < 0.0 {
+            dx[3] = 0.0;
+        }
+        rectified(ve, self.kc, signals.field_current)
+    }
+}
+
+impl ControlModel for Dc1a {
+    fn state_count(&self) -> usize {
+        5
+    }
+
+    fn initialize(&self, output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        require_positive(self.ka, "KA", "DC1A")?;
+        require_positive(self.te, "TE", "DC1A")?;
+        let vr = (self.ke + self.saturation.value(output)) * output;
+        let error = vr / self.ka;
+        x.copy_from_slice(&[signals.terminal_voltage, error, vr, output, output]);
+        Ok(error + signals.terminal_voltage - signals.stabilizer)
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        let vc = lag_output(x[0], signals.terminal_voltage, self.tr);
+        dx[0] = lag(x[0], signals.terminal_voltage, self.tr);
+
+        let efd = x[3];
+        let feedback = rate_feedback(x[4], efd, self.kf, self.tf);
+        dx[4] = lag(x[4], efd, self.tf);
+
+        let error = reference - vc + signals.stabilizer - feedback;
+        let y = lead_lag(x[1], error, self.tc, self.tb);
+        dx[1] = lag(x[1], error, self.tb);
+        let vr = limited_lag_output(x[2], self.ka * y, self.ta, self.vrmin, self.vrmax);
+        dx[2] = limited_lag(x[2], self.ka * y, self.ta, self.vrmin, self.vrmax);
+
+        dx[3] = (vr - (self.ke + self.saturation.value(efd)) * efd) / self.te;
+        efd
+    }
+}
+
+impl ControlModel for Exciter {
+    fn state_count(&self) -> usize {
+        match self {
+            Exciter::St1a(model) => model.state_count(),
+            Exciter::Ac1a(model) => model.state_count(),
+            Exciter::Dc1a(model) => model.state_count(),
+        }
+    }
+
+    fn initialize(&self, output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        match self {
+            Exciter::St1a(model) => model.initialize(output, signals, x),
+            Exciter::Ac1a(model) => model.initialize(output, signals, x),
+            Exciter::Dc1a(model) => model.initialize(output, signals, x),
+        }
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        match self {
+            Exciter::St1a(model) => model.evaluate(x, reference, signals, dx),
+            Exciter::Ac1a(model) => model.evaluate(x, reference, signals, dx),
+            Exciter::Dc1a(model) => model.evaluate(x, reference, signals, dx),
+        }
+    }
+}
+
+impl ControlModel for Tgov1 {
+    fn state_count(&self) -> usize {
+        2
+    }
+
+    fn initialize(&self, output: f64, _signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        require_positive(self.r, "R", "TGOV1")?;
+        x.copy_from_slice(&[output, output]);
+        Ok(self.r * output)
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        let speed = signals.speed_deviation;
+        let demand = (reference - speed) / self.r;
+        let valve = limited_lag_output(x[0], demand, self.t1, self.vmin, self.vmax);
+        dx[0] = limited_lag(x[0], demand, self.t1, self.vmin, self.vmax);
+        let turbine = lead_lag(x[1], valve, self.t2, self.t3);
+        dx[1] = lag(x[1], valve, self.t3);
+        turbine - self.dt * speed
+    }
+}
+
+impl ControlModel for Hygov {
+    fn state_count(&self) -> usize {
+        4
+    }
+
+    fn initialize(&self, output: f64, _signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        require_positive(self.temporary_droop, "r", "HYGOV")?;
+        require_positive(self.tr, "Tr", "HYGOV")?;
+        require_positive(self.tf, "Tf", "HYGOV")?;
+        require_positive(self.tw, "Tw", "HYGOV")?;
+        require_positive(self.at, "At", "HYGOV")?;
+        let gate = output / self.at + self.qnl;
+        x.copy_from_slice(&[0.0, gate, gate, gate]);
+        Ok(self.r * gate)
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        let speed = signals.speed_deviation;
+        let filtered = x[0];
+
+        // PI governor (1 + sTr)/(r·Tr·s) with gate and velocity limits
+        let desired = (x[1] + filtered / self.temporary_droop).clamp(self.gmin, self.gmax);
+        let mut rate = filtered / (self.temporary_droop * self.tr);
+        if self.velm > 0.0 {
+            rate = rate.clamp(-self.velm, self.velm);
+        }
+        if (x[1] >= self.gmax && rate > 0.0) || (x[1] <= self.gmin && rate < 0.0) {
+            rate = 0.0;
+        }
+        dx[1] = rate;
+        dx[0] = lag(filtered, reference - speed - self.r * desired, self.tf);
+
+        let gate = lag_output(x[2], desired, self.tg);
+        dx[2] = lag(x[2], desired, self.tg);
+
+        let flow = x[3];
+        let head = if gate > 1e-6 {
+            (flow / gate).powi(2)
+        } else {
+            0.0
+        };
+        dx[3] = (1.0 - head) / self.tw;
+        self.at * head * (flow - self.qnl) - self.dturb * speed * gate
+    }
+}
+
+impl ControlModel for Ieeeg1 {
+    fn state_count(&self) -> usize {
+        6
+    }
+
+    fn initialize(&self, output: f64, _signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        require_positive(self.t3, "T3", "IEEEG1")?;
+        let gain = self.k1 + self.k3 + self.k5 + self.k7;
+        require_positive(gain, "K1 + K3 + K5 + K7", "IEEEG1")?;
+        let valve = output / gain;
+        x.copy_from_slice(&[0.0, valve, valve, valve, valve, valve]);
+        Ok(valve)
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        let speed = signals.speed_deviation;
+        let droop = self.k * lead_lag(x[0], speed, self.t2, self.t1);
+        dx[0] = lag(x[0], speed, self.t1);
+
+        // Rate-limited valve positioner with non-windup position limits
+        let valve = x[1];
+        let rate = ((reference - droop - valve) / self.t3).clamp(self.uc, self.uo.max(self.uc));
+        dx[1] = if (valve >= self.pmax && rate > 0.0) || (valve <= self.pmin && rate < 0.0) {
+            0.0
+        } else {
+            rate
+        };
+
+        let mut input = valve.clamp(self.pmin, self.pmax.max(self.pmin));
+        let mut power = 0.0;
+        for (i, (t, k)) in [
+            (self.t4, self.k1),
+            (self.t5, self.k3),
+            (self.t6, self.k5),
+            (self.t7, self.k7),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let output = lag_output(x[2 + i], input, t);
+            dx[2 + i] = lag(x[2 + i], input, t);
+            power += k * output;
+            input = output;
+        }
+        power
+    }
+}
+
+impl ControlModel for Governor {
+    fn state_count(&self) -> usize {
+        match self {
+            Governor::Tgov1(model) => model.state_count(),
+            Governor::Hygov(model) => model.state_count(),
+            Governor::Ieeeg1(model) => model.state_count(),
+        }
+    }
+
+    fn initialize(&self, output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        match self {
+            Governor::Tgov1(model) => model.initialize(output, signals, x),
+            Governor::Hygov(model) => model.initialize(output, signals, x),
+            Governor::Ieeeg1(model) => model.initialize(output, signals, x),
+        }
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        match self {
+            Governor::Tgov1(model) => model.evaluate(x, reference, signals, dx),
+            Governor::Hygov(model) => model.evaluate(x, reference, signals, dx),
+            Governor::Ieeeg1(model) => model.evaluate(x, reference, signals, dx),
+        }
+    }
+}
+
+fn stabilizer_input(input: StabilizerInput, signals: &Signals) -> Result<f64> {
+    match input {
+        StabilizerInput::SpeedDeviation => Ok(signals.speed_deviation),
+        StabilizerInput::ElectricalPower => Ok(signals.electrical_power),
+        StabilizerInput::Other(code) => Err(CoreError::SimulationError(format!(
+            "PSS2A input signal {code} is not supported"
+        ))),
+    }
+}
+
+/// Offset of the PSS2A lead-lag states after the ramp-tracking filter
+fn lead_lag_offset(model: &Pss2a) -> usize {
+    6 + model.m * model.n
+}
+
+impl ControlModel for Pss2a {
+    fn state_count(&self) -> usize {
+        lead_lag_offset(self) + 2
+    }
+
+    /// Steady-state output is taken from the inputs; the reference is unused
+    fn initialize(&self, _output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        let u1 = stabilizer_input(self.input1, signals)?;
+        let u2 = stabilizer_input(self.input2, signals)?;
+        // In steady state every lag equals its input and washouts block
+        let passed = |input: f64, t: f64| if t > 0.0 { 0.0 } else { input };
+        let a = passed(u1, self.tw1);
+        let b = passed(a, self.tw2);
+        let d = passed(u2, self.tw3);
+        let e = passed(d, self.tw4);
+        x[..6].copy_from_slice(&[u1, a, b, u2, d, e]);
+        let filter = b + self.ks3 * self.ks2 * e;
+        let offset = lead_lag_offset(self);
+        x[6..offset].fill(filter);
+        let v = self.ks1 * (filter - self.ks2 * e);
+        x[offset..].fill(v);
+        Ok(0.0)
+    }
+
+    fn evaluate(&self, x: &[f64], _reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        // Unsupported inputs are rejected at initialization
+        let u1 = stabilizer_input(self.input1, signals).unwrap_or(0.0);
+        let u2 = stabilizer_input(self.input2, signals).unwrap_or(0.0);
+
+        let a = washout(x[0], u1, self.tw1);
+        dx[0] = lag(x[0], u1, self.tw1);
+        let b = washout(x[1], a, self.tw2);
+        dx[1] = lag(x[1], a, self.tw2);
+        let path1 = lag_output(x[2], b, self.t6);
+        dx[2] = lag(x[2], b, self.t6);
+
+        let d = washout(x[3], u2, self.tw3);
+        dx[3] = lag(x[3], u2, self.tw3);
+        let e = washout(x[4], d, self.tw4);
+        dx[4] = lag(x[4], d, self.tw4);
+        let path2 = self.ks2 * lag_output(x[5], e, self.t7);
+        dx[5] = lag(x[5], e, self.t7);
+
+        // Ramp-tracking filter: N stages of (1 + sT8)/(1 + sT9)^M
+        let mut signal = path1 + self.ks3 * path2;
+        for stage in 0..self.n {
+            for order in 0..self.m {
+                let i = 6 + stage * self.m + order;
+                let output = if order == 0 {
+                    lead_lag(x[i], signal, self.t8, self.t9)
+                } else {
+                    lag_output(x[i], signal, self.t9)
+                };
+                dx[i] = lag(x[i], signal, self.t9);
+                signal = output;
+            }
+        }
+
+        let o = lead_lag_offset(self);
+        let v = self.ks1 * (signal - path2);
+        let y1 = lead_lag(x[o], v, self.t1, self.t2);
+        dx[o] = lag(x[o], v, self.t2);
+        let y2 = lead_lag(x[o + 1], y1, self.t3, self.t4);
+        dx[o + 1] = lag(x[o + 1], y1, self.t4);
+        y2.clamp(self.vstmin, self.vstmax.max(self.vstmin))
+    }
+}
+
+impl ControlModel for Stabilizer {
+    fn state_count(&self) -> usize {
+        match self {
+            Stabilizer::Pss2a(model) => model.state_count(),
+        }
+    }
+
+    fn initialize(&self, output: f64, signals: &Signals, x: &mut [f64]) -> Result<f64> {
+        match self {
+            Stabilizer::Pss2a(model) => model.initialize(output, signals, x),
+        }
+    }
+
+    fn evaluate(&self, x: &[f64], reference: f64, signals: &Signals, dx: &mut [f64]) -> f64 {
+        match self {
+            Stabilizer::Pss2a(model) => model.evaluate(x, reference, signals, dx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_elements::Saturation;
+
+    const DT: f64 = 1e-3;
+
+    /// Integrate `model` with forward Euler for `duration` seconds and
+    /// return its final output
+    fn simulate(
+        model: &impl ControlModel,
+        x: &mut [f64],
+        reference: f64,
+        signals: &Signals,
+        duration: f64,
+    ) -> f64 {
+        let mut dx = vec![0.0; x.len()];
+        for _ in 0..(duration / DT).round() as usize {
+            model.evaluate(x, reference, signals, &mut dx);
+            for (x, dx) in x.iter_mut().zip(&dx) {
+                *x += DT * dx;
+            }
+        }
+        model.evaluate(x, reference, signals, &mut dx)
+    }
+
+    /// Initialize `model` for `output` and check that it is at rest
+    fn initialize(model: &impl ControlModel, output: f64, signals: &Signals) -> (Vec<f64>, f64) {
+        let mut x = vec![0.0; model.state_count()];
+        let reference = model.initialize(output, signals, &mut x).unwrap();
+        let mut dx = vec![0.0; x.len()];
+        let initial = model.evaluate(&x, reference, signals, &mut dx);
+        assert!((initial - output).abs() < 1e-9);
+        assert!(dx.iter().all(|d| d.abs() < 1e-9));
+        (x, reference)
+    }
+
+    fn exciter_signals() -> Signals {
+        Signals {
+            terminal_voltage: 1.0,
+            field_current: 1.0,
+            ..Signals::default()
+        }
+    }
+
+    #[test]
+    fn test_ac1a_regulator_limits() {
+        let model = Ac1a::default();
+        let signals = exciter_signals();
+        let (mut x, reference) = initialize(&model, 2.0, &signals);
+
+        // KA·ΔV = 80 drives VA to VAMAX and VR to VRMAX
+        let output = simulate(&model, &mut x, reference + 0.2, &signals, 0.5);
+        let mut dx = vec![0.0; x.len()];
+        model.evaluate(&x, reference + 0.2, &signals, &mut dx);
+        let ve = x[3];
+        let vfe = (model.ke + model.saturation.value(ve)) * ve + model.kd;
+        assert!(output > 2.0);
+        assert!((dx[3] - (model.vrmax - vfe) / model.te).abs() < 1e-9);
+        // Non-windup: the regulator state stays near VAMAX
+        assert!(x[2] < 1.5 * model.vamax);
+        // Rectifier regulation at IN = KC·IFD/VE < 0.433
+        assert!((output - (ve - 0.577 * model.kc)).abs() < 1e-9);
+
+        // The exciter voltage cannot go negative
+        let output = simulate(&model, &mut x, reference - 1.0, &signals, 5.0);
+        assert!(output >= 0.0);
+        assert!(x[3] > -0.01);
+    }
+
+    #[test]
+    fn test_dc1a_step_response() {
+        let model = Dc1a {
+            ke: 1.0,
+            ..Dc1a::default()
+        };
+        let signals = exciter_signals();
+        // Below the saturation knee, so EFD = VR in steady state
+        assert_eq!(model.saturation.value(1.0), 0.0);
+        let (mut x, reference) = initialize(&model, 0.5, &signals);
+
+        let efd = simulate(&model, &mut x, reference + 0.005, &signals, 60.0);
+        assert!((efd - (0.5 + model.ka * 0.005)).abs() < 1e-3);
+
+        // KA·ΔV = 2.3 is held at VRMAX
+        let efd = simulate(&model, &mut x, reference + 0.05, &signals, 60.0);
+        assert!((efd - model.vrmax).abs() < 1e-3);
+        assert!(x[2] < model.vrmax + 0.05);
+    }
+
+    #[test]
+    fn test_dc1a_saturation_lowers_field_voltage() {
+        let saturated = Dc1a {
+            ke: 1.0,
+            vrmax: 10.0,
+            saturation: Saturation::new(3.1, 0.33, 2.3, 0.1),
+            ..Dc1a::default()
+        };
+        let linear = Dc1a {
+            saturation: Saturation::default(),
+            ..saturated
+        };
+        let signals = exciter_signals();
+        let step = |model: &Dc1a| {
+            let (mut x, reference) = initialize(model, 1.0, &signals);
+            simulate(model, &mut x, reference + 0.05, &signals, 60.0)
+        };
+        // VR = 3.3: EFD solves (KE + Se(EFD))·EFD = 3.3
+        let efd = step(&saturated);
+        assert!((step(&linear) - 3.3).abs() < 1e-3);
+        assert!(efd < 3.3);
+        let se = saturated.saturation.value(efd);
+        assert!(((1.0 + se) * efd - 3.3).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_hygov_permanent_droop() {
+        let model = Hygov::default();
+        let mut signals = Signals::default();
+        let (mut x, reference) = initialize(&model, 0.5, &signals);
+        let gate = x[2];
+
+        // A 1% overspeed closes the gate by Δω/R = 0.2 in steady state
+        signals.speed_deviation = 0.01;
+        let power = simulate(&model, &mut x, reference, &signals, 300.0);
+        let expected_gate = gate - 0.01 / model.r;
+        assert!((x[2] - expected_gate).abs() < 1e-4);
+        assert!((x[3] - expected_gate).abs() < 1e-4);
+        let expected = model.at * (expected_gate - model.qnl) - model.dturb * 0.01 * expected_gate;
+        assert!((power - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_hygov_gate_limits() {
+        let model = Hygov::default();
+        let signals = Signals::default();
+        let (mut x, reference) = initialize(&model, 0.5, &signals);
+        let mut dx = vec![0.0; x.len()];
+
+        // Gate velocity is limited to VELM
+        x[0] = -1.0;
+        model.evaluate(&x, reference, &signals, &mut dx);
+        assert_eq!(dx[1], -model.velm);
+        x[0] = 1.0;
+        model.evaluate(&x, reference, &signals, &mut dx);
+        assert_eq!(dx[1], model.velm);
+
+        // and stops at the gate limits
+        x[1] = model.gmax;
+        model.evaluate(&x, reference, &signals, &mut dx);
+        assert_eq!(dx[1], 0.0);
+        x[0] = -1.0;
+        x[1] = model.gmin;
+        model.evaluate(&x, reference, &signals, &mut dx);
+        assert_eq!(dx[1], 0.0);
+    }
+
+    #[test]
+    fn test_ieeeg1_droop_and_valve_limits() {
+        let model = Ieeeg1::default();
+        let mut signals = Signals::default();
+        let (mut x, reference) = initialize(&model, 0.8, &signals);
+
+        // Droop K: a 0.5% overspeed closes the valve by 0.1
+        signals.speed_deviation = 0.005;
+        let power = simulate(&model, &mut x, reference, &signals, 60.0);
+        assert!((x[1] - 0.7).abs() < 1e-6);
+        assert!((power - 0.7).abs() < 1e-4);
+
+        // A 10% overspeed closes the valve at UC until it reaches PMIN
+        signals.speed_deviation = 0.1;
+        simulate(&model, &mut x, reference, &signals, 0.1);
+        assert!((x[1] - (0.7 + 0.1 * model.uc)).abs() < 1e-6);
+        let power = simulate(&model, &mut x, reference, &signals, 120.0);
+        assert!(x[1] <= model.pmin && x[1] > model.pmin - 0.01);
+        assert!(power.abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_pss2a_lead_lag_gain_and_washout() {
+        // Without the ramp-tracking filter and input lag, a step passes the
+        // washouts and reaches the output with gain KS1·(T1/T2)·(T3/T4)
+        let model = Pss2a {
+            m: 0,
+            n: 0,
+            t6: 0.0,
+            ..Pss2a::default()
+        };
+        let mut signals = Signals {
+            electrical_power: 0.8,
+            ..Signals::default()
+        };
+        let (mut x, _) = initialize(&model, 0.0, &signals);
+        let mut dx = vec![0.0; x.len()];
+
+        signals.speed_deviation = 1e-4;
+        let gain = model.ks1 * (model.t1 / model.t2) * (model.t3 / model.t4);
+        let output = model.evaluate(&x, 0.0, &signals, &mut dx);
+        assert!((output - gain * 1e-4).abs() < 1e-12);
+
+        // Larger steps are clamped to VSTMAX
+        signals.speed_deviation = 1e-3;
+        assert_eq!(model.evaluate(&x, 0.0, &signals, &mut dx), model.vstmax);
+        signals.speed_deviation = -1e-3;
+        assert_eq!(model.evaluate(&x, 0.0, &signals, &mut dx), model.vstmin);
+
+        // The washouts remove a sustained speed deviation
+        signals.speed_deviation = 1e-4;
+        let output = simulate(&model, &mut x, 0.0, &signals, 60.0);
+        assert!(output.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pss2a_ignores_steady_power() {
+        let model = Pss2a::default();
+        let mut signals = Signals {
+            electrical_power: 0.8,
+            ..Signals::default()
+        };
+        let (mut x, _) = initialize(&model, 0.0, &signals);
+
+        // The ramp-tracking filter has zero instantaneous gain, and a power
+        // step is washed out
+        signals.electrical_power = 0.9;
+        let mut dx = vec![0.0; x.len()];
+        assert_eq!(model.evaluate(&x, 0.0, &signals, &mut dx), 0.0);
+        let output = simulate(&model, &mut x, 0.0, &signals, 60.0);
+        assert!(output.abs() < 1e-6);
+
+        let other = Pss2a {
+            input2: StabilizerInput::Other(5),
+            ..model
+        };
+        let mut x = vec![0.0; other.state_count()];
+        assert!(other.initialize(0.0, &signals, &mut x).is_err());
+    }
+}
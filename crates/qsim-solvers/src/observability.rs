@@ -0,0 +1,407 @@
+//! Network observability analysis
+//!
+//! Determines whether a measurement set allows the bus voltage angles to be
+//! estimated, using the decoupled P-θ model (the Q-V part additionally
+//! needs one voltage magnitude measurement per island).
+//!
+//! - [`topological_observability`] builds a measurement spanning forest
+//!   with [`Topology::observable_islands`](qsim_core::Topology::observable_islands).
+//! - [`numerical_observability`] factorizes the DC gain matrix `G = HᵀH`;
+//!   zero pivots mark unobservable directions, and branches whose angle
+//!   difference is non-zero in the resulting null-space solution are
+//!   unobservable. Those branches and the injections at their ends are
+//!   removed until every remaining branch is observable.
+//!
+//! Both return the observable islands and a set of pseudo-measurements that
+//! would join them into a single observable network.
+
+use std::collections::HashSet;
+
+use nalgebra::{DMatrix, DVector};
+use qsim_core::{BranchId, BusId, CoreError, Result};
+use qsim_elements::{BranchEnd, Measurement, MeasurementKind, Network};
+
+use crate::{dc_susceptance, validate_measurements};
+
+/// Result of an observability analysis
+#[derive(Debug, Clone)]
+pub struct ObservabilityReport {
+    /// Whether the whole network forms one observable island
+    pub observable: bool,
+    /// Observable islands (bus indices)
+    pub islands: Vec<Vec<usize>>,
+    /// In-service branches connecting different observable islands
+    pub unobservable_branches: Vec<usize>,
+    /// Pseudo-measurements that restore observability
+    pub pseudo_measurements: Vec<MeasurementKind>,
+    /// Islands without a voltage magnitude measurement (Q-V unobservable)
+    pub missing_voltage_reference: Vec<usize>,
+}
+
+/// Buses with P injection measurements and branches with P flow measurements
+fn active_power_measurements(measurements: &[Measurement]) -> (Vec<usize>, Vec<usize>) {
+    let mut injections = Vec::new();
+    let mut flows = Vec::new();
+    for m in measurements {
+        match m.kind {
+            MeasurementKind::ActiveInjection { bus } => injections.push(bus),
+            MeasurementKind::ActiveFlow { branch, .. } => flows.push(branch),
+            _ => {}
+        }
+    }
+    injections.sort_unstable();
+    injections.dedup();
+    flows.sort_unstable();
+    flows.dedup();
+    (injections, flows)
+}
+
+/// Island index of every bus
+fn island_lookup(network: &Network, islands: &[Vec<usize>]) -> Vec<usize> {
+    let mut lookup = vec![0; network.bus_count()];
+    for (k, island) in islands.iter().enumerate() {
+        for &bus in island {
+            lookup[bus] = k;
+        }
+    }
+    lookup
+}
+
+/// Assemble the report and suggest pseudo-measurements joining the islands
+fn build_report(
+    network: &Network,
+    measurements: &[Measurement],
+    islands: Vec<Vec<usize>>,
+) -> ObservabilityReport {
+    let lookup = island_lookup(network, &islands);
+    let (injections, _) = active_power_measurements(measurements);
+
+    let unobservable_branches: Vec<usize> = network
+        .branches
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.in_service && lookup[b.from_bus] != lookup[b.to_bus])
+        .map(|(i, _)| i)
+        .collect();
+
+    // Islands adjacent to each bus through unobservable branches
+    let mut neighbours: Vec<HashSet<usize>> = vec![HashSet::new(); network.bus_count()];
+    for &i in &unobservable_branches {
+        let b = &network.branches[i];
+        neighbours[b.from_bus].insert(lookup[b.to_bus]);
+        neighbours[b.to_bus].insert(lookup[b.from_bus]);
+    }
+
+    // Join islands along a spanning tree of the island graph. An injection
+    // pseudo-measurement works at a boundary bus whose outside neighbours all
+    // lie in one island; otherwise measure the boundary branch flow.
+    let mut joined: Vec<usize> = (0..islands.len()).collect();
+    fn root(joined: &mut [usize], mut k: usize) -> usize {
+        while joined[k] != k {
+            joined[k] = joined[joined[k]];
+            k = joined[k];
+        }
+        k
+    }
+
+    let mut pseudo_measurements = Vec::new();
+    let mut used_buses = HashSet::new();
+    for &i in &unobservable_branches {
+        let b = &network.branches[i];
+        let (a, c) = (
+            root(&mut joined, lookup[b.from_bus]),
+            root(&mut joined, lookup[b.to_bus]),
+        );
+        if a == c {
+            continue;
+        }
+        joined[a] = c;
+
+        let injection_bus = [b.from_bus, b.to_bus].into_iter().find(|&bus| {
+            neighbours[bus].len() == 1
+                && injections.binary_search(&bus).is_err()
+                && !used_buses.contains(&bus)
+        });
+        match injection_bus {
+            Some(bus) => {
+                used_buses.insert(bus);
+                pseudo_measurements.push(MeasurementKind::ActiveInjection { bus });
+            }
+            None => pseudo_measurements.push(MeasurementKind::ActiveFlow {
+                branch: i,
+                end: BranchEnd::From,
+            }),
+        }
+    }
+
+    let observable = islands.len() == 1;
+
+    let mut has_voltage = vec![false; islands.len()];
+    for m in measurements {
+        if let MeasurementKind::VoltageMagnitude { bus } = m.kind {
+            has_voltage[lookup[bus]] = true;
+        }
+    }
+    let missing_voltage_reference = (0..islands.len()).filter(|&k| !has_voltage[k]).collect();
+
+    ObservabilityReport {
+        observable,
+        islands,
+        unobservable_branches,
+        pseudo_measurements,
+        missing_voltage_reference,
+    }
+}
+
+/// Topological observability analysis
+pub fn topological_observability(
+    network: &Network,
+    measurements: &[Measurement],
+) -> Result<ObservabilityReport> {
+    validate_measurements(network, measurements)?;
+    let (injections, flows) = active_power_measurements(measurements);
+
+    let flow_ids: Vec<BranchId> = flows.into_iter().map(BranchId).collect();
+    let injection_ids: Vec<BusId> = injections.into_iter().map(BusId).collect();
+    let islands = network
+        .topology()
+        .observable_islands(&flow_ids, &injection_ids)
+        .into_iter()
+        .map(|island| island.into_iter().map(|b| b.0).collect())
+        .collect();
+
+    Ok(build_report(network, measurements, islands))
+}
+
+/// Symmetric elimination of `G` that replaces zero pivots by 1
+///
+/// Returns the factored matrix (upper triangle holds Lᵀ scaled by the
+/// pivots) and the indices of the zero pivots.
+fn factor_with_zero_pivots(mut gain: DMatrix<f64>) -> (DMatrix<f64>, Vec<usize>) {
+    let n = gain.nrows();
+    let scale = gain.diagonal().amax().max(1.0);
+    let tolerance = 1e-10 * scale;
+    let mut zero_pivots = Vec::new();
+
+    for k in 0..n {
+        if gain[(k, k)].abs() < tolerance {
+            zero_pivots.push(k);
+            for j in k..n {
+                gain[(k, j)] = 0.0;
+                gain[(j, k)] = 0.0;
+            }
+            gain[(k, k)] = 1.0;
+            continue;
+        }
+        for i in k + 1..n {
+            let factor = gain[(i, k)] / gain[(k, k)];
+            if factor == 0.0 {
+                continue;
+            }
+            for j in k..n {
+                gain[(i, j)] -= factor * gain[(k, j)];
+            }
+        }
+    }
+
+    (gain, zero_pivots)
+}
+
+/// Numerical observability analysis on the DC measurement model
+pub fn numerical_observability(
+    network: &Network,
+    measurements: &[Measurement],
+) -> Result<ObservabilityReport> {
+    validate_measurements(network, measurements)?;
+    let n = network.bus_count();
+    if n == 0 {
+        return Err(CoreError::SimulationError("No buses in network".into()));
+    }
+
+    let (mut injections, flows) = active_power_measurements(measurements);
+    let angles: Vec<usize> = measurements
+        .iter()
+        .filter_map(|m| match m.kind {
+            MeasurementKind::VoltageAngle { bus } => Some(bus),
+            _ => None,
+        })
+        .collect();
+    let mut removed = vec![false; network.branch_count()];
+    for (i, b) in network.branches.iter().enumerate() {
+        removed[i] = !b.in_service;
+    }
+
+    loop {
+        // DC measurement Jacobian with unit branch susceptances where the
+        // actual value is zero, so topology alone decides observability
+        let susceptance = |i: usize| {
+            let b = dc_susceptance(&network.branches[i]);
+            if b != 0.0 {
+                b
+            } else {
+                1.0
+            }
+        };
+        let mut rows: Vec<DVector<f64>> = Vec::new();
+        for &i in flows.iter().filter(|&&i| !removed[i]) {
+            let b = &network.branches[i];
+            let mut row = DVector::zeros(n);
+            row[b.from_bus] = susceptance(i);
+            row[b.to_bus] = -susceptance(i);
+            rows.push(row);
+        }
+        for &bus in &injections {
+            let mut row = DVector::zeros(n);
+            for (i, b) in network.branches.iter().enumerate() {
+                if removed[i] || (b.from_bus != bus && b.to_bus != bus) {
+                    continue;
+                }
+                let other = if b.from_bus == bus {
+                    b.to_bus
+                } else {
+                    b.from_bus
+                };
+                row[bus] += susceptance(i);
+                row[other] -= susceptance(i);
+            }
+            rows.push(row);
+        }
+        for &bus in &angles {
+            let mut row = DVector::zeros(n);
+            row[bus] = 1.0;
+            rows.push(row);
+        }
+
+        let mut gain = DMatrix::zeros(n, n);
+        for row in &rows {
+            gain += row * row.transpose();
+        }
+        let (factored, zero_pivots) = factor_with_zero_pivots(gain);
+
+        // Null-space test vector: distinct values at the zero pivots
+        let mut rhs = DVector::zeros(n);
+        for (k, &p) in zero_pivots.iter().enumerate() {
+            rhs[p] = k as f64;
+        }
+        let theta = factored
+            .upper_triangle()
+            .solve_upper_triangular(&rhs)
+            .ok_or_else(|| CoreError::SimulationError("Gain factorization failed".into()))?;
+
+        let unobservable: Vec<usize> = network
+            .branches
+            .iter()
+            .enumerate()
+            .filter(|&(i, b)| !removed[i] && (theta[b.from_bus] - theta[b.to_bus]).abs() > 1e-6)
+            .map(|(i, _)| i)
+            .collect();
+        if unobservable.is_empty() {
+            break;
+        }
+
+        // Injections at the ends of unobservable branches are irrelevant
+        for &i in &unobservable {
+            removed[i] = true;
+            let b = &network.branches[i];
+            injections.retain(|&bus| bus != b.from_bus && bus != b.to_bus);
+        }
+    }
+
+    // Observable islands: components of the remaining branches
+    let mut reduced = network.clone();
+    for (branch, &r) in reduced.branches.iter_mut().zip(&removed) {
+        branch.in_service = !r;
+    }
+    let islands = reduced
+        .topology()
+        .islands()
+        .into_iter()
+        .map(|island| island.into_iter().map(|b| b.0).collect())
+        .collect();
+
+    Ok(build_report(network, measurements, islands))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qsim_elements::{Branch, BranchEnd, Bus};
+
+    /// Six-bus chain with a loop: 0-1-2-3-4-5 and 1-4
+    fn six_bus() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        for _ in 1..6 {
+            network.add_bus(Bus::pq(0.0, 0.0));
+        }
+        for (f, t) in [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (1, 4)] {
+            network.add_branch(Branch::line(f, t, 0.01, 0.1));
+        }
+        network
+    }
+
+    fn partial_measurements() -> Vec<Measurement> {
+        vec![
+            Measurement::voltage_magnitude(0, 1.0, 0.01),
+            Measurement::active_flow(0, BranchEnd::From, 10.0, 1.0),
+            Measurement::active_flow(1, BranchEnd::From, 10.0, 1.0),
+            Measurement::active_flow(4, BranchEnd::From, 10.0, 1.0),
+        ]
+    }
+
+    #[test]
+    fn test_observable_islands_agree() {
+        let network = six_bus();
+        let measurements = partial_measurements();
+        let topological = topological_observability(&network, &measurements).unwrap();
+        let numerical = numerical_observability(&network, &measurements).unwrap();
+
+        let expected = vec![vec![0, 1, 2], vec![3], vec![4, 5]];
+        assert!(!topological.observable);
+        assert_eq!(topological.islands, expected);
+        assert_eq!(numerical.islands, expected);
+        assert_eq!(numerical.missing_voltage_reference, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_pseudo_measurements_restore_observability() {
+        let network = six_bus();
+        let mut measurements = partial_measurements();
+        let report = numerical_observability(&network, &measurements).unwrap();
+        assert_eq!(report.pseudo_measurements.len(), 2);
+
+        measurements.extend(
+            report
+                .pseudo_measurements
+                .iter()
+                .map(|&kind| Measurement::new(kind, 0.0, 10.0)),
+        );
+        assert!(
+            numerical_observability(&network, &measurements)
+                .unwrap()
+                .observable
+        );
+        assert!(
+            topological_observability(&network, &measurements)
+                .unwrap()
+                .observable
+        );
+    }
+
+    #[test]
+    fn test_injections_make_network_observable() {
+        let network = six_bus();
+        let mut measurements = vec![Measurement::voltage_magnitude(0, 1.0, 0.01)];
+        for bus in 1..6 {
+            measurements.push(Measurement::active_injection(bus, 0.0, 1.0));
+        }
+        let report = numerical_observability(&network, &measurements).unwrap();
+        assert!(report.observable);
+        assert!(report.pseudo_measurements.is_empty());
+        assert!(
+            topological_observability(&network, &measurements)
+                .unwrap()
+                .observable
+        );
+    }
+}
@@ -0,0 +1,478 @@
+//! Fast DC contingency screening
+//!
+//! Factorizes the reduced B matrix of the base case once and evaluates
+//! outages with the compensation (Sherman-Morrison-Woodbury) method.
+//! Removing branches `A` with susceptances `D` gives
+//!
+//! ```text
+//! B' = B − A·D·Aᵀ
+//! B'⁻¹ = X + X·A·(D⁻¹ − Aᵀ·X·A)⁻¹·Aᵀ·X      with X = B⁻¹
+//! ```
+//!
+//! so each contingency costs one solve with the base factorization per
+//! outaged branch plus a k×k dense solve, instead of a full refactorization.
+//! Contingencies are ranked by a performance index so the most severe ones
+//! can be verified with a full AC power flow.
+
+use nalgebra::{DMatrix, DVector, Dyn, LU};
+use qsim_core::{CoreError, Result, StateStore};
+use qsim_elements::Network;
+use rayon::prelude::*;
+
+use crate::{
+    build_bbus, dc_branch_flows, dc_susceptance, phase_shift_injections, BranchFlow, Contingency,
+    ContingencyAnalysis, ContingencyOutcome, ContingencyReport, ContingencyResult,
+    DcPowerFlowSolver, NetworkSolver, Outage,
+};
+
+/// Screening result for one contingency
+#[derive(Debug, Clone)]
+pub struct ScreeningResult {
+    /// The contingency that was screened
+    pub contingency: Contingency,
+    /// Whether the outage islands part of the network
+    pub islanded: bool,
+    /// Performance index Σ (1/2n)·(S/S_max)^2n over rated branches
+    pub performance_index: f64,
+    /// Highest branch loading (percent of rating)
+    pub max_loading: f64,
+    /// Number of branches loaded above 100 %
+    pub overloads: usize,
+    /// Why the contingency could not be screened (it then ranks first)
+    pub error: Option<String>,
+}
+
+/// DC contingency screener reusing the base-case factorization
+pub struct DcContingencyScreener<'a> {
+    network: &'a Network,
+    slack: usize,
+    /// Position of each bus in the reduced system (`None` for the slack)
+    reduced_index: Vec<Option<usize>>,
+    lu: LU<f64, Dyn, Dyn>,
+    /// Reduced right-hand side of the base case
+    rhs: DVector<f64>,
+    /// Exponent `n` of the performance index
+    pub pi_order: i32,
+}
+
+impl<'a> DcContingencyScreener<'a> {
+    /// Factorize the base case of `network`
+    pub fn new(network: &'a Network) -> Result<Self> {
+        let slack = network
+            .slack_bus()
+            .ok_or_else(|| CoreError::SimulationError("No buses in network".into()))?;
+        let n = network.bus_count();
+
+        let mut reduced_index = vec![None; n];
+        let others: Vec<usize> = (0..n).filter(|&i| i != slack).collect();
+        for (k, &i) in others.iter().enumerate() {
+            reduced_index[i] = Some(k);
+        }
+
+        let bbus = build_bbus(network);
+        let b_reduced = bbus.select_rows(&others).select_columns(&others);
+        let lu = b_reduced.lu();
+        if !lu.is_invertible() {
+            return Err(CoreError::SimulationError("B matrix is singular".into()));
+        }
+
+        let state = network.initial_state();
+        let slack_angle = network.buses[slack].voltage_angle;
+        let shift = phase_shift_injections(network);
+        let shunts = network.shunt_admittance();
+        let rhs = DVector::from_iterator(
+            others.len(),
+            others.iter().map(|&i| {
+                (state.active_power[i] - shunts[i].0) / network.base_mva
+                    - shift[i]
+                    - bbus[(i, slack)] * slack_angle
+            }),
+        );
+
+        Ok(Self {
+            network,
+            slack,
+            reduced_index,
+            lu,
+            rhs,
+            pi_order: 1,
+        })
+    }
+
+    /// Reduced incidence vector of a branch
+    fn incidence(&self, from: usize, to: usize) -> DVector<f64> {
+        let mut a = DVector::zeros(self.rhs.len());
+        if let Some(k) = self.reduced_index[from] {
+            a[k] += 1.0;
+        }
+        if let Some(k) = self.reduced_index[to] {
+            a[k] -= 1.0;
+        }
+        a
+    }
+
+    fn solve(&self, rhs: &DVector<f64>) -> DVector<f64> {
+        // The factorization was checked to be invertible in `new`
+        self.lu.solve(rhs).unwrap_or_else(|| DVector::zeros(rhs.len()))
+    }
+
+    /// Bus voltage angles after the outages, or `None` if they island the network
+    pub fn outage_angles(&self, contingency: &Contingency) -> Result<Option<Vec<f64>>> {
+        let case = contingency.apply(self.network)?;
+        if case.topology().islands().len() > 1 {
+            return Ok(None);
+        }
+
+        let base = self.network.base_mva;
+        let mut rhs = self.rhs.clone();
+        let mut removed = Vec::new();
+        for (k, outage) in contingency.outages.iter().enumerate() {
+            // A repeated outage removes the element only once
+            if contingency.outages[..k].contains(outage) {
+                continue;
+            }
+            match *outage {
+                Outage::Branch(i) => {
+                    let branch = &self.network.branches[i];
+                    let b = dc_susceptance(branch);
+                    if !branch.in_service || b == 0.0 {
+                        continue;
+                    }
+                    // Outaged phase shifters no longer inject
+                    if branch.phase_shift != 0.0 {
+                        let p = -b * branch.phase_shift;
+                        rhs += self.incidence(branch.from_bus, branch.to_bus) * p;
+                    }
+                    removed.push((self.incidence(branch.from_bus, branch.to_bus), b));
+                }
+                Outage::Generator(i) => {
+                    let generator = &self.network.generators[i];
+                    if let Some(k) = self.reduced_index[generator.bus] {
+                        if generator.in_service {
+                            rhs[k] -= generator.active_power / base;
+                        }
+                    }
+                }
+            }
+        }
+
+        let z = self.solve(&rhs);
+        let theta_reduced = if removed.is_empty() {
+            z
+        } else {
+            let k = removed.len();
+            let xa: Vec<DVector<f64>> = removed.iter().map(|(a, _)| self.solve(a)).collect();
+            let compensation = DMatrix::from_fn(k, k, |r, c| {
+                let diag = if r == c { 1.0 / removed[r].1 } else { 0.0 };
+                diag - removed[r].0.dot(&xa[c])
+            });
+            let at_z = DVector::from_iterator(k, removed.iter().map(|(a, _)| a.dot(&z)));
+            let weights = compensation.lu().solve(&at_z).ok_or_else(|| {
+                CoreError::SimulationError("Compensation matrix is singular".into())
+            })?;
+            let mut theta = z;
+            for (x, w) in xa.iter().zip(weights.iter()) {
+                theta += x * *w;
+            }
+            theta
+        };
+
+        let slack_angle = self.network.buses[self.slack].voltage_angle;
+        Ok(Some(
+            self.reduced_index
+                .iter()
+                .map(|k| k.map_or(slack_angle, |k| theta_reduced[k]))
+                .collect(),
+        ))
+    }
+
+    /// Post-contingency DC branch flows, or `None` if the network islands
+    pub fn outage_flows(&self, contingency: &Contingency) -> Result<Option<Vec<BranchFlow>>> {
+        let Some(angles) = self.outage_angles(contingency)? else {
+            return Ok(None);
+        };
+        let case = contingency.apply(self.network)?;
+        let mut state = StateStore::new(case.bus_count());
+        state.voltage_angle = angles;
+        Ok(Some(dc_branch_flows(&case, &state)))
+    }
+
+    /// Screen a single contingency
+    pub fn screen(&self, contingency: &Contingency) -> Result<ScreeningResult> {
+        let mut result = ScreeningResult {
+            contingency: contingency.clone(),
+            islanded: false,
+            performance_index: 0.0,
+            max_loading: 0.0,
+            overloads: 0,
+            error: None,
+        };
+
+        let Some(flows) = self.outage_flows(contingency)? else {
+            result.islanded = true;
+            result.performance_index = f64::INFINITY;
+            return Ok(result);
+        };
+
+        let n = self.pi_order.max(1);
+        for flow in flows {
+            let rating = self.network.branches[flow.branch].rating;
+            if rating <= 0.0 {
+                continue;
+            }
+            let ratio = flow.s_max() / rating;
+            result.performance_index += ratio.powi(2 * n) / (2 * n) as f64;
+            result.max_loading = result.max_loading.max(ratio * 100.0);
+            if ratio > 1.0 {
+                result.overloads += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Screen all contingencies in parallel, most severe first
+    ///
+    /// Islanding contingencies rank above all others. A contingency that
+    /// cannot be screened is recorded with its [`error`](ScreeningResult::error)
+    /// and ranks first as well, so that it is not mistaken for a mild one.
+    pub fn rank(&self, contingencies: &[Contingency]) -> Vec<ScreeningResult> {
+        let mut ranked: Vec<ScreeningResult> = contingencies
+            .par_iter()
+            .map(|c| {
+                self.screen(c).unwrap_or_else(|e| ScreeningResult {
+                    contingency: c.clone(),
+                    islanded: false,
+                    performance_index: f64::INFINITY,
+                    max_loading: 0.0,
+                    overloads: 0,
+                    error: Some(e.to_string()),
+                })
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.performance_index.total_cmp(&a.performance_index));
+        ranked
+    }
+}
+
+impl ContingencyAnalysis<DcPowerFlowSolver> {
+    /// Contingency study using compensation updates of the base factorization
+    ///
+    /// Produces the same report as [`ContingencyAnalysis::run`] without
+    /// refactorizing the B matrix for every case.
+    pub fn run_fast(
+        &self,
+        network: &Network,
+        contingencies: &[Contingency],
+    ) -> Result<ContingencyReport> {
+        let mut base_state = network.initial_state();
+        let base = self.solver.solve_network(network, &mut base_state)?;
+        if !base.converged {
+            return Err(CoreError::SimulationError(format!(
+                "Base case did not converge (mismatch {:.3e})",
+                base.convergence_error
+            )));
+        }
+        let base_violations = self.check_violations(network, &base_state);
+
+        let screener = DcContingencyScreener::new(network)?;
+        let results = contingencies
+            .par_iter()
+            .map(|contingency| {
+                let result = |outcome, violations| ContingencyResult {
+                    contingency: contingency.clone(),
+                    outcome,
+                    violations,
+                };
+                let angles = contingency.apply(network).and_then(|case| {
+                    screener.outage_angles(contingency).map(|angles| (case, angles))
+                });
+                match angles {
+                    Ok((case, Some(angles))) => {
+                        let mut state = StateStore::new(case.bus_count());
+                        state.voltage_angle = angles;
+                        let violations = self.check_violations(&case, &state);
+                        result(ContingencyOutcome::Solved, violations)
+                    }
+                    Ok((case, None)) => {
+                        let islands = case
+                            .topology()
+                            .islands()
+                            .into_iter()
+                            .map(|island| island.into_iter().map(|b| b.0).collect())
+                            .collect();
+                        result(ContingencyOutcome::Islanded { islands }, Vec::new())
+                    }
+                    Err(e) => result(ContingencyOutcome::Failed(e.to_string()), Vec::new()),
+                }
+            })
+            .collect();
+
+        Ok(ContingencyReport {
+            base_violations,
+            results,
+        })
+    }
+}
+
+/// Verification of a screening ranking
+#[derive(Debug, Clone)]
+pub struct RankedReport {
+    /// Contingencies the screening found to island the network, not re-run
+    pub islanded: Vec<Contingency>,
+    /// Results of the verified contingencies, most severe first
+    pub report: ContingencyReport,
+}
+
+impl<S: NetworkSolver> ContingencyAnalysis<S> {
+    /// Run the `count` most severe screened contingencies with this solver
+    ///
+    /// Typically used with an AC solver to verify a DC screening ranking.
+    /// Islanding contingencies cannot be solved as a single network; they
+    /// are reported separately and do not count towards `count`.
+    pub fn run_ranked(
+        &self,
+        network: &Network,
+        ranked: &[ScreeningResult],
+        count: usize,
+    ) -> Result<RankedReport> {
+        let (islanded, connected): (Vec<_>, Vec<_>) = ranked.iter().partition(|r| r.islanded);
+        let selected: Vec<Contingency> = connected
+            .iter()
+            .take(count)
+            .map(|r| r.contingency.clone())
+            .collect();
+        Ok(RankedReport {
+            islanded: islanded.into_iter().map(|r| r.contingency.clone()).collect(),
+            report: self.run(network, &selected)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{n_minus_1, n_minus_k_branches, AcPowerFlowSolver};
+    use qsim_elements::{Branch, Bus, Generator, Load};
+
+    /// Solve a DC case with a fresh factorization
+    fn dc_reference(network: &Network) -> StateStore {
+        let mut state = network.initial_state();
+        DcPowerFlowSolver::new()
+            .solve_network(network, &mut state)
+            .unwrap();
+        state
+    }
+
+    fn meshed() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_generator(Generator::new(1, 80.0, 1.0));
+        network.add_load(Load::new(2, 70.0, 20.0));
+        network.add_load(Load::new(3, 60.0, 15.0));
+        network.add_load(Load::new(4, 40.0, 10.0));
+        for (f, t, x) in [
+            (0, 1, 0.06),
+            (0, 2, 0.12),
+            (1, 2, 0.09),
+            (1, 3, 0.10),
+            (2, 3, 0.05),
+            (3, 4, 0.08),
+            (2, 4, 0.11),
+        ] {
+            let mut branch = Branch::line(f, t, 0.01, x);
+            branch.rating = 60.0;
+            network.add_branch(branch);
+        }
+        network.branches[4].phase_shift = 0.05;
+        network
+    }
+
+    #[test]
+    fn test_compensation_matches_refactorization() {
+        let network = meshed();
+        let screener = DcContingencyScreener::new(&network).unwrap();
+
+        let mut contingencies = n_minus_1(&network);
+        contingencies.extend(n_minus_k_branches(&network, 2));
+        for contingency in &contingencies {
+            let case = contingency.apply(&network).unwrap();
+            let fast = screener.outage_angles(contingency).unwrap();
+            if case.topology().islands().len() > 1 {
+                assert!(fast.is_none());
+                continue;
+            }
+            let reference = dc_reference(&case);
+            let fast = fast.unwrap();
+            for (a, b) in fast.iter().zip(&reference.voltage_angle) {
+                assert!((a - b).abs() < 1e-10, "{}: {a} != {b}", contingency.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ranking_and_verification() {
+        let network = meshed();
+        let contingencies = n_minus_k_branches(&network, 2);
+        let ranked = DcContingencyScreener::new(&network)
+            .unwrap()
+            .rank(&contingencies);
+
+        assert!(ranked[0].islanded);
+        assert!(ranked
+            .windows(2)
+            .all(|w| w[0].performance_index >= w[1].performance_index));
+
+        let verified = ContingencyAnalysis::new(AcPowerFlowSolver::new())
+            .run_ranked(&network, &ranked, 3)
+            .unwrap();
+        let islanded = ranked.iter().filter(|r| r.islanded).count();
+        assert!(islanded > 0);
+        assert_eq!(verified.islanded.len(), islanded);
+        assert_eq!(verified.report.results.len(), 3);
+        assert!(verified.report.islanded().next().is_none());
+        assert_eq!(
+            verified.report.results[0].contingency,
+            ranked[islanded].contingency
+        );
+    }
+
+    #[test]
+    fn test_repeated_and_invalid_outages() {
+        let network = meshed();
+        let screener = DcContingencyScreener::new(&network).unwrap();
+
+        let single = screener.screen(&Contingency::branch(2)).unwrap();
+        let repeated = Contingency::new("Twice", vec![Outage::Branch(2), Outage::Branch(2)]);
+        let repeated = screener.screen(&repeated).unwrap();
+        assert!((single.performance_index - repeated.performance_index).abs() < 1e-12);
+        assert!((single.max_loading - repeated.max_loading).abs() < 1e-9);
+
+        // One bad case does not abort the ranking
+        let mut contingencies = n_minus_1(&network);
+        contingencies.push(Contingency::branch(99));
+        let ranked = screener.rank(&contingencies);
+        assert_eq!(ranked.len(), contingencies.len());
+        assert_eq!(ranked[0].contingency.name, "Branch 99");
+        assert!(ranked[0].error.is_some());
+        assert_eq!(ranked.iter().filter(|r| r.error.is_some()).count(), 1);
+    }
+
+    #[test]
+    fn test_run_fast_matches_run() {
+        let network = meshed();
+        let contingencies = n_minus_1(&network);
+        let analysis = ContingencyAnalysis::new(DcPowerFlowSolver::new());
+        let full = analysis.run(&network, &contingencies).unwrap();
+        let fast = analysis.run_fast(&network, &contingencies).unwrap();
+
+        for (a, b) in full.results.iter().zip(&fast.results) {
+            assert_eq!(a.outcome, b.outcome);
+            assert_eq!(a.violations.len(), b.violations.len());
+        }
+    }
+}
@@ -0,0 +1,1082 @@
+//! Electromechanical transient stability
+//!
+//! Generators with [`qsim_elements::MachineDynamics`] are voltage sources
+//! behind their transient impedance. Loads and all other injections
+//! (static generators, HVDC links) become constant admittances at the
+//! pre-disturbance power flow solution. Each step solves the network
+//! for the bus voltages and integrates the machine equations, by default
+//! with the modified Euler (Heun) method:
+//!
+//! ```text
+//! dδ/dt   = ωb·(ω − 1)
+//! dω/dt   = (Pm − Pe − D·(ω − 1)) / 2H
+//! dE'q/dt = (Efd − E'q − (Xd − X'd)·Id) / T'd0      two-axis only
+//! dE'd/dt = (−E'd + (Xq − X'q)·Iq) / T'q0           two-axis only
+//! ```
+//!
+//! Two-axis machines connect through the mean of X'd and X'q; saliency
+//! (`X'q ≠ X'd`) is handled by iterating the network solution on the
+//! machine currents. Machine states live in
+//! [`StateStore::dynamic_states`]: δ (radians) and ω (per-unit) for every
+//! machine in generator order, followed by E'q and E'd for two-axis
+//! machines.
+//!
+//! An attached exciter drives Efd (two-axis machines only), a governor Pm,
+//! and a stabilizer adds its output to the exciter voltage error. Their
+//! states follow the machine's own, exciter first, and start in steady
+//! state with the voltage and power references back-calculated.
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use nalgebra::{DMatrix, DVector, Dyn, LU};
+use num_complex::Complex64;
+use qsim_core::{CoreError, Result, StateStore, TimeStepper};
+use qsim_elements::{Exciter, Governor, MachineModel, Network, Stabilizer};
+
+use crate::machine_controls::{ControlModel, Signals};
+use crate::{build_ybus, DynamicModel};
+
+/// Impedance of a bolted fault (per-unit)
+const BOLTED_FAULT_IMPEDANCE: f64 = 1e-6;
+
+/// Tolerance when comparing event times to the simulation time (s)
+const EVENT_TIME_TOLERANCE: f64 = 1e-9;
+
+/// A network disturbance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Disturbance {
+    /// Three-phase fault at a bus through an impedance (per-unit)
+    BusFault {
+        bus: usize,
+        resistance: f64,
+        reactance: f64,
+    },
+    /// Remove the fault at a bus
+    ClearFault { bus: usize },
+    /// Open a branch
+    BranchTrip { branch: usize },
+}
+
+/// A disturbance applied at a given time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisturbanceEvent {
+    /// Simulation time (s)
+    pub time: f64,
+    /// What happens
+    pub disturbance: Disturbance,
+}
+
+impl DisturbanceEvent {
+    /// Create an event
+    pub fn new(time: f64, disturbance: Disturbance) -> Self {
+        Self { time, disturbance }
+    }
+
+    /// Bolted three-phase fault at a bus
+    pub fn bus_fault(time: f64, bus: usize) -> Self {
+        Self::new(
+            time,
+            Disturbance::BusFault {
+                bus,
+                resistance: 0.0,
+                reactance: 0.0,
+            },
+        )
+    }
+
+    /// Clear the fault at a bus
+    pub fn clear_fault(time: f64, bus: usize) -> Self {
+        Self::new(time, Disturbance::ClearFault { bus })
+    }
+
+    /// Trip a branch
+    pub fn branch_trip(time: f64, branch: usize) -> Self {
+        Self::new(time, Disturbance::BranchTrip { branch })
+    }
+}
+
+/// Machine data converted to the system base
+#[derive(Debug, Clone)]
+struct Machine {
+    generator: usize,
+    bus: usize,
+    model: MachineModel,
+    offset: usize,
+    inertia: f64,
+    damping: f64,
+    impedance: Complex64,
+    xd: f64,
+    xq: f64,
+    xd_prime: f64,
+    xq_prime: f64,
+    td0_prime: f64,
+    tq0_prime: f64,
+    /// Internal voltage magnitude of a classical machine
+    internal_voltage: f64,
+    mechanical_power: f64,
+    field_voltage: f64,
+    /// System base over machine base, scaling powers to the machine base
+    power_scale: f64,
+    exciter: Option<Exciter>,
+    governor: Option<Governor>,
+    stabilizer: Option<Stabilizer>,
+    voltage_reference: f64,
+    power_reference: f64,
+}
+
+impl Machine {
+    fn state_count(&self) -> usize {
+        let (_, _, stabilizer) = self.control_offsets();
+        stabilizer + self.stabilizer.map_or(0, |s| s.state_count()) - self.offset
+    }
+
+    /// Offsets of the exciter, governor and stabilizer states
+    fn control_offsets(&self) -> (usize, usize, usize) {
+        let exciter = self.offset
+            + match self.model {
+                MachineModel::Classical => 2,
+                MachineModel::TwoAxis => 4,
+            };
+        let governor = exciter + self.exciter.map_or(0, |e| e.state_count());
+        let stabilizer = governor + self.governor.map_or(0, |g| g.state_count());
+        (exciter, governor, stabilizer)
+    }
+
+    fn is_salient(&self) -> bool {
+        self.model == MachineModel::TwoAxis && self.xq_prime != self.xd_prime
+    }
+
+    /// Source voltage behind the Norton impedance in the network frame
+    ///
+    /// Two-axis machines use the mean of X'd and X'q, corrected by the
+    /// machine-frame current `idq`.
+    fn source_voltage(&self, x: &[f64], idq: Complex64) -> Complex64 {
+        let delta = x[self.offset];
+        match self.model {
+            MachineModel::Classical => Complex64::from_polar(self.internal_voltage, delta),
+            MachineModel::TwoAxis => {
+                let (eq, ed) = (x[self.offset + 2], x[self.offset + 3]);
+                let reactance = self.impedance.im;
+                Complex64::new(
+                    ed + (self.xq_prime - reactance) * idq.im,
+                    eq + (reactance - self.xd_prime) * idq.re,
+                ) * rotation(delta)
+            }
+        }
+    }
+}
+
+impl Machine {
+    /// Append steady-state controller states and set the references
+    fn initialize_controls(&mut self, terminal_voltage: f64, x: &mut Vec<f64>) -> Result<()> {
+        let (exciter, governor, stabilizer) = self.control_offsets();
+        x.resize(self.offset + self.state_count(), 0.0);
+        let mut signals = Signals {
+            terminal_voltage,
+            speed_deviation: 0.0,
+            electrical_power: self.mechanical_power * self.power_scale,
+            field_current: self.field_voltage,
+            stabilizer: 0.0,
+        };
+        if let Some(model) = self.stabilizer {
+            let states = &mut x[stabilizer..];
+            model.initialize(0.0, &signals, states)?;
+            let mut dx = vec![0.0; states.len()];
+            signals.stabilizer = model.evaluate(states, 0.0, &signals, &mut dx);
+        }
+        if let Some(model) = self.exciter {
+            self.voltage_reference =
+                model.initialize(self.field_voltage, &signals, &mut x[exciter..governor])?;
+        }
+        if let Some(model) = self.governor {
+            self.power_reference = model.initialize(
+                self.mechanical_power * self.power_scale,
+                &signals,
+                &mut x[governor..stabilizer],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Rotation from the machine d-q frame to the network frame
+fn rotation(delta: f64) -> Complex64 {
+    Complex64::from_polar(1.0, delta - FRAC_PI_2)
+}
+
+/// Rotor angle and speed trajectories of a simulation
+#[derive(Debug, Clone, Default)]
+pub struct StabilityTrajectory {
+    /// Sample times (s)
+    pub times: Vec<f64>,
+    /// Rotor angle per machine and sample (radians)
+    pub rotor_angles: Vec<Vec<f64>>,
+    /// Rotor speed per machine and sample (per-unit)
+    pub speeds: Vec<Vec<f64>>,
+}
+
+impl StabilityTrajectory {
+    /// Largest rotor angle difference between two machines over the run (radians)
+    pub fn max_angle_spread(&self) -> f64 {
+        (0..self.times.len())
+            .map(|k| {
+                let angles = self.rotor_angles.iter().map(|a| a[k]);
+                let max = angles.clone().fold(f64::NEG_INFINITY, f64::max);
+                let min = angles.fold(f64::INFINITY, f64::min);
+                max - min
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Whether no two machines drifted more than π apart
+    pub fn is_stable(&self) -> bool {
+        self.max_angle_spread() < PI
+    }
+}
+
+/// Transient stability simulation of a network after a power flow
+///
+/// As a [`TimeStepper`] each call advances the machine states in
+/// [`StateStore::dynamic_states`] with the modified Euler method and
+/// stores the bus voltages. As a [`DynamicModel`] it can be wrapped in any
+/// integrator, e.g. [`crate::RungeKutta4`] or [`crate::Trapezoidal`].
+#[derive(Debug, Clone)]
+pub struct TransientStability {
+    /// Nominal system frequency (Hz)
+    pub frequency: f64,
+    /// Convergence tolerance of the saliency iteration (per-unit current)
+    pub tolerance: f64,
+    /// Maximum saliency iterations per network solution
+    pub max_iterations: usize,
+    network: Network,
+    machines: Vec<Machine>,
+    /// Constant admittances replacing loads and static injections (per-unit)
+    shunts: Vec<Complex64>,
+    faults: Vec<(usize, Complex64)>,
+    events: Vec<DisturbanceEvent>,
+    next_event: usize,
+    time: f64,
+    lu: LU<Complex64, Dyn, Dyn>,
+}
+
+impl TransientStability {
+    /// Initialize from a solved power flow
+    ///
+    /// Sets `state.dynamic_states` to the steady-state machine states.
+    /// Generation at a bus is shared among its generators in proportion to
+    /// their active power and reactive sharing factor.
+    pub fn new(network: &Network, state: &mut StateStore) -> Result<Self> {
+        let n = network.bus_count();
+        if state.bus_count() != n {
+            return Err(CoreError::StateError(format!(
+                "State has {} buses, network has {n}",
+                state.bus_count()
+            )));
+        }
+        let base = network.base_mva;
+        let voltage: Vec<Complex64> = (0..n)
+            .map(|i| Complex64::from_polar(state.voltage_magnitude[i], state.voltage_angle[i]))
+            .collect();
+        let (load_p, load_q) = network.load_demand(state);
+        let mut other: Vec<Complex64> = (0..n)
+            .map(|i| Complex64::new(state.active_power[i], state.reactive_power[i]) / base)
+            .collect();
+
+        let mut machines = Vec::new();
+        let mut x = Vec::new();
+        for (index, generator) in network.generators.iter().enumerate() {
+            let Some(dynamics) = generator.dynamics.filter(|_| generator.in_service) else {
+                continue;
+            };
+            let bus = generator.bus;
+            if bus >= n {
+                return Err(CoreError::InvalidBusId(bus));
+            }
+            if dynamics.inertia <= 0.0 || dynamics.xd_prime <= 0.0 {
+                return Err(CoreError::SimulationError(format!(
+                    "Generator {index} needs positive H and X'd"
+                )));
+            }
+            if dynamics.model == MachineModel::TwoAxis
+                && (dynamics.td0_prime <= 0.0 || dynamics.tq0_prime <= 0.0)
+            {
+                return Err(CoreError::SimulationError(format!(
+                    "Generator {index} needs positive T'd0 and T'q0"
+                )));
+            }
+            if dynamics.exciter.is_some() && dynamics.model != MachineModel::TwoAxis {
+                return Err(CoreError::SimulationError(format!(
+                    "Exciter of generator {index} needs the two-axis model"
+                )));
+            }
+            if dynamics.stabilizer.is_some() && dynamics.exciter.is_none() {
+                return Err(CoreError::SimulationError(format!(
+                    "Stabilizer of generator {index} needs an exciter"
+                )));
+            }
+
+            // This unit's share of the bus generation
+            let units: Vec<_> = network
+                .generators
+                .iter()
+                .filter(|g| g.in_service && g.bus == bus)
+                .collect();
+            let share = |value: f64, total: f64| {
+                if total.abs() > 1e-12 {
+                    value / total
+                } else {
+                    1.0 / units.len() as f64
+                }
+            };
+            let p_share = share(
+                generator.active_power,
+                units.iter().map(|g| g.active_power).sum(),
+            );
+            let q_share = share(
+                generator.reactive_share,
+                units.iter().map(|g| g.reactive_share).sum(),
+            );
+            let generation = Complex64::new(
+                state.active_power[bus] + load_p[bus],
+                state.reactive_power[bus] + load_q[bus],
+            ) / base;
+            let power = Complex64::new(p_share * generation.re, q_share * generation.im);
+            other[bus] -= power;
+
+            let scale = base / generator.machine_base(base);
+            let norton_reactance = match dynamics.model {
+                MachineModel::Classical => dynamics.xd_prime,
+                MachineModel::TwoAxis => 0.5 * (dynamics.xd_prime + dynamics.xq_prime),
+            };
+            let mut machine = Machine {
+                generator: index,
+                bus,
+                model: dynamics.model,
+                offset: x.len(),
+                inertia: dynamics.inertia / scale,
+                damping: dynamics.damping / scale,
+                impedance: Complex64::new(dynamics.armature_resistance, norton_reactance) * scale,
+                xd: dynamics.xd * scale,
+                xq: dynamics.xq * scale,
+                xd_prime: dynamics.xd_prime * scale,
+                xq_prime: dynamics.xq_prime * scale,
+                td0_prime: dynamics.td0_prime,
+                tq0_prime: dynamics.tq0_prime,
+                internal_voltage: 0.0,
+                mechanical_power: 0.0,
+                field_voltage: 0.0,
+                power_scale: scale,
+                exciter: dynamics.exciter,
+                governor: dynamics.governor,
+                stabilizer: dynamics.stabilizer,
+                voltage_reference: 0.0,
+                power_reference: 0.0,
+            };
+
+            let v = voltage[bus];
+            let current = (power / v).conj();
+            let ra = machine.impedance.re;
+            machine.mechanical_power = (v * current.conj()).re + ra * current.norm_sqr();
+            match machine.model {
+                MachineModel::Classical => {
+                    let e = v + machine.impedance * current;
+                    machine.internal_voltage = e.norm();
+                    x.extend([e.arg(), 1.0]);
+                }
+                MachineModel::TwoAxis => {
+                    let delta = (v + Complex64::new(ra, machine.xq) * current).arg();
+                    let to_machine = rotation(delta).conj();
+                    let (vdq, idq) = (v * to_machine, current * to_machine);
+                    let eq = vdq.im + ra * idq.im + machine.xd_prime * idq.re;
+                    let ed = vdq.re + ra * idq.re - machine.xq_prime * idq.im;
+                    machine.field_voltage = eq + (machine.xd - machine.xd_prime) * idq.re;
+                    x.extend([delta, 1.0, eq, ed]);
+                }
+            }
+            machine.initialize_controls(v.norm(), &mut x)?;
+            machines.push(machine);
+        }
+        if machines.is_empty() {
+            return Err(CoreError::SimulationError(
+                "No in-service generator has machine dynamics".to_string(),
+            ));
+        }
+
+        let shunts = (0..n)
+            .map(|i| {
+                let v2 = voltage[i].norm_sqr();
+                if v2 > 1e-12 {
+                    -other[i].conj() / v2
+                } else {
+                    Complex64::new(0.0, 0.0)
+                }
+            })
+            .collect();
+
+        let mut simulation = Self {
+            frequency: 50.0,
+            tolerance: 1e-10,
+            max_iterations: 100,
+            network: network.clone(),
+            machines,
+            shunts,
+            faults: Vec::new(),
+            events: Vec::new(),
+            next_event: 0,
+            time: 0.0,
+            lu: DMatrix::<Complex64>::zeros(0, 0).lu(),
+        };
+        simulation.lu = simulation.network_matrix().lu();
+        state.dynamic_states = x;
+        Ok(simulation)
+    }
+
+    /// Resume a run from saved machine states at `time`
+    ///
+    /// `initial` is the pre-disturbance power flow the run started from, so
+    /// machine parameters and load admittances are rebuilt as before; `saved`
+    /// holds the machine states reached at `time`. Schedule the same events
+    /// as the original run: those due by `time` are replayed in order on
+    /// the next step, restoring applied faults and tripped branches. Start
+    /// a wrapping integrator at `time` too, e.g. with
+    /// [`RungeKutta4::with_start_time`](crate::RungeKutta4::with_start_time).
+    pub fn resume(
+        network: &Network,
+        initial: &StateStore,
+        saved: &StateStore,
+        time: f64,
+    ) -> Result<Self> {
+        let mut simulation = Self::new(network, &mut initial.clone())?;
+        let count = simulation.state_count();
+        if saved.dynamic_states.len() != count {
+            return Err(CoreError::StateError(format!(
+                "Expected {count} machine states, found {}",
+                saved.dynamic_states.len()
+            )));
+        }
+        simulation.time = time;
+        Ok(simulation)
+    }
+
+    /// Builder: set the nominal frequency (Hz)
+    pub fn with_frequency(mut self, frequency: f64) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Builder: schedule disturbances
+    pub fn with_events(mut self, events: impl IntoIterator<Item = DisturbanceEvent>) -> Self {
+        for event in events {
+            self.add_event(event);
+        }
+        self
+    }
+
+    /// Schedule a disturbance; events in the past are applied on the next step
+    pub fn add_event(&mut self, event: DisturbanceEvent) {
+        let position = self.events[self.next_event..].partition_point(|e| e.time <= event.time);
+        self.events.insert(self.next_event + position, event);
+    }
+
+    /// Current simulation time (s)
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Number of simulated machines
+    pub fn machine_count(&self) -> usize {
+        self.machines.len()
+    }
+
+    /// Generator index of a machine
+    pub fn generator(&self, machine: usize) -> usize {
+        self.machines[machine].generator
+    }
+
+    /// Rotor angle of a machine (radians)
+    pub fn rotor_angle(&self, state: &StateStore, machine: usize) -> f64 {
+        state.dynamic_states[self.machines[machine].offset]
+    }
+
+    /// Rotor speed of a machine (per-unit)
+    pub fn speed(&self, state: &StateStore, machine: usize) -> f64 {
+        state.dynamic_states[self.machines[machine].offset + 1]
+    }
+
+    /// Run for `duration` seconds in steps of `time_step`, recording trajectories
+    pub fn simulate(
+        &mut self,
+        state: &mut StateStore,
+        duration: f64,
+        time_step: f64,
+    ) -> Result<StabilityTrajectory> {
+        let m = self.machines.len();
+        let mut trajectory = StabilityTrajectory {
+            times: Vec::new(),
+            rotor_angles: vec![Vec::new(); m],
+            speeds: vec![Vec::new(); m],
+        };
+        let mut record = |sim: &Self, state: &StateStore| {
+            trajectory.times.push(sim.time);
+            for k in 0..m {
+                trajectory.rotor_angles[k].push(sim.rotor_angle(state, k));
+                trajectory.speeds[k].push(sim.speed(state, k));
+            }
+        };
+
+        record(self, state);
+        let steps = (duration / time_step).round() as usize;
+        for _ in 0..steps {
+            self.step(state, time_step)?;
+            record(self, state);
+        }
+        Ok(trajectory)
+    }
+
+    /// Admittance matrix with machine, load and fault admittances
+    fn network_matrix(&self) -> DMatrix<Complex64> {
+        let mut y = build_ybus(&self.network);
+        for (i, shunt) in self.shunts.iter().enumerate() {
+            y[(i, i)] += shunt;
+        }
+        for machine in &self.machines {
+            y[(machine.bus, machine.bus)] += machine.impedance.inv();
+        }
+        for &(bus, admittance) in &self.faults {
+            y[(bus, bus)] += admittance;
+        }
+        y
+    }
+
+    /// Whether any event opens a branch (faults leave the branches intact)
+    fn has_branch_trips(&self) -> bool {
+        self.events
+            .iter()
+            .any(|e| matches!(e.disturbance, Disturbance::BranchTrip { .. }))
+    }
+
+    /// Apply events that are due and refactor the network if anything changed
+    fn apply_events(&mut self) -> Result<()> {
+        let mut changed = false;
+        while let Some(event) = self
+            .events
+            .get(self.next_event)
+            .filter(|e| e.time <= self.time + EVENT_TIME_TOLERANCE)
+            .copied()
+        {
+            self.next_event += 1;
+            changed = true;
+            match event.disturbance {
+                Disturbance::BusFault {
+                    bus,
+                    resistance,
+                    reactance,
+                } => {
+                    if bus >= self.network.bus_count() {
+                        return Err(CoreError::InvalidBusId(bus));
+                    }
+                    let mut impedance = Complex64::new(resistance, reactance);
+                    if impedance.norm() < BOLTED_FAULT_IMPEDANCE {
+                        impedance = Complex64::new(BOLTED_FAULT_IMPEDANCE, 0.0);
+                    }
+                    self.faults.retain(|&(b, _)| b != bus);
+                    self.faults.push((bus, impedance.inv()));
+                }
+                Disturbance::ClearFault { bus } => self.faults.retain(|&(b, _)| b != bus),
+                Disturbance::BranchTrip { branch } => {
+                    self.network
+                        .branches
+                        .get_mut(branch)
+                        .ok_or(CoreError::InvalidBranchId(branch))?
+                        .in_service = false;
+                }
+            }
+        }
+        if changed {
+            self.lu = self.network_matrix().lu();
+        }
+        Ok(())
+    }
+
+    /// Bus voltages and machine currents (network frame) for states `x`
+    fn solve_network(&self, x: &[f64]) -> Result<(DVector<Complex64>, Vec<Complex64>)> {
+        let n = self.network.bus_count();
+        let salient = self.machines.iter().any(Machine::is_salient);
+        let mut idq = vec![Complex64::new(0.0, 0.0); self.machines.len()];
+
+        for _ in 0..self.max_iterations {
+            let sources: Vec<Complex64> = self
+                .machines
+                .iter()
+                .zip(&idq)
+                .map(|(machine, &idq)| machine.source_voltage(x, idq))
+                .collect();
+            let mut rhs = DVector::from_element(n, Complex64::new(0.0, 0.0));
+            for (machine, e) in self.machines.iter().zip(&sources) {
+                rhs[machine.bus] += e / machine.impedance;
+            }
+            let v = self.lu.solve(&rhs).ok_or_else(|| {
+                CoreError::SimulationError("Singular network admittance matrix".to_string())
+            })?;
+            let currents: Vec<Complex64> = self
+                .machines
+                .iter()
+                .zip(&sources)
+                .map(|(machine, e)| (e - v[machine.bus]) / machine.impedance)
+                .collect();
+            if !salient {
+                return Ok((v, currents));
+            }
+
+            let mut change: f64 = 0.0;
+            for ((machine, current), idq) in self.machines.iter().zip(&currents).zip(&mut idq) {
+                let new = current * rotation(x[machine.offset]).conj();
+                change = change.max((new - *idq).norm());
+                *idq = new;
+            }
+            if change < self.tolerance {
+                return Ok((v, currents));
+            }
+        }
+        Err(CoreError::SimulationError(
+            "Network solution did not converge".to_string(),
+        ))
+    }
+
+    /// Time derivatives of the machine states
+    fn machine_derivatives(
+        &self,
+        x: &[f64],
+        v: &DVector<Complex64>,
+        currents: &[Complex64],
+    ) -> Vec<f64> {
+        let omega_base = 2.0 * PI * self.frequency;
+        let mut dx = vec![0.0; x.len()];
+        for (machine, &current) in self.machines.iter().zip(currents) {
+            let o = machine.offset;
+            let speed_deviation = x[o + 1] - 1.0;
+            let electrical =
+                (v[machine.bus] * current.conj()).re + machine.impedance.re * current.norm_sqr();
+            let idq = current * rotation(x[o]).conj();
+            let field_current = match machine.model {
+                MachineModel::Classical => 0.0,
+                MachineModel::TwoAxis => x[o + 2] + (machine.xd - machine.xd_prime) * idq.re,
+            };
+
+            let (exciter, governor, stabilizer) = machine.control_offsets();
+            let end = o + machine.state_count();
+            let mut signals = Signals {
+                terminal_voltage: v[machine.bus].norm(),
+                speed_deviation,
+                electrical_power: electrical * machine.power_scale,
+                field_current,
+                stabilizer: 0.0,
+            };
+            if let Some(model) = machine.stabilizer {
+                signals.stabilizer =
+                    model.evaluate(&x[stabilizer..end], 0.0, &signals, &mut dx[stabilizer..end]);
+            }
+            let field_voltage = match machine.exciter {
+                Some(model) => model.evaluate(
+                    &x[exciter..governor],
+                    machine.voltage_reference,
+                    &signals,
+                    &mut dx[exciter..governor],
+                ),
+                None => machine.field_voltage,
+            };
+            let mechanical_power = match machine.governor {
+                Some(model) => {
+                    model.evaluate(
+                        &x[governor..stabilizer],
+                        machine.power_reference,
+                        &signals,
+                        &mut dx[governor..stabilizer],
+                    ) / machine.power_scale
+                }
+                None => machine.mechanical_power,
+            };
+
+            dx[o] = omega_base * speed_deviation;
+            dx[o + 1] = (mechanical_power - electrical - machine.damping * speed_deviation)
+                / (2.0 * machine.inertia);
+
+            if machine.model == MachineModel::TwoAxis {
+                dx[o + 2] = (field_voltage - x[o + 2] - (machine.xd - machine.xd_prime) * idq.re)
+                    / machine.td0_prime;
+                dx[o + 3] =
+                    (-x[o + 3] + (machine.xq - machine.xq_prime) * idq.im) / machine.tq0_prime;
+            }
+        }
+        dx
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Result<Vec<f64>> {
+        let (v, currents) = self.solve_network(x)?;
+        Ok(self.machine_derivatives(x, &v, &currents))
+    }
+}
+
+impl DynamicModel for TransientStability {
+    fn state_count(&self) -> usize {
+        self.machines.iter().map(Machine::state_count).sum()
+    }
+
+    fn begin_step(&mut self, time: f64) -> Result<()> {
+        self.time = time;
+        self.apply_events()
+    }
+
+    fn derivatives(&self, _time: f64, x: &[f64], _y: &[f64], dx: &mut [f64]) -> Result<()> {
+        dx.copy_from_slice(&self.evaluate(x)?);
+        Ok(())
+    }
+
+    fn store(&self, _time: f64, x: &[f64], _y: &[f64], state: &mut StateStore) -> Result<()> {
+        let (v, _) = self.solve_network(x)?;
+        for (i, v) in v.iter().enumerate() {
+            state.voltage_magnitude[i] = v.norm();
+            state.voltage_angle[i] = v.arg();
+        }
+        Ok(())
+    }
+
+    fn changes_network(&self) -> bool {
+        self.has_branch_trips()
+    }
+}
+
+impl TimeStepper for TransientStability {
+    fn step(&mut self, state: &mut StateStore, dt: f64) -> Result<()> {
+        let count = self.state_count();
+        if state.dynamic_states.len() != count {
+            return Err(CoreError::StateError(format!(
+                "Expected {count} machine states, found {}",
+                state.dynamic_states.len()
+            )));
+        }
+        self.apply_events()?;
+
+        // Modified Euler: explicit predictor, trapezoidal corrector
+        let x0 = &state.dynamic_states;
+        let f0 = self.evaluate(x0)?;
+        let predicted: Vec<f64> = x0.iter().zip(&f0).map(|(x, f)| x + dt * f).collect();
+        let f1 = self.evaluate(&predicted)?;
+        let x1: Vec<f64> = x0
+            .iter()
+            .zip(f0.iter().zip(&f1))
+            .map(|(x, (a, b))| x + 0.5 * dt * (a + b))
+            .collect();
+
+        self.time += dt;
+        self.store(self.time, &x1, &[], state)?;
+        state.dynamic_states = x1;
+        Ok(())
+    }
+
+    fn simulation_time(&self) -> Option<f64> {
+        Some(self.time)
+    }
+
+    fn changes_network(&self) -> bool {
+        self.has_branch_trips()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AcPowerFlowSolver, NetworkSolver};
+    use qsim_elements::{
+        Ac1a, Branch, Bus, Dc1a, Generator, Hygov, Ieeeg1, Load, MachineDynamics, Pss2a, St1a,
+        Tgov1,
+    };
+
+    /// Generator at bus 1 feeding a near-infinite bus 0 over two lines
+    fn machine_infinite_bus(dynamics: MachineDynamics) -> (Network, StateStore) {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.4));
+        network.add_branch(Branch::line(0, 1, 0.0, 0.4));
+        network.add_generator(
+            Generator::new(0, 0.0, 1.0).with_dynamics(MachineDynamics::classical(1e6, 0.0, 1e-4)),
+        );
+        network.add_generator(Generator::new(1, 80.0, 1.0).with_dynamics(dynamics));
+        let mut state = network.initial_state();
+        assert!(
+            AcPowerFlowSolver::new()
+                .solve_network(&network, &mut state)
+                .unwrap()
+                .converged
+        );
+        (network, state)
+    }
+
+    fn relative_angle(sim: &TransientStability, state: &StateStore) -> f64 {
+        sim.rotor_angle(state, 1) - sim.rotor_angle(state, 0)
+    }
+
+    #[test]
+    fn test_undisturbed_system_stays_in_equilibrium() {
+        let dynamics = MachineDynamics::two_axis(5.0, 2.0, 1.8, 1.7, 0.3, 0.55);
+        let (network, mut state) = machine_infinite_bus(dynamics);
+        let initial = state.clone();
+        let mut sim = TransientStability::new(&network, &mut state).unwrap();
+        let start = state.dynamic_states.clone();
+        let trajectory = sim.simulate(&mut state, 1.0, 0.01).unwrap();
+
+        assert_eq!(trajectory.times.len(), 101);
+        assert!((sim.time() - 1.0).abs() < 1e-9);
+        for (x, x0) in state.dynamic_states.iter().zip(&start) {
+            assert!((x - x0).abs() < 1e-8);
+        }
+        for i in 0..2 {
+            assert!((state.voltage_magnitude[i] - initial.voltage_magnitude[i]).abs() < 1e-8);
+            assert!((state.voltage_angle[i] - initial.voltage_angle[i]).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_fault_clearing_time_decides_stability() {
+        let run = |clearing: f64| {
+            let (network, mut state) =
+                machine_infinite_bus(MachineDynamics::classical(5.0, 0.0, 0.3));
+            let mut sim = TransientStability::new(&network, &mut state)
+                .unwrap()
+                .with_events([
+                    DisturbanceEvent::bus_fault(0.1, 1),
+                    DisturbanceEvent::clear_fault(0.1 + clearing, 1),
+                ]);
+            sim.simulate(&mut state, 2.0, 0.005).unwrap()
+        };
+
+        let fast = run(0.1);
+        let slow = run(0.5);
+        assert!(fast.is_stable());
+        assert!(!slow.is_stable());
+        // The machine accelerates during the fault
+        assert!(fast.speeds[1].iter().any(|&w| w > 1.001));
+    }
+
+    #[test]
+    fn test_integrators_agree() {
+        let (network, mut initial) =
+            machine_infinite_bus(MachineDynamics::two_axis(5.0, 2.0, 1.8, 1.7, 0.3, 0.55));
+        let sim = TransientStability::new(&network, &mut initial)
+            .unwrap()
+            .with_events([
+                DisturbanceEvent::bus_fault(0.05, 1),
+                DisturbanceEvent::clear_fault(0.15, 1),
+            ]);
+        let run = |stepper: &mut dyn TimeStepper| {
+            let mut state = initial.clone();
+            for _ in 0..100 {
+                stepper.step(&mut state, 0.005).unwrap();
+            }
+            state
+        };
+
+        let heun = run(&mut sim.clone());
+        let rk4 = run(&mut crate::RungeKutta4::new(sim.clone()));
+        let mut trapezoidal = crate::Trapezoidal::new(sim).with_tolerances(1e-6, 1e-8);
+        let implicit = run(&mut trapezoidal);
+
+        assert!(rk4.dynamic_states[2] > initial.dynamic_states[2] + 0.05);
+        for other in [&heun, &implicit] {
+            assert!((other.dynamic_states[2] - rk4.dynamic_states[2]).abs() < 1e-3);
+            assert!((other.voltage_magnitude[1] - rk4.voltage_magnitude[1]).abs() < 1e-3);
+        }
+        assert!((trapezoidal.model.time() - 0.495).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_branch_trips_change_the_network() {
+        let (network, mut state) = machine_infinite_bus(MachineDynamics::classical(5.0, 0.0, 0.3));
+        let sim = TransientStability::new(&network, &mut state)
+            .unwrap()
+            .with_events([
+                DisturbanceEvent::bus_fault(0.1, 1),
+                DisturbanceEvent::clear_fault(0.2, 1),
+            ]);
+        // Faults leave the branches as they are
+        assert!(!TimeStepper::changes_network(&sim));
+
+        let sim = sim.with_events([DisturbanceEvent::branch_trip(0.2, 0)]);
+        assert!(TimeStepper::changes_network(&sim));
+        assert!(crate::RungeKutta4::new(sim).changes_network());
+    }
+
+    #[test]
+    fn test_resumed_run_matches_uninterrupted_run() {
+        let dynamics = MachineDynamics::two_axis(5.0, 2.0, 1.8, 1.7, 0.3, 0.55)
+            .with_exciter(Exciter::St1a(St1a::default()));
+        let (network, initial) = machine_infinite_bus(dynamics);
+        let events = [
+            DisturbanceEvent::bus_fault(0.05, 1),
+            DisturbanceEvent::clear_fault(0.15, 1),
+            DisturbanceEvent::branch_trip(0.15, 0),
+        ];
+        let run = |sim: &mut TransientStability, state: &mut StateStore, steps: usize| {
+            for _ in 0..steps {
+                sim.step(state, 0.005).unwrap();
+            }
+        };
+
+        let mut state = initial.clone();
+        let mut sim = TransientStability::new(&network, &mut state)
+            .unwrap()
+            .with_events(events);
+        run(&mut sim, &mut state, 100);
+
+        // Stop after the fault is cleared and the line tripped, then resume
+        let mut split = initial.clone();
+        let mut first = TransientStability::new(&network, &mut split)
+            .unwrap()
+            .with_events(events);
+        run(&mut first, &mut split, 40);
+        let mut second = TransientStability::resume(&network, &initial, &split, 40.0 * 0.005)
+            .unwrap()
+            .with_events(events);
+        run(&mut second, &mut split, 60);
+
+        assert!((second.time() - sim.time()).abs() < 1e-9);
+        for (resumed, reference) in split.dynamic_states.iter().zip(&state.dynamic_states) {
+            assert!((resumed - reference).abs() < 1e-12);
+        }
+        assert!((split.voltage_magnitude[1] - state.voltage_magnitude[1]).abs() < 1e-12);
+
+        let mut wrong = split.clone();
+        wrong.dynamic_states.pop();
+        assert!(TransientStability::resume(&network, &initial, &wrong, 0.2).is_err());
+    }
+
+    #[test]
+    fn test_line_trip_settles_at_new_equilibrium() {
+        let (network, mut state) = machine_infinite_bus(MachineDynamics::classical(5.0, 20.0, 0.3));
+
+        // Internal voltages from the power flow solution
+        let internal = |bus: usize, reactance: f64| {
+            let v = Complex64::from_polar(state.voltage_magnitude[bus], state.voltage_angle[bus]);
+            let s = Complex64::new(state.active_power[bus], state.reactive_power[bus]) / 100.0;
+            (v + Complex64::new(0.0, reactance) * (s / v).conj()).norm()
+        };
+        let (e0, e1) = (internal(0, 1e-4), internal(1, 0.3));
+        let reactance = 0.3 + 0.4 + 1e-4;
+        let expected = (0.8 * reactance / (e0 * e1)).asin();
+
+        let mut sim = TransientStability::new(&network, &mut state)
+            .unwrap()
+            .with_events([DisturbanceEvent::branch_trip(0.1, 0)]);
+        let before = relative_angle(&sim, &state);
+        let trajectory = sim.simulate(&mut state, 15.0, 0.01).unwrap();
+
+        assert!(trajectory.is_stable());
+        assert!(relative_angle(&sim, &state) > before);
+        assert!((relative_angle(&sim, &state) - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_controllers_start_in_equilibrium() {
+        let exciters = [
+            Exciter::St1a(St1a::default()),
+            Exciter::Ac1a(Ac1a::default()),
+            Exciter::Dc1a(Dc1a::default()),
+        ];
+        let governors = [
+            Governor::Tgov1(Tgov1::default()),
+            Governor::Hygov(Hygov::default()),
+            Governor::Ieeeg1(Ieeeg1::default()),
+        ];
+        for (exciter, governor) in exciters.into_iter().zip(governors) {
+            let dynamics = MachineDynamics::two_axis(5.0, 2.0, 1.8, 1.7, 0.3, 0.55)
+                .with_exciter(exciter)
+                .with_governor(governor)
+                .with_stabilizer(Stabilizer::Pss2a(Pss2a::default()));
+            let (network, mut state) = machine_infinite_bus(dynamics);
+            let mut sim = TransientStability::new(&network, &mut state).unwrap();
+            let start = state.dynamic_states.clone();
+            assert_eq!(start.len(), 2 + 4 + 5 + governor.state_count() + 13);
+            sim.simulate(&mut state, 1.0, 0.01).unwrap();
+
+            for (x, x0) in state.dynamic_states.iter().zip(&start) {
+                assert!((x - x0).abs() < 1e-8, "{exciter:?} {governor:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_exciter_requires_two_axis_model() {
+        let dynamics =
+            MachineDynamics::classical(5.0, 0.0, 0.3).with_exciter(Exciter::St1a(St1a::default()));
+        let (network, mut state) = machine_infinite_bus(dynamics);
+        assert!(TransientStability::new(&network, &mut state).is_err());
+    }
+
+    #[test]
+    fn test_voltage_regulator_holds_terminal_voltage() {
+        let run = |exciter: Option<Exciter>| {
+            let mut dynamics = MachineDynamics::two_axis(5.0, 2.0, 1.8, 1.7, 0.3, 0.55);
+            dynamics.exciter = exciter;
+            let (network, mut state) = machine_infinite_bus(dynamics);
+            let initial = state.voltage_magnitude[1];
+            let mut sim = TransientStability::new(&network, &mut state)
+                .unwrap()
+                .with_events([DisturbanceEvent::branch_trip(0.1, 0)]);
+            let trajectory = sim.simulate(&mut state, 10.0, 0.005).unwrap();
+            assert!(trajectory.is_stable());
+            (state.voltage_magnitude[1] - initial).abs()
+        };
+
+        let manual = run(None);
+        let regulated = run(Some(Exciter::St1a(St1a {
+            ka: 50.0,
+            ..St1a::default()
+        })));
+        assert!(manual > 0.01);
+        assert!(regulated < 0.25 * manual);
+    }
+
+    #[test]
+    fn test_governor_settles_at_droop_frequency() {
+        // Islanded generator losing a 20 MW load when its feeder trips
+        let run = |governor: Option<Governor>| {
+            let mut network = Network::new();
+            network.add_bus(Bus::slack(1.0));
+            network.add_bus(Bus::pq(0.0, 0.0));
+            network.add_bus(Bus::pq(0.0, 0.0));
+            network.add_branch(Branch::line(0, 1, 0.0, 0.1));
+            network.add_branch(Branch::line(0, 2, 0.0, 0.1));
+            network.add_load(Load::new(1, 40.0, 0.0));
+            network.add_load(Load::new(2, 20.0, 0.0));
+            let mut dynamics = MachineDynamics::classical(5.0, 0.0, 0.3);
+            dynamics.governor = governor;
+            network.add_generator(Generator::new(0, 60.0, 1.0).with_dynamics(dynamics));
+            let mut state = network.initial_state();
+            assert!(
+                AcPowerFlowSolver::new()
+                    .solve_network(&network, &mut state)
+                    .unwrap()
+                    .converged
+            );
+            let mut sim = TransientStability::new(&network, &mut state)
+                .unwrap()
+                .with_events([DisturbanceEvent::branch_trip(0.1, 1)]);
+            sim.simulate(&mut state, 30.0, 0.01).unwrap();
+            sim.speed(&state, 0) - 1.0
+        };
+
+        let free = run(None);
+        let droop = 0.05;
+        let governed = run(Some(Governor::Tgov1(Tgov1 {
+            r: droop,
+            t2: 0.0,
+            t3: 0.0,
+            ..Tgov1::default()
+        })));
+        // Speed keeps rising without a governor; with one it settles at
+        // R·ΔP with ΔP = 0.2 per-unit on the 100 MVA machine base
+        assert!(free > 0.1);
+        assert!((governed - droop * 0.2).abs() < 1e-3);
+    }
+}
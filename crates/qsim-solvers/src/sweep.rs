@@ -0,0 +1,217 @@
+//! Backward/forward sweep power flow for radial feeders
+//!
+//! Buses are ordered breadth-first from the slack bus. Each iteration:
+//!
+//! ```text
+//! backward:  J_c = Σ I_children − conj(S_c / V_c)    (leaves to root)
+//! forward:   V_c = (−J_c − y_cp·V_p) / y_cc          (root to leaves)
+//! ```
+//!
+//! where `y` are the π-model two-port admittances of the branch from the
+//! parent `p` to the child `c`. A meshed network is rejected with its loop
+//! branches listed. PV buses are treated as PQ buses with their
+//! scheduled injections; voltage-dependent loads are re-evaluated on every
+//! backward sweep.
+
+use num_complex::Complex64;
+use qsim_core::{BranchId, BusId, CoreError, Result, SolverResult, StateStore};
+use qsim_elements::Network;
+
+use crate::{
+    ac_branch_flows, branch_admittance, BranchAdmittance, BranchFlow, NetworkSolver,
+    VoltageDependentLoads,
+};
+
+/// Backward/forward sweep solver for radial networks
+#[derive(Debug, Clone)]
+pub struct BackwardForwardSweepSolver {
+    /// Convergence tolerance on the voltage update (per-unit)
+    pub tolerance: f64,
+    /// Maximum number of sweeps
+    pub max_iterations: usize,
+}
+
+impl BackwardForwardSweepSolver {
+    /// Create a solver with default settings
+    pub fn new() -> Self {
+        Self {
+            tolerance: 1e-8,
+            max_iterations: 50,
+        }
+    }
+}
+
+impl Default for BackwardForwardSweepSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Two-port admittances with the parent as the "from" side
+fn oriented_admittance(network: &Network, parent: usize, branch: usize) -> BranchAdmittance {
+    let y = branch_admittance(&network.branches[branch]);
+    if network.branches[branch].from_bus == parent {
+        y
+    } else {
+        BranchAdmittance {
+            yff: y.ytt,
+            yft: y.ytf,
+            ytf: y.yft,
+            ytt: y.yff,
+        }
+    }
+}
+
+impl NetworkSolver for BackwardForwardSweepSolver {
+    fn solve_network(&self, network: &Network, state: &mut StateStore) -> Result<SolverResult> {
+        let n = network.bus_count();
+        if state.bus_count() != n {
+            return Err(CoreError::StateError(format!(
+                "State has {} buses, network has {n}",
+                state.bus_count()
+            )));
+        }
+        let root = network
+            .slack_bus()
+            .ok_or_else(|| CoreError::SimulationError("Network has no buses".into()))?;
+        let order = network.topology().radial_order(BusId(root))?;
+
+        let base = network.base_mva;
+        let s_spec: Vec<Complex64> = (0..n)
+            .map(|i| Complex64::new(state.active_power[i], state.reactive_power[i]) / base)
+            .collect();
+        let mut v: Vec<Complex64> = (0..n)
+            .map(|i| Complex64::from_polar(state.voltage_magnitude[i], state.voltage_angle[i]))
+            .collect();
+        let loads = VoltageDependentLoads::from_network(network);
+        let shunt: Vec<Complex64> = network
+            .shunt_admittance()
+            .into_iter()
+            .map(|(g, b)| Complex64::new(g, b) / base)
+            .collect();
+        v[root] = Complex64::from_polar(network.voltage_setpoint(root), state.voltage_angle[root]);
+
+        let links: Vec<(usize, usize, BranchAdmittance)> = order
+            .iter()
+            .filter_map(|node| {
+                node.parent.map(|(BusId(p), BranchId(b))| {
+                    (node.bus_id.0, p, oriented_admittance(network, p, b))
+                })
+            })
+            .collect();
+
+        let mut iterations = 0;
+        let mut change = f64::INFINITY;
+        while iterations < self.max_iterations {
+            iterations += 1;
+
+            // Backward: current drawn from each parent into the branch
+            let vm: Vec<f64> = v.iter().map(|vi| vi.norm()).collect();
+            let s_bus = loads.injections(&s_spec, &vm);
+            let mut outgoing = vec![Complex64::new(0.0, 0.0); n];
+            let mut received = vec![Complex64::new(0.0, 0.0); n];
+            for &(child, parent, y) in links.iter().rev() {
+                let j =
+                    outgoing[child] - (s_bus[child] / v[child]).conj() + shunt[child] * v[child];
+                received[child] = j;
+                let v_child = (-j - y.ytf * v[parent]) / y.ytt;
+                outgoing[parent] += y.yff * v[parent] + y.yft * v_child;
+            }
+
+            // Forward: update voltages from the root
+            change = 0.0;
+            for &(child, parent, y) in &links {
+                let updated = (-received[child] - y.ytf * v[parent]) / y.ytt;
+                change = f64::max(change, (updated - v[child]).norm());
+                v[child] = updated;
+            }
+            if change < self.tolerance {
+                break;
+            }
+        }
+
+        for (i, vi) in v.iter().enumerate() {
+            let (magnitude, angle) = vi.to_polar();
+            state.voltage_magnitude[i] = magnitude;
+            state.voltage_angle[i] = angle;
+        }
+        // Slack injection from the currents leaving the root and its shunt
+        let mut root_current = shunt[root] * v[root];
+        for &(child, parent, y) in &links {
+            if parent == root {
+                root_current += y.yff * v[root] + y.yft * v[child];
+            }
+        }
+        let s_root = v[root] * root_current.conj() * base;
+        state.active_power[root] = s_root.re;
+        state.reactive_power[root] = s_root.im;
+
+        if change < self.tolerance {
+            Ok(SolverResult::converged(iterations, change))
+        } else {
+            Ok(SolverResult::failed(iterations, change))
+        }
+    }
+
+    fn branch_flows(&self, network: &Network, state: &StateStore) -> Vec<BranchFlow> {
+        ac_branch_flows(network, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AcPowerFlowSolver;
+    use qsim_elements::{Branch, Bus, Load, LoadModel};
+
+    fn feeder() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.02));
+        for _ in 0..4 {
+            network.add_bus(Bus::pq(0.0, 0.0));
+        }
+        network.add_branch(Branch::transformer(0, 1, 0.005, 0.05, 0.975));
+        network.add_branch(Branch::line_with_charging(1, 2, 0.02, 0.04, 0.002));
+        network.add_branch(Branch::line(2, 3, 0.03, 0.05));
+        network.add_branch(Branch::line(4, 2, 0.04, 0.06));
+        network.add_load(Load::new(2, 4.0, 1.5));
+        network.add_load(Load::new(3, 6.0, 2.0));
+        network.add_load(Load::new(4, 3.0, 1.0).with_model(LoadModel::Exponential {
+            active: 1.5,
+            reactive: 2.0,
+        }));
+        network
+    }
+
+    #[test]
+    fn test_matches_newton_raphson() {
+        let network = feeder();
+        let mut state = network.initial_state();
+        let result = BackwardForwardSweepSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+
+        let mut reference = network.initial_state();
+        AcPowerFlowSolver::new()
+            .solve_network(&network, &mut reference)
+            .unwrap();
+        for i in 0..network.bus_count() {
+            assert!((state.voltage_magnitude[i] - reference.voltage_magnitude[i]).abs() < 1e-7);
+            assert!((state.voltage_angle[i] - reference.voltage_angle[i]).abs() < 1e-7);
+        }
+        assert!((state.active_power[0] - reference.active_power[0]).abs() < 1e-5);
+        assert!((state.reactive_power[0] - reference.reactive_power[0]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_meshed_network_lists_loop_branches() {
+        let mut network = feeder();
+        network.add_branch(Branch::line(3, 4, 0.01, 0.02));
+        let mut state = network.initial_state();
+        let err = BackwardForwardSweepSolver::new()
+            .solve_network(&network, &mut state)
+            .unwrap_err();
+        assert!(err.to_string().contains("loop branches: 4"));
+    }
+}
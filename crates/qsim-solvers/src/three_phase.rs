@@ -0,0 +1,430 @@
+//! Three-phase unbalanced power flow
+//!
+//! Solves a [`PhaseNetwork`] in phase coordinates with the implicit Z-bus
+//! Gauss method: the source bus holds a balanced voltage set, and the
+//! remaining node voltages are updated from
+//!
+//! ```text
+//! Yuu·Vu = I(V) − Yus·Vs
+//! ```
+//!
+//! where `I(V)` are the constant-power load currents. `Yuu` is factorized
+//! once, so every iteration is a pair of triangular solves.
+//!
+//! Transformer banks use the standard connection matrices (YI, YII, YIII).
+//! Wye sides lead delta sides by 30°. Ungrounded windings get a tiny shunt
+//! to ground so the zero-sequence voltage stays defined.
+
+use nalgebra::{DMatrix, DVector};
+use num_complex::Complex64;
+use qsim_core::{CoreError, Result, SolverResult, StateStore, NOMINAL_PHASE_ANGLES};
+use qsim_elements::{LoadConnection, PhaseLine, PhaseNetwork, PhaseTransformer, WindingConnection};
+
+/// Relative admittance tying ungrounded windings to ground
+const FLOATING_SHUNT: f64 = 1e-6;
+
+/// Three-phase unbalanced power flow solver
+#[derive(Debug, Clone)]
+pub struct ThreePhasePowerFlowSolver {
+    /// Convergence tolerance on the voltage update (per-unit)
+    pub tolerance: f64,
+    /// Maximum number of iterations
+    pub max_iterations: usize,
+}
+
+impl ThreePhasePowerFlowSolver {
+    /// Create a solver with default settings
+    pub fn new() -> Self {
+        Self {
+            tolerance: 1e-8,
+            max_iterations: 100,
+        }
+    }
+}
+
+impl Default for ThreePhasePowerFlowSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Phase-coordinate admittance matrix
+///
+/// Node `3·bus + phase` holds phase `phase` of `bus`; nodes of missing
+/// phases are left empty and excluded by [`PhaseYbus::nodes`].
+#[derive(Debug, Clone)]
+pub struct PhaseYbus {
+    /// Admittance matrix over all 3·n nodes (per-unit)
+    pub matrix: DMatrix<Complex64>,
+    /// Nodes of phases present in the network
+    pub nodes: Vec<usize>,
+}
+
+fn complex_matrix(resistance: &[[f64; 3]; 3], reactance: &[[f64; 3]; 3]) -> DMatrix<Complex64> {
+    DMatrix::from_fn(3, 3, |i, j| {
+        Complex64::new(resistance[i][j], reactance[i][j])
+    })
+}
+
+/// Series and shunt admittances of a line over its own phases
+fn line_admittance(
+    line: &PhaseLine,
+) -> Result<(Vec<usize>, DMatrix<Complex64>, DMatrix<Complex64>)> {
+    let phases: Vec<usize> = (0..3).filter(|&p| line.phases[p]).collect();
+    let z = complex_matrix(&line.resistance, &line.reactance)
+        .select_rows(&phases)
+        .select_columns(&phases);
+    let series = z.try_inverse().ok_or_else(|| {
+        CoreError::TopologyError(format!(
+            "Line {}-{} has a singular impedance matrix",
+            line.from_bus, line.to_bus
+        ))
+    })?;
+    let shunt = DMatrix::from_fn(phases.len(), phases.len(), |i, j| {
+        Complex64::new(0.0, line.susceptance[phases[i]][phases[j]] / 2.0)
+    });
+    Ok((phases, series, shunt))
+}
+
+/// Transformer connection blocks (Ypp, Yps, Ysp, Yss)
+fn transformer_blocks(transformer: &PhaseTransformer) -> [DMatrix<Complex64>; 4] {
+    use WindingConnection::{Yg, D, Y};
+
+    let yt = Complex64::new(transformer.resistance, transformer.reactance).inv();
+    let y_i = DMatrix::<Complex64>::identity(3, 3) * yt;
+    let y_ii =
+        DMatrix::from_fn(3, 3, |i, j| if i == j { 2.0 } else { -1.0 }).map(|v: f64| yt * v / 3.0);
+    let y_iii = DMatrix::from_row_slice(3, 3, &[-1.0, 1.0, 0.0, 0.0, -1.0, 1.0, 1.0, 0.0, -1.0])
+        .map(|v: f64| yt * v / 3.0_f64.sqrt());
+
+    let (ypp, yps, ysp, yss) = match (transformer.from_connection, transformer.to_connection) {
+        (Yg, Yg) => (y_i.clone(), -&y_i, -&y_i, y_i),
+        (Yg, D) => (y_i, y_iii.clone(), y_iii.transpose(), y_ii),
+        (D, Yg) => (y_ii, y_iii.transpose(), y_iii, y_i),
+        (Y, D) => (y_ii.clone(), y_iii.clone(), y_iii.transpose(), y_ii),
+        (D, Y) => (y_ii.clone(), y_iii.transpose(), y_iii, y_ii),
+        _ => (y_ii.clone(), -&y_ii, -&y_ii, y_ii),
+    };
+
+    let tap = Complex64::new(
+        if transformer.tap_ratio == 0.0 {
+            1.0
+        } else {
+            transformer.tap_ratio
+        },
+        0.0,
+    );
+    [ypp / (tap * tap), yps / tap, ysp / tap, yss]
+}
+
+/// Build the phase-coordinate admittance matrix
+pub fn build_phase_ybus(network: &PhaseNetwork) -> Result<PhaseYbus> {
+    let n = network.bus_count();
+    let mut matrix = DMatrix::from_element(3 * n, 3 * n, Complex64::new(0.0, 0.0));
+    let check_bus = |bus: usize| {
+        if bus < n {
+            Ok(())
+        } else {
+            Err(CoreError::InvalidBusId(bus))
+        }
+    };
+
+    for line in network.lines.iter().filter(|l| l.in_service) {
+        check_bus(line.from_bus)?;
+        check_bus(line.to_bus)?;
+        let (phases, series, shunt) = line_admittance(line)?;
+        for &p in &phases {
+            if !network.buses[line.from_bus].phases[p] || !network.buses[line.to_bus].phases[p] {
+                return Err(CoreError::TopologyError(format!(
+                    "Line {}-{} uses phase {} missing at one of its buses",
+                    line.from_bus,
+                    line.to_bus,
+                    ["a", "b", "c"][p]
+                )));
+            }
+        }
+        let (f, t) = (3 * line.from_bus, 3 * line.to_bus);
+        for (i, &pi) in phases.iter().enumerate() {
+            for (j, &pj) in phases.iter().enumerate() {
+                let y = series[(i, j)];
+                matrix[(f + pi, f + pj)] += y + shunt[(i, j)];
+                matrix[(t + pi, t + pj)] += y + shunt[(i, j)];
+                matrix[(f + pi, t + pj)] -= y;
+                matrix[(t + pi, f + pj)] -= y;
+            }
+        }
+    }
+
+    for transformer in network.transformers.iter().filter(|t| t.in_service) {
+        check_bus(transformer.from_bus)?;
+        check_bus(transformer.to_bus)?;
+        let (f, t) = (3 * transformer.from_bus, 3 * transformer.to_bus);
+        let [ypp, yps, ysp, yss] = transformer_blocks(transformer);
+        for i in 0..3 {
+            for j in 0..3 {
+                matrix[(f + i, f + j)] += ypp[(i, j)];
+                matrix[(f + i, t + j)] += yps[(i, j)];
+                matrix[(t + i, f + j)] += ysp[(i, j)];
+                matrix[(t + i, t + j)] += yss[(i, j)];
+            }
+        }
+        let floating = FLOATING_SHUNT
+            * Complex64::new(transformer.resistance, transformer.reactance)
+                .inv()
+                .norm();
+        for (connection, base) in [
+            (transformer.from_connection, f),
+            (transformer.to_connection, t),
+        ] {
+            if connection != WindingConnection::Yg {
+                for p in 0..3 {
+                    matrix[(base + p, base + p)] += floating;
+                }
+            }
+        }
+    }
+
+    let nodes = (0..3 * n)
+        .filter(|&node| network.buses[node / 3].phases[node % 3])
+        .collect();
+    Ok(PhaseYbus { matrix, nodes })
+}
+
+/// Load current injections (per-unit) at every node
+fn load_currents(network: &PhaseNetwork, v: &DVector<Complex64>) -> DVector<Complex64> {
+    let mut current = DVector::from_element(v.len(), Complex64::new(0.0, 0.0));
+    let phase_base = network.base_mva / 3.0;
+
+    for load in network.loads.iter().filter(|l| l.in_service) {
+        let node = 3 * load.bus;
+        for k in 0..3 {
+            let s = Complex64::new(load.active_power[k], load.reactive_power[k]) / phase_base;
+            if s.norm() == 0.0 {
+                continue;
+            }
+            match load.connection {
+                LoadConnection::Wye => {
+                    let vk = v[node + k];
+                    if vk.norm() > 0.0 {
+                        current[node + k] -= (s / vk).conj();
+                    }
+                }
+                LoadConnection::Delta => {
+                    let (p, q) = (node + k, node + (k + 1) % 3);
+                    let vpq = v[p] - v[q];
+                    if vpq.norm() > 0.0 {
+                        let i = (s / vpq).conj();
+                        current[p] -= i;
+                        current[q] += i;
+                    }
+                }
+            }
+        }
+    }
+
+    current
+}
+
+impl ThreePhasePowerFlowSolver {
+    /// Solve a phase-resolved network
+    ///
+    /// `state` must hold one entry per bus. Existing per-phase voltages are
+    /// used as the starting point; otherwise a balanced flat start is used.
+    /// Bus-level magnitudes are the mean over present phases, and injections
+    /// are three-phase totals.
+    pub fn solve_phases(
+        &self,
+        network: &PhaseNetwork,
+        state: &mut StateStore,
+    ) -> Result<SolverResult> {
+        let n = network.bus_count();
+        if state.bus_count() != n {
+            return Err(CoreError::StateError(format!(
+                "State has {} buses, network has {n}",
+                state.bus_count()
+            )));
+        }
+        if network.source_bus >= n {
+            return Err(CoreError::InvalidBusId(network.source_bus));
+        }
+        if !state.has_phases() {
+            *state = StateStore::with_phases(n);
+        }
+
+        let ybus = build_phase_ybus(network)?;
+        let mut v = DVector::from_fn(3 * n, |node, _| {
+            let (bus, p) = (node / 3, node % 3);
+            if network.buses[bus].phases[p] {
+                Complex64::from_polar(
+                    state.phase_voltage_magnitude[bus][p],
+                    state.phase_voltage_angle[bus][p],
+                )
+            } else {
+                Complex64::new(0.0, 0.0)
+            }
+        });
+
+        let (source, unknown): (Vec<usize>, Vec<usize>) = ybus
+            .nodes
+            .iter()
+            .partition(|&&node| node / 3 == network.source_bus);
+        for &node in &source {
+            v[node] = Complex64::from_polar(network.source_voltage, NOMINAL_PHASE_ANGLES[node % 3]);
+        }
+
+        let y_uu = ybus.matrix.select_rows(&unknown).select_columns(&unknown);
+        let y_us = ybus.matrix.select_rows(&unknown).select_columns(&source);
+        let v_s = DVector::from_iterator(source.len(), source.iter().map(|&node| v[node]));
+        let fixed = &y_us * &v_s;
+        let lu = y_uu.lu();
+
+        let mut iterations = 0;
+        let mut change = f64::INFINITY;
+        while iterations < self.max_iterations {
+            iterations += 1;
+            let injections = load_currents(network, &v);
+            let rhs = DVector::from_fn(unknown.len(), |k, _| injections[unknown[k]] - fixed[k]);
+            let v_u = lu.solve(&rhs).ok_or_else(|| {
+                CoreError::SimulationError("Phase admittance matrix is singular".into())
+            })?;
+
+            change = unknown
+                .iter()
+                .enumerate()
+                .map(|(k, &node)| (v_u[k] - v[node]).norm())
+                .fold(0.0, f64::max);
+            for (k, &node) in unknown.iter().enumerate() {
+                v[node] = v_u[k];
+            }
+            if change < self.tolerance {
+                break;
+            }
+        }
+
+        let current = &ybus.matrix * &v;
+        let phase_base = network.base_mva / 3.0;
+        for (bus, phases) in network.buses.iter().map(|b| b.phases).enumerate() {
+            let mut magnitude_sum = 0.0;
+            let mut reference = None;
+            let mut power = Complex64::new(0.0, 0.0);
+            for p in 0..3 {
+                let node = 3 * bus + p;
+                let (magnitude, angle) = if phases[p] {
+                    v[node].to_polar()
+                } else {
+                    (0.0, 0.0)
+                };
+                state.phase_voltage_magnitude[bus][p] = magnitude;
+                state.phase_voltage_angle[bus][p] = angle;
+                if phases[p] {
+                    magnitude_sum += magnitude;
+                    reference.get_or_insert(angle - NOMINAL_PHASE_ANGLES[p]);
+                    power += v[node] * current[node].conj() * phase_base;
+                }
+            }
+            let present = phases.iter().filter(|&&p| p).count().max(1);
+            state.voltage_magnitude[bus] = magnitude_sum / present as f64;
+            state.voltage_angle[bus] = reference.unwrap_or(0.0);
+            state.active_power[bus] = power.re;
+            state.reactive_power[bus] = power.im;
+        }
+
+        if change < self.tolerance {
+            Ok(SolverResult::converged(iterations, change))
+        } else {
+            Ok(SolverResult::failed(iterations, change))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AcPowerFlowSolver, NetworkSolver};
+    use qsim_elements::{Branch, Bus, Load, Network, PhaseBus, PhaseLoad};
+
+    #[test]
+    fn test_balanced_matches_positive_sequence() {
+        let mut network = PhaseNetwork::new();
+        network.add_bus(PhaseBus::new(12.47));
+        network.add_bus(PhaseBus::new(12.47));
+        network.add_line(PhaseLine::from_sequence(0, 1, (0.02, 0.06), (0.06, 0.18)));
+        network.add_load(PhaseLoad::wye(1, [10.0; 3], [4.0; 3]));
+        let mut state = StateStore::new(2);
+        let result = ThreePhasePowerFlowSolver::new()
+            .solve_phases(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+
+        let mut balanced = Network::new();
+        balanced.add_bus(Bus::slack(1.0));
+        balanced.add_bus(Bus::pq(0.0, 0.0));
+        balanced.add_branch(Branch::line(0, 1, 0.02, 0.06));
+        balanced.add_load(Load::new(1, 30.0, 12.0));
+        let mut reference = balanced.initial_state();
+        AcPowerFlowSolver::new()
+            .solve_network(&balanced, &mut reference)
+            .unwrap();
+
+        for (p, nominal) in NOMINAL_PHASE_ANGLES.iter().enumerate() {
+            assert!(
+                (state.phase_voltage_magnitude[1][p] - reference.voltage_magnitude[1]).abs() < 1e-7
+            );
+            let shift = state.phase_voltage_angle[1][p] - nominal;
+            assert!((shift - reference.voltage_angle[1]).abs() < 1e-7);
+        }
+        assert!((state.active_power[0] - reference.active_power[0]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_single_phase_lateral_and_delta_load() {
+        let mut network = PhaseNetwork::new();
+        network.add_bus(PhaseBus::new(12.47));
+        network.add_bus(PhaseBus::new(12.47));
+        network.add_bus(PhaseBus::with_phases([false, true, false], 12.47));
+        network.add_line(PhaseLine::from_sequence(0, 1, (0.01, 0.03), (0.03, 0.09)));
+        network.add_line(PhaseLine::single_phase(1, 2, 1, 0.02, 0.02));
+        network.add_load(PhaseLoad::wye(2, [0.0, 2.0, 0.0], [0.0, 0.5, 0.0]));
+        network.add_load(PhaseLoad::delta(1, [3.0, 0.0, 1.0], [1.0, 0.0, 0.5]));
+
+        let mut state = StateStore::new(3);
+        let result = ThreePhasePowerFlowSolver::new()
+            .solve_phases(&network, &mut state)
+            .unwrap();
+        assert!(result.converged);
+
+        // Missing phases carry no voltage; the loaded phase drops most
+        assert_eq!(state.phase_voltage_magnitude[2][0], 0.0);
+        assert!(state.phase_voltage_magnitude[2][1] < state.phase_voltage_magnitude[1][1]);
+        assert!((state.active_power[2] + 2.0).abs() < 1e-6);
+        assert!((state.active_power[1] + 4.0).abs() < 1e-6);
+
+        // Source supplies the loads plus losses
+        let losses: f64 = state.active_power.iter().sum();
+        assert!(losses > 0.0 && losses < 0.5);
+    }
+
+    #[test]
+    fn test_delta_wye_phase_shift() {
+        let mut network = PhaseNetwork::new();
+        network.add_bus(PhaseBus::new(12.47));
+        network.add_bus(PhaseBus::new(0.48));
+        network.add_transformer(PhaseTransformer::new(
+            0,
+            1,
+            WindingConnection::D,
+            WindingConnection::Yg,
+            0.0,
+            0.05,
+        ));
+
+        let mut state = StateStore::new(2);
+        ThreePhasePowerFlowSolver::new()
+            .solve_phases(&network, &mut state)
+            .unwrap();
+        for p in 0..3 {
+            assert!((state.phase_voltage_magnitude[1][p] - 1.0).abs() < 1e-6);
+        }
+        assert!((state.voltage_angle[1] - 30f64.to_radians()).abs() < 1e-6);
+    }
+}
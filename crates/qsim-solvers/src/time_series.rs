@@ -0,0 +1,224 @@
+//! Quasi-static time-series power flow
+//!
+//! Each step applies the load and generation profiles for that step to the
+//! base network and re-solves the power flow. With warm start, bus voltages
+//! start from the previous converged solution, which usually saves most of
+//! the Newton iterations between neighbouring hours. Drive the stepper with
+//! a [`qsim_core::TimeSeriesRunner`].
+
+use qsim_core::{CoreError, Result, SolverResult, StateStore, TimeStepper};
+use qsim_elements::{Network, Profile};
+
+use crate::NetworkSolver;
+
+/// [`TimeStepper`] that advances profiles and re-solves a power flow
+#[derive(Debug, Clone)]
+pub struct QuasiStaticStepper<S: NetworkSolver> {
+    /// Power flow solver for each step
+    pub solver: S,
+    /// Start each solve from the previous converged voltages
+    pub warm_start: bool,
+    /// Return an error instead of continuing when a step does not converge
+    pub stop_on_failure: bool,
+    base: Network,
+    network: Network,
+    profiles: Vec<Profile>,
+    first_step: usize,
+    results: Vec<SolverResult>,
+}
+
+impl<S: NetworkSolver> QuasiStaticStepper<S> {
+    /// Create a stepper; every profile must target an element of `network`
+    pub fn new(solver: S, network: Network, profiles: Vec<Profile>) -> Result<Self> {
+        if let Some(profile) = profiles.iter().find(|p| !p.is_bound(&network)) {
+            return Err(CoreError::SimulationError(format!(
+                "Profile target {:?} is not in the network",
+                profile.target
+            )));
+        }
+        Ok(Self {
+            solver,
+            warm_start: true,
+            stop_on_failure: false,
+            network: network.clone(),
+            base: network,
+            profiles,
+            first_step: 0,
+            results: Vec::new(),
+        })
+    }
+
+    /// Builder: enable or disable warm start
+    pub fn with_warm_start(mut self, warm_start: bool) -> Self {
+        self.warm_start = warm_start;
+        self
+    }
+
+    /// Builder: resume at profile step `step`, e.g. from a checkpoint
+    ///
+    /// The incoming state is taken as converged for warm start.
+    pub fn with_first_step(mut self, step: usize) -> Self {
+        self.first_step = step;
+        self
+    }
+
+    /// Profile step of the next solve
+    pub fn current_step(&self) -> usize {
+        self.first_step + self.results.len()
+    }
+
+    /// Network with the profile values of the last step applied
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Solver result of every step taken since the first step
+    pub fn results(&self) -> &[SolverResult] {
+        &self.results
+    }
+
+    /// Steps whose power flow did not converge
+    pub fn failed_steps(&self) -> Vec<usize> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| !r.converged)
+            .map(|(i, _)| self.first_step + i)
+            .collect()
+    }
+}
+
+impl<S: NetworkSolver> TimeStepper for QuasiStaticStepper<S> {
+    fn step(&mut self, state: &mut StateStore, _dt: f64) -> Result<()> {
+        let step = self.current_step();
+        let mut network = self.base.clone();
+        for profile in &self.profiles {
+            profile.apply(&self.base, &mut network, step);
+        }
+
+        let mut next = network.initial_state();
+        let previous_converged = self.results.last().is_none_or(|r| r.converged);
+        if self.warm_start && previous_converged && state.bus_count() == next.bus_count() {
+            next.voltage_magnitude.clone_from(&state.voltage_magnitude);
+            next.voltage_angle.clone_from(&state.voltage_angle);
+        }
+
+        let result = self.solver.solve_network(&network, &mut next)?;
+        if !result.converged && self.stop_on_failure {
+            return Err(CoreError::SimulationError(format!(
+                "Power flow did not converge at step {step}"
+            )));
+        }
+        self.results.push(result);
+        self.network = network;
+        *state = next;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AcPowerFlowSolver;
+    use qsim_core::{OutputHandler, TimeSeriesRunner};
+    use qsim_elements::{Branch, Bus, Generator, Load, ProfileTarget};
+
+    #[derive(Default)]
+    struct Recorder {
+        times: Vec<f64>,
+        slack_power: Vec<f64>,
+        completed: bool,
+    }
+
+    impl OutputHandler for Recorder {
+        fn on_step(&mut self, _step: usize, time: f64, state: &StateStore) {
+            self.times.push(time);
+            self.slack_power.push(state.active_power[0]);
+        }
+
+        fn on_complete(&mut self, _state: &StateStore) {
+            self.completed = true;
+        }
+    }
+
+    fn network() -> Network {
+        let mut network = Network::new();
+        network.add_bus(Bus::slack(1.0));
+        network.add_bus(Bus::pv(1.0, 0.0));
+        network.add_bus(Bus::pq(0.0, 0.0));
+        network.add_branch(Branch::line(0, 2, 0.01, 0.1));
+        network.add_branch(Branch::line(1, 2, 0.01, 0.1));
+        network.add_generator(Generator::new(1, 20.0, 1.0));
+        network.add_load(Load::new(2, 50.0, 10.0));
+        network
+    }
+
+    #[test]
+    fn test_profiles_drive_each_step() {
+        let network = network();
+        let profiles = vec![
+            Profile::multiplier(ProfileTarget::Load(0), vec![0.5, 1.0, 1.5, 1.5]),
+            Profile::absolute(ProfileTarget::Generator(0), vec![0.0, 20.0]),
+        ];
+        let mut stepper =
+            QuasiStaticStepper::new(AcPowerFlowSolver::new(), network.clone(), profiles).unwrap();
+        let mut state = network.initial_state();
+        let mut recorder = Recorder::default();
+        TimeSeriesRunner::new(1.0, 4)
+            .with_start_time(10.0)
+            .run(&mut stepper, &mut state, &mut [&mut recorder])
+            .unwrap();
+
+        assert_eq!(recorder.times, vec![10.0, 11.0, 12.0, 13.0]);
+        assert!(recorder.completed);
+        assert!(stepper.failed_steps().is_empty());
+        // Slack covers load minus generation plus losses
+        assert!(recorder.slack_power[0] > 25.0 && recorder.slack_power[0] < 26.0);
+        assert!(recorder.slack_power[1] > 30.0 && recorder.slack_power[1] < 31.0);
+        assert!(recorder.slack_power[2] > 55.0 && recorder.slack_power[2] < 57.0);
+        assert_eq!(stepper.network().loads[0].reactive_power, 15.0);
+        assert_eq!(stepper.network().generators[0].active_power, 20.0);
+
+        // Warm start: an unchanged step needs no Newton iterations
+        assert_eq!(stepper.results()[3].iterations, 0);
+        assert!(stepper.results()[2].iterations > 0);
+    }
+
+    #[test]
+    fn test_resume_matches_uninterrupted_run() {
+        let network = network();
+        let profiles = vec![Profile::multiplier(
+            ProfileTarget::Load(0),
+            vec![0.6, 0.9, 1.2, 0.8, 1.1],
+        )];
+        let stepper = |first| {
+            QuasiStaticStepper::new(AcPowerFlowSolver::new(), network.clone(), profiles.clone())
+                .unwrap()
+                .with_first_step(first)
+        };
+
+        let mut full = network.initial_state();
+        TimeSeriesRunner::new(1.0, 5)
+            .run(&mut stepper(0), &mut full, &mut [])
+            .unwrap();
+
+        let mut resumed = network.initial_state();
+        TimeSeriesRunner::new(1.0, 2)
+            .run(&mut stepper(0), &mut resumed, &mut [])
+            .unwrap();
+        let mut later = stepper(2);
+        TimeSeriesRunner::new(1.0, 5)
+            .with_first_step(2)
+            .run(&mut later, &mut resumed, &mut [])
+            .unwrap();
+
+        assert_eq!(later.current_step(), 5);
+        assert_eq!(resumed, full);
+    }
+
+    #[test]
+    fn test_unbound_profile_is_rejected() {
+        let profiles = vec![Profile::multiplier(ProfileTarget::Load(3), vec![1.0])];
+        assert!(QuasiStaticStepper::new(AcPowerFlowSolver::new(), network(), profiles).is_err());
+    }
+}
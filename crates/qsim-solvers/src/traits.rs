@@ -0,0 +1,25 @@
+//! Solver traits over full networks
+
+use qsim_core::{Result, SolverResult, StateStore};
+use qsim_elements::Network;
+
+use crate::{BranchFlow, ThreeWindingFlow};
+
+/// Power flow solver that works from complete network element data
+///
+/// Unlike [`qsim_core::Solver`], which only sees the topology graph,
+/// implementations have access to branch impedances, generator setpoints
+/// and the other element parameters.
+pub trait NetworkSolver: Send + Sync {
+    /// Solve power flow for `network`, starting from and updating `state`
+    fn solve_network(&self, network: &Network, state: &mut StateStore) -> Result<SolverResult>;
+
+    /// Branch flows consistent with this solver's model of the network
+    fn branch_flows(&self, network: &Network, state: &StateStore) -> Vec<BranchFlow>;
+
+    /// Per-winding flows of the three-winding transformers, mapped back
+    /// from their star branches
+    fn three_winding_flows(&self, network: &Network, state: &StateStore) -> Vec<ThreeWindingFlow> {
+        crate::three_winding_flows(network, state, &self.branch_flows(network, state))
+    }
+}
@@ -0,0 +1,115 @@
+//! Network admittance matrices
+//!
+//! Branches use the standard π-model with an ideal transformer on the
+//! from side:
+//!
+//! ```text
+//! Yff = (ys + j·b/2) / |t|²    Yft = -ys / conj(t)
+//! Ytf = -ys / t                Ytt =  ys + j·b/2
+//! ```
+//!
+//! where `ys = 1/(r + jx)` and `t = tap·e^(j·shift)`.
+
+use nalgebra::{DMatrix, DVector};
+use num_complex::Complex64;
+use qsim_elements::{Branch, Network};
+
+/// Two-port admittances of a single branch
+#[derive(Debug, Clone, Copy)]
+pub struct BranchAdmittance {
+    pub yff: Complex64,
+    pub yft: Complex64,
+    pub ytf: Complex64,
+    pub ytt: Complex64,
+}
+
+/// Effective off-nominal tap ratio (0.0 is treated as 1.0)
+pub fn tap_magnitude(branch: &Branch) -> f64 {
+    if branch.tap_ratio == 0.0 {
+        1.0
+    } else {
+        branch.tap_ratio
+    }
+}
+
+/// π-model admittances of a branch
+pub fn branch_admittance(branch: &Branch) -> BranchAdmittance {
+    let (g, b) = branch.admittance();
+    let ys = Complex64::new(g, b);
+    let charging = Complex64::new(0.0, branch.susceptance / 2.0);
+    let tap = Complex64::from_polar(tap_magnitude(branch), branch.phase_shift);
+
+    let ytt = ys + charging;
+    BranchAdmittance {
+        yff: ytt / tap.norm_sqr(),
+        yft: -ys / tap.conj(),
+        ytf: -ys / tap,
+        ytt,
+    }
+}
+
+/// Build the bus admittance matrix (Ybus) from in-service branches and
+/// bus shunts
+pub fn build_ybus(network: &Network) -> DMatrix<Complex64> {
+    let n = network.bus_count();
+    let mut ybus = DMatrix::from_element(n, n, Complex64::new(0.0, 0.0));
+
+    for branch in network.branches.iter().filter(|b| b.in_service) {
+        let (f, t) = (branch.from_bus, branch.to_bus);
+        let y = branch_admittance(branch);
+        ybus[(f, f)] += y.yff;
+        ybus[(f, t)] += y.yft;
+        ybus[(t, f)] += y.ytf;
+        ybus[(t, t)] += y.ytt;
+    }
+    for (i, (g, b)) in network.shunt_admittance().into_iter().enumerate() {
+        ybus[(i, i)] += Complex64::new(g, b) / network.base_mva;
+    }
+
+    ybus
+}
+
+/// DC series susceptance of a branch (1 / (x·tap)), ignoring resistance
+pub fn dc_susceptance(branch: &Branch) -> f64 {
+    let x = branch.reactance * tap_magnitude(branch);
+    if x != 0.0 {
+        1.0 / x
+    } else {
+        0.0
+    }
+}
+
+/// Build the DC susceptance matrix (Bbus) from in-service branches
+///
+/// B[i][j] = -1/X[i][j] for connected buses
+/// B[i][i] = sum of 1/X for all branches connected to bus i
+pub fn build_bbus(network: &Network) -> DMatrix<f64> {
+    let n = network.bus_count();
+    let mut bbus = DMatrix::zeros(n, n);
+
+    for branch in network.branches.iter().filter(|b| b.in_service) {
+        let (f, t) = (branch.from_bus, branch.to_bus);
+        let b = dc_susceptance(branch);
+        bbus[(f, f)] += b;
+        bbus[(t, t)] += b;
+        bbus[(f, t)] -= b;
+        bbus[(t, f)] -= b;
+    }
+
+    bbus
+}
+
+/// Equivalent bus injections (per-unit) caused by phase shifters in DC flow
+pub fn phase_shift_injections(network: &Network) -> DVector<f64> {
+    let mut injections = DVector::zeros(network.bus_count());
+
+    for branch in network.branches.iter().filter(|b| b.in_service) {
+        if branch.phase_shift != 0.0 {
+            let p = -dc_susceptance(branch) * branch.phase_shift;
+            injections[branch.from_bus] += p;
+            injections[branch.to_bus] -= p;
+        }
+    }
+
+    injections
+}
@@ -52,10 +52,12 @@ pub mod prelude {
     };
 
     // Elements
-    pub use qsim_elements::{Branch, Bus, BusType, Generator, Load};
+    pub use qsim_elements::{Branch, Bus, BusType, Generator, Load, Network};
 
     // Solvers
-    pub use qsim_solvers::DcPowerFlowSolver;
+    pub use qsim_solvers::{
+        AcPowerFlowSolver, ContingencyAnalysis, DcPowerFlowSolver, NetworkSolver,
+    };
 
     // I/O
     pub use qsim_io::NetworkData;